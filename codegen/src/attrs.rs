@@ -42,12 +42,31 @@ pub fn parse_attr_items(args: ParseStream) -> syn::Result<ExportInfo> {
             items: Vec::new(),
         });
     }
-    let arg_list = args
-        .call(syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_separated_nonempty)?;
+    let arg_list =
+        syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_separated_nonempty_with(
+            args,
+            parse_attr_item,
+        )?;
 
     parse_punctuated_items(arg_list)
 }
 
+/// Parse a single attribute item, allowing the `const` keyword as a flag.
+fn parse_attr_item(input: ParseStream) -> syn::Result<syn::Expr> {
+    if input.peek(syn::Token![const]) {
+        let token: syn::Token![const] = input.parse()?;
+        let ident = proc_macro2::Ident::new("const", token.span);
+
+        Ok(syn::Expr::Path(syn::ExprPath {
+            attrs: Vec::new(),
+            qself: None,
+            path: ident.into(),
+        }))
+    } else {
+        input.parse()
+    }
+}
+
 pub fn parse_punctuated_items(
     arg_list: syn::punctuated::Punctuated<syn::Expr, syn::Token![,]>,
 ) -> syn::Result<ExportInfo> {
@@ -109,7 +128,12 @@ pub(crate) fn outer_item_attributes<T: ExportedParams>(
         return Ok(T::no_attrs());
     }
 
-    let parser = syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_separated_nonempty;
+    let parser = |input: ParseStream| {
+        syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_separated_nonempty_with(
+            input,
+            parse_attr_item,
+        )
+    };
     let arg_list = parser.parse2(args)?;
 
     let export_info = parse_punctuated_items(arg_list)?;
@@ -0,0 +1,230 @@
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+};
+
+use crate::attrs::{AttrItem, ExportInfo, ExportedParams};
+
+/// Name of the helper attribute of `#[derive(CustomType)]`.
+const ATTR_NAME: &str = "rhai";
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub(crate) struct CustomTypeParams {
+    pub name: Option<String>,
+}
+
+impl Parse for CustomTypeParams {
+    fn parse(args: ParseStream) -> syn::Result<Self> {
+        if args.is_empty() {
+            return Ok(CustomTypeParams::default());
+        }
+
+        let info = crate::attrs::parse_attr_items(args)?;
+
+        Self::from_info(info)
+    }
+}
+
+impl ExportedParams for CustomTypeParams {
+    fn parse_stream(args: ParseStream) -> syn::Result<Self> {
+        Self::parse(args)
+    }
+
+    fn no_attrs() -> Self {
+        Default::default()
+    }
+
+    fn from_info(info: ExportInfo) -> syn::Result<Self> {
+        let ExportInfo { items: attrs, .. } = info;
+        let mut name = None;
+        for attr in attrs {
+            let AttrItem { key, value, .. } = attr;
+            match (key.to_string().as_ref(), value) {
+                ("name", Some(_)) if name.is_some() => {
+                    return Err(syn::Error::new(key.span(), "conflicting name"))
+                }
+                ("name", Some(s)) => name = Some(s.value()),
+                ("name", None) => return Err(syn::Error::new(key.span(), "requires value")),
+                (attr, _) => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown attribute '{}'", attr),
+                    ))
+                }
+            }
+        }
+
+        Ok(CustomTypeParams { name })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub(crate) struct CustomTypeFieldParams {
+    pub name: Option<String>,
+    pub skip: bool,
+    pub readonly: bool,
+}
+
+impl Parse for CustomTypeFieldParams {
+    fn parse(args: ParseStream) -> syn::Result<Self> {
+        if args.is_empty() {
+            return Ok(CustomTypeFieldParams::default());
+        }
+
+        let info = crate::attrs::parse_attr_items(args)?;
+
+        Self::from_info(info)
+    }
+}
+
+impl ExportedParams for CustomTypeFieldParams {
+    fn parse_stream(args: ParseStream) -> syn::Result<Self> {
+        Self::parse(args)
+    }
+
+    fn no_attrs() -> Self {
+        Default::default()
+    }
+
+    fn from_info(info: ExportInfo) -> syn::Result<Self> {
+        let ExportInfo { items: attrs, .. } = info;
+        let mut name = None;
+        let mut skip = false;
+        let mut readonly = false;
+        for attr in attrs {
+            let AttrItem { key, value, .. } = attr;
+            match (key.to_string().as_ref(), value) {
+                ("name", Some(_)) if name.is_some() => {
+                    return Err(syn::Error::new(key.span(), "conflicting name"))
+                }
+                ("name", Some(s)) => name = Some(s.value()),
+                ("name", None) => return Err(syn::Error::new(key.span(), "requires value")),
+
+                ("skip", None) => skip = true,
+                ("skip", Some(s)) => return Err(syn::Error::new(s.span(), "extraneous value")),
+
+                ("readonly", None) => readonly = true,
+                ("readonly", Some(s)) => return Err(syn::Error::new(s.span(), "extraneous value")),
+                (attr, _) => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown attribute '{}'", attr),
+                    ))
+                }
+            }
+        }
+
+        if skip && (readonly || name.is_some()) {
+            return Err(syn::Error::new(
+                info.item_span,
+                "a skipped field cannot have other attributes",
+            ));
+        }
+
+        Ok(CustomTypeFieldParams {
+            name,
+            skip,
+            readonly,
+        })
+    }
+}
+
+/// Find and parse the `#[rhai(...)]` helper attribute, if any.
+fn helper_attribute<T: ExportedParams>(attrs: &[syn::Attribute]) -> syn::Result<T> {
+    let mut found = attrs
+        .iter()
+        .filter(|a| a.path.get_ident().map(|i| *i == ATTR_NAME).unwrap_or(false));
+
+    match (found.next(), found.next()) {
+        (Some(_), Some(duplicated)) => Err(syn::Error::new(
+            duplicated.span(),
+            "duplicated 'rhai' attribute",
+        )),
+        (Some(attr), None) => attr.parse_args_with(T::parse_stream),
+        (None, _) => Ok(T::no_attrs()),
+    }
+}
+
+pub(crate) fn derive_custom_type(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let type_name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generic types cannot derive CustomType",
+        ));
+    }
+
+    let params: CustomTypeParams = helper_attribute(&input.attrs)?;
+    let name = params.name.unwrap_or_else(|| type_name.to_string());
+
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct { ref fields, .. }) => fields,
+        syn::Data::Enum(syn::DataEnum { enum_token, .. }) => {
+            return Err(syn::Error::new(
+                enum_token.span(),
+                "only structs can derive CustomType",
+            ))
+        }
+        syn::Data::Union(syn::DataUnion { union_token, .. }) => {
+            return Err(syn::Error::new(
+                union_token.span(),
+                "only structs can derive CustomType",
+            ))
+        }
+    };
+
+    let mut accessors = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let field_params: CustomTypeFieldParams = helper_attribute(&field.attrs)?;
+
+        // Only public fields are exposed
+        if field_params.skip || !matches!(field.vis, syn::Visibility::Public(_)) {
+            continue;
+        }
+
+        // Tuple fields are named 'field0', 'field1' etc.
+        let (member, default_name) = match field.ident {
+            Some(ref ident) => (quote! { #ident }, ident.to_string()),
+            None => {
+                let index = syn::Index::from(i);
+                (quote! { #index }, format!("field{}", i))
+            }
+        };
+        let prop_name = syn::LitStr::new(&field_params.name.unwrap_or(default_name), field.span());
+        let field_type = &field.ty;
+
+        let get_fn = quote_spanned! { field_type.span()=>
+            |obj: &mut #type_name| obj.#member.clone()
+        };
+
+        accessors.push(if field_params.readonly {
+            quote! {
+                builder.with_get(#prop_name, #get_fn);
+            }
+        } else {
+            let set_fn = quote_spanned! { field_type.span()=>
+                |obj: &mut #type_name, value: #field_type| obj.#member = value
+            };
+            quote! {
+                builder.with_get_set(#prop_name, #get_fn, #set_fn);
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl rhai::CustomType for #type_name {
+            fn build(mut builder: rhai::TypeBuilder<Self>) {
+                #[allow(unused_imports)]
+                use rhai::plugin::custom_type::*;
+
+                builder.with_name(#name);
+                #(#accessors)*
+                (&Probe::<#type_name>::new()).build_debug(&mut builder);
+                (&Probe::<#type_name>::new()).build_eq(&mut builder);
+            }
+        }
+    })
+}
@@ -0,0 +1,251 @@
+use quote::{quote, ToTokens};
+use syn::{parse::Parse, parse::ParseStream, spanned::Spanned};
+
+use crate::attrs::ExportScope;
+use crate::custom_type::CustomTypeParams;
+use crate::function::ExportedFn;
+use crate::rhai_module::ExportedConst;
+
+#[derive(Debug)]
+pub(crate) struct ExportedEnum {
+    item_enum: syn::ItemEnum,
+    params: CustomTypeParams,
+}
+
+impl ExportedEnum {
+    pub fn set_params(&mut self, params: CustomTypeParams) -> syn::Result<()> {
+        self.params = params;
+        Ok(())
+    }
+}
+
+impl Parse for ExportedEnum {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let item_enum: syn::ItemEnum = input.parse()?;
+
+        if !item_enum.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                item_enum.generics.span(),
+                "generic enums cannot be exported to Rhai",
+            ));
+        }
+
+        if item_enum.variants.is_empty() {
+            return Err(syn::Error::new(
+                item_enum.ident.span(),
+                "enums without variants cannot be exported to Rhai",
+            ));
+        }
+
+        // #[cfg] attributes are not allowed on variants due to what is generated for them
+        for variant in item_enum.variants.iter() {
+            crate::attrs::deny_cfg_attr(&variant.attrs)?;
+        }
+
+        Ok(ExportedEnum {
+            item_enum,
+            params: CustomTypeParams::default(),
+        })
+    }
+}
+
+/// Convert a variant name in `CamelCase` into `snake_case`.
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map(|c| c.is_lowercase()).unwrap_or(false);
+
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                result.push('_');
+            }
+        }
+        result.extend(ch.to_lowercase());
+    }
+
+    result
+}
+
+/// Name of the property for a field of a variant.
+///
+/// Fields of tuple variants are named `field0`, `field1` etc.
+fn field_name(field: &syn::Field, index: usize) -> String {
+    match field.ident {
+        Some(ref ident) => ident.to_string(),
+        None => format!("field{}", index),
+    }
+}
+
+impl ExportedEnum {
+    pub fn generate(self) -> proc_macro2::TokenStream {
+        // Keep the original enum on errors to avoid spurious errors in code using it
+        let item_enum = self.item_enum.clone();
+
+        match self.generate_inner() {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                let err = e.to_compile_error();
+                quote! {
+                    #item_enum
+                    #err
+                }
+            }
+        }
+    }
+
+    fn generate_inner(self) -> Result<proc_macro2::TokenStream, syn::Error> {
+        let ExportedEnum { item_enum, params } = self;
+
+        let type_name = &item_enum.ident;
+        let name = params.name.unwrap_or_else(|| type_name.to_string());
+
+        let mut variant_names = Vec::new();
+        let mut is_fns = Vec::new();
+        let mut constructors = Vec::new();
+        let mut consts: Vec<ExportedConst> = Vec::new();
+        let mut const_defs = Vec::new();
+        // Field getters in order of first appearance, with the match arms for each variant.
+        let mut getters: Vec<(String, Vec<proc_macro2::TokenStream>)> = Vec::new();
+
+        for variant in item_enum.variants.iter() {
+            let ident = &variant.ident;
+            let variant_name = ident.to_string();
+
+            let pattern = match variant.fields {
+                syn::Fields::Named(_) => quote! { #type_name::#ident { .. } },
+                syn::Fields::Unnamed(_) => quote! { #type_name::#ident(..) },
+                syn::Fields::Unit => quote! { #type_name::#ident },
+            };
+
+            variant_names.push(quote! { #pattern => #variant_name });
+
+            let is_fn = syn::LitStr::new(
+                &format!("is_{}", to_snake_case(&variant_name)),
+                ident.span(),
+            );
+            is_fns.push(quote! {
+                builder.with_fn(#is_fn, |value: &mut #type_name| matches!(value, #pattern));
+            });
+
+            if let syn::Fields::Unit = variant.fields {
+                // Unit variants are constants
+                const_defs.push(quote! {
+                    #[allow(non_upper_case_globals)]
+                    pub const #ident: #type_name = #type_name::#ident;
+                });
+                consts.push((
+                    variant_name,
+                    Box::new(syn::parse2(type_name.to_token_stream())?),
+                    syn::parse2(quote! { #type_name::#ident })?,
+                ));
+                continue;
+            }
+
+            // Other variants are constructed by functions taking all the fields
+            let args: Vec<_> = (0..variant.fields.len())
+                .map(|i| syn::Ident::new(&format!("field{}", i), ident.span()))
+                .collect();
+            let arg_types = variant.fields.iter().map(|f| &f.ty);
+
+            let value = match variant.fields {
+                syn::Fields::Named(_) => {
+                    let names = variant.fields.iter().map(|f| &f.ident);
+                    quote! { #type_name::#ident { #(#names: #args),* } }
+                }
+                _ => quote! { #type_name::#ident(#(#args),*) },
+            };
+
+            constructors.push(quote! {
+                #[allow(non_snake_case)]
+                pub fn #ident(#(#args: #arg_types),*) -> #type_name {
+                    #value
+                }
+            });
+
+            // Field getters
+            for (i, field) in variant.fields.iter().enumerate() {
+                let prop_name = field_name(field, i);
+
+                let arm = match variant.fields {
+                    syn::Fields::Named(_) => {
+                        let field_ident = &field.ident;
+                        quote! {
+                            #type_name::#ident { #field_ident: value, .. } => rhai::Dynamic::from(value.clone())
+                        }
+                    }
+                    _ => {
+                        let skipped = (0..i).map(|_| quote! { _ });
+                        quote! {
+                            #type_name::#ident(#(#skipped,)* value, ..) => rhai::Dynamic::from(value.clone())
+                        }
+                    }
+                };
+
+                match getters.iter_mut().find(|(n, _)| *n == prop_name) {
+                    Some((_, arms)) => arms.push(arm),
+                    None => getters.push((prop_name, vec![arm])),
+                }
+            }
+        }
+
+        let getters = getters.into_iter().map(|(prop_name, arms)| {
+            quote! {
+                builder.with_get(#prop_name, |value: &mut #type_name| -> rhai::Dynamic {
+                    #[allow(unreachable_patterns)]
+                    match value {
+                        #(#arms,)*
+                        _ => rhai::Dynamic::UNIT,
+                    }
+                });
+            }
+        });
+
+        // Parse constructors as exported functions
+        let mut fns = constructors
+            .iter()
+            .map(|f| syn::parse2::<ExportedFn>(f.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mod_name = crate::register::generated_enum_module_name(type_name);
+        let mod_gen =
+            crate::rhai_module::generate_body(&mut fns, &consts, &mut [], &ExportScope::default());
+
+        Ok(quote! {
+            #item_enum
+
+            impl rhai::CustomType for #type_name {
+                fn build(mut builder: rhai::TypeBuilder<Self>) {
+                    #[allow(unused_imports)]
+                    use rhai::plugin::custom_type::*;
+
+                    let enum_type = |value: &mut #type_name| -> rhai::ImmutableString {
+                        match value {
+                            #(#variant_names,)*
+                        }
+                        .into()
+                    };
+
+                    builder
+                        .with_name(#name)
+                        .with_fn("enum_type", enum_type)
+                        .with_get("enum_type", enum_type);
+                    #(#is_fns)*
+                    #(#getters)*
+                    (&Probe::<#type_name>::new()).build_debug(&mut builder);
+                    (&Probe::<#type_name>::new()).build_eq(&mut builder);
+                }
+            }
+
+            #[allow(non_snake_case)]
+            pub mod #mod_name {
+                use super::*;
+                #(#const_defs)*
+                #(#constructors)*
+                #mod_gen
+            }
+        })
+    }
+}
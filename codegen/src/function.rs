@@ -106,6 +106,8 @@ pub(crate) fn print_type(ty: &syn::Type) -> String {
 pub(crate) struct ExportedFnParams {
     pub name: Vec<String>,
     pub return_raw: bool,
+    pub pure: bool,
+    pub constant: bool,
     pub skip: bool,
     pub special: FnSpecialAccess,
     pub namespace: FnNamespaceAccess,
@@ -144,6 +146,8 @@ impl ExportedParams for ExportedFnParams {
         } = info;
         let mut name = Vec::new();
         let mut return_raw = false;
+        let mut pure = false;
+        let mut constant = false;
         let mut skip = false;
         let mut namespace = FnNamespaceAccess::Unset;
         let mut special = FnSpecialAccess::None;
@@ -228,6 +232,10 @@ impl ExportedParams for ExportedFnParams {
                 ("return_raw", Some(s)) => {
                     return Err(syn::Error::new(s.span(), "extraneous value"))
                 }
+                ("pure", None) => pure = true,
+                ("pure", Some(s)) => return Err(syn::Error::new(s.span(), "extraneous value")),
+                ("const", None) => constant = true,
+                ("const", Some(s)) => return Err(syn::Error::new(s.span(), "extraneous value")),
                 ("skip", None) => skip = true,
                 ("skip", Some(s)) => return Err(syn::Error::new(s.span(), "extraneous value")),
                 ("global", Some(s)) | ("internal", Some(s)) => {
@@ -255,6 +263,8 @@ impl ExportedParams for ExportedFnParams {
         Ok(ExportedFnParams {
             name,
             return_raw,
+            pure,
+            constant,
             skip,
             special,
             namespace,
@@ -272,6 +282,7 @@ pub(crate) struct ExportedFn {
     pass_context: bool,
     return_dynamic: bool,
     mut_receiver: bool,
+    self_type: Option<syn::Type>,
     params: ExportedFnParams,
 }
 
@@ -407,12 +418,119 @@ impl Parse for ExportedFn {
             pass_context,
             return_dynamic,
             mut_receiver,
+            self_type: None,
             params: Default::default(),
         })
     }
 }
 
+/// Replace all occurrences of `Self` in a token stream with a concrete type.
+fn replace_self_type(
+    tokens: proc_macro2::TokenStream,
+    self_type: &syn::Type,
+) -> proc_macro2::TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            proc_macro2::TokenTree::Ident(ref ident) if ident == "Self" => {
+                self_type.to_token_stream()
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut new_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_self_type(group.stream(), self_type),
+                );
+                new_group.set_span(group.span());
+                proc_macro2::TokenTree::Group(new_group).into()
+            }
+            token => token.into(),
+        })
+        .collect()
+}
+
 impl ExportedFn {
+    /// Parse a method inside an `impl` block of `self_type`.
+    ///
+    /// A `&mut self` or `&self` receiver turns into a first parameter `this: &mut Type`,
+    /// and `Self` is replaced by the concrete type, so the method can be treated as a function.
+    pub(crate) fn from_impl_method(
+        method: &syn::ImplItemMethod,
+        self_type: &syn::Type,
+    ) -> syn::Result<Self> {
+        let sig = &method.sig;
+
+        // Methods are called with arguments from Rhai only.
+        if let Some(ref token) = sig.asyncness {
+            return Err(syn::Error::new(
+                token.span(),
+                "async methods cannot be exported to Rhai",
+            ));
+        }
+        if !sig.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                sig.generics.span(),
+                "generic methods cannot be exported to Rhai",
+            ));
+        }
+        if let Some(ref variadic) = sig.variadic {
+            return Err(syn::Error::new(
+                variadic.span(),
+                "variadic methods cannot be exported to Rhai",
+            ));
+        }
+
+        let mut inputs = syn::punctuated::Punctuated::<syn::FnArg, syn::Token![,]>::new();
+
+        for arg in sig.inputs.iter() {
+            let arg = match arg {
+                // &mut self or &self
+                syn::FnArg::Receiver(syn::Receiver {
+                    reference: Some(_),
+                    ..
+                }) => syn::parse2::<syn::FnArg>(quote! { this: &mut #self_type }).unwrap(),
+                // self
+                syn::FnArg::Receiver(receiver) => {
+                    return Err(syn::Error::new(
+                        receiver.span(),
+                        "methods taking 'self' by value cannot be exported to Rhai, use '&mut self' instead",
+                    ))
+                }
+                // self: Type
+                syn::FnArg::Typed(syn::PatType { ref pat, .. })
+                    if matches!(pat.as_ref(), syn::Pat::Ident(syn::PatIdent { ref ident, .. }) if ident == "self") =>
+                {
+                    return Err(syn::Error::new(
+                        arg.span(),
+                        "methods with a typed 'self' parameter cannot be exported to Rhai, use '&mut self' instead",
+                    ))
+                }
+                syn::FnArg::Typed(_) => syn::parse2::<syn::FnArg>(replace_self_type(
+                    arg.to_token_stream(),
+                    self_type,
+                ))?,
+            };
+            inputs.push(arg);
+        }
+
+        let mut signature = sig.clone();
+        signature.inputs = inputs;
+        signature.output = syn::parse2::<syn::ReturnType>(replace_self_type(
+            sig.output.to_token_stream(),
+            self_type,
+        ))?;
+
+        let fn_all = syn::ItemFn {
+            attrs: method.attrs.clone(),
+            vis: method.vis.clone(),
+            sig: signature,
+            block: Box::new(syn::parse2::<syn::Block>(quote! { {} }).unwrap()),
+        };
+
+        let mut function = syn::parse2::<ExportedFn>(fn_all.to_token_stream())?;
+        function.self_type = Some(self_type.clone());
+        Ok(function)
+    }
+
     pub(crate) fn params(&self) -> &ExportedFnParams {
         &self.params
     }
@@ -515,6 +633,22 @@ impl ExportedFn {
             ));
         }
 
+        // 1a. Functions evaluated at compile time must have no side effects
+        //     and must not depend on the calling context.
+        //
+        if params.constant && !params.pure {
+            return Err(syn::Error::new(
+                self.signature.span(),
+                "functions marked with 'const' must also be marked 'pure'",
+            ));
+        }
+        if params.constant && self.pass_context {
+            return Err(syn::Error::new(
+                self.signature.span(),
+                "functions taking a NativeCallContext cannot be marked 'const'",
+            ));
+        }
+
         match params.special {
             // 2a. Property getters must take only the subject as an argument.
             FnSpecialAccess::Property(Property::Get(_)) if self.arg_count() != 1 => {
@@ -707,7 +841,13 @@ impl ExportedFn {
     }
 
     pub fn generate_impl(&self, on_type_name: &str) -> proc_macro2::TokenStream {
-        let sig_name = self.name().clone();
+        let sig_name = match self.self_type {
+            Some(ref self_type) => {
+                let name = self.name();
+                quote! { <#self_type>::#name }
+            }
+            None => self.name().to_token_stream(),
+        };
         let name = self
             .params
             .name
@@ -0,0 +1,217 @@
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+};
+
+use crate::attrs::{AttrItem, ExportInfo, ExportScope, ExportedParams};
+use crate::function::{ExportedFn, ExportedFnParams};
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub(crate) struct ExportedImplParams {
+    pub name: Option<syn::Ident>,
+    pub scope: ExportScope,
+}
+
+impl Parse for ExportedImplParams {
+    fn parse(args: ParseStream) -> syn::Result<Self> {
+        if args.is_empty() {
+            return Ok(ExportedImplParams::default());
+        }
+
+        let info = crate::attrs::parse_attr_items(args)?;
+
+        Self::from_info(info)
+    }
+}
+
+impl ExportedParams for ExportedImplParams {
+    fn parse_stream(args: ParseStream) -> syn::Result<Self> {
+        Self::parse(args)
+    }
+
+    fn no_attrs() -> Self {
+        Default::default()
+    }
+
+    fn from_info(info: ExportInfo) -> syn::Result<Self> {
+        let ExportInfo { items: attrs, .. } = info;
+        let mut name = None;
+        let mut scope = None;
+        for attr in attrs {
+            let AttrItem { key, value, .. } = attr;
+            match (key.to_string().as_ref(), value) {
+                ("name", Some(_)) if name.is_some() => {
+                    return Err(syn::Error::new(key.span(), "duplicate name"));
+                }
+                ("name", Some(s)) => match syn::parse_str::<syn::Ident>(&s.value()) {
+                    Ok(ident) => name = Some(syn::Ident::new(&ident.to_string(), s.span())),
+                    Err(_) => return Err(syn::Error::new(s.span(), "name must be an identifier")),
+                },
+                ("name", None) => return Err(syn::Error::new(key.span(), "requires value")),
+                ("export_prefix", Some(_)) | ("export_all", None) if scope.is_some() => {
+                    return Err(syn::Error::new(key.span(), "duplicate export scope"));
+                }
+                ("export_prefix", Some(s)) => scope = Some(ExportScope::Prefix(s.value())),
+                ("export_prefix", None) => {
+                    return Err(syn::Error::new(key.span(), "requires value"))
+                }
+                ("export_all", None) => scope = Some(ExportScope::All),
+                ("export_all", Some(s)) => {
+                    return Err(syn::Error::new(s.span(), "extraneous value"))
+                }
+                (attr, _) => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown attribute '{}'", attr),
+                    ))
+                }
+            }
+        }
+
+        Ok(ExportedImplParams {
+            name,
+            scope: scope.unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportedImpl {
+    impl_all: syn::ItemImpl,
+    type_name: syn::Ident,
+    methods: Vec<(syn::ImplItemMethod, ExportedFnParams)>,
+    params: ExportedImplParams,
+}
+
+impl ExportedImpl {
+    pub fn set_params(&mut self, params: ExportedImplParams) -> syn::Result<()> {
+        self.params = params;
+        Ok(())
+    }
+}
+
+impl Parse for ExportedImpl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut impl_all: syn::ItemImpl = input.parse()?;
+
+        // Only inherent impl blocks of concrete types are supported
+        if let Some((_, ref trait_path, _)) = impl_all.trait_ {
+            return Err(syn::Error::new(
+                trait_path.span(),
+                "trait implementations cannot be exported to Rhai",
+            ));
+        }
+        if !impl_all.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                impl_all.generics.span(),
+                "generic impl blocks cannot be exported to Rhai",
+            ));
+        }
+
+        let type_name = match crate::function::flatten_type_groups(impl_all.self_ty.as_ref()) {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => {
+                path.segments.last().unwrap().ident.clone()
+            }
+            ty => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "only impl blocks of named types can be exported to Rhai",
+                ))
+            }
+        };
+
+        // #[cfg] attributes are not allowed on the impl block due to what is generated for it
+        crate::attrs::deny_cfg_attr(&impl_all.attrs)?;
+
+        // Gather methods and their parameters.
+        //
+        // Methods are only parsed during generation, when the export scope is known, so that
+        // methods not exported are not checked for unsupported signatures.
+        let methods = impl_all
+            .items
+            .iter_mut()
+            .filter_map(|item| match item {
+                syn::ImplItem::Method(m) => Some(m),
+                _ => None,
+            })
+            .try_fold(Vec::new(), |mut vec, method| {
+                // #[cfg] attributes are not allowed on methods
+                crate::attrs::deny_cfg_attr(&method.attrs)?;
+
+                let params: ExportedFnParams =
+                    crate::attrs::inner_item_attributes(&mut method.attrs, "rhai_fn")?;
+                vec.push((method.clone(), params));
+                Ok::<_, syn::Error>(vec)
+            })?;
+
+        Ok(ExportedImpl {
+            impl_all,
+            type_name,
+            methods,
+            params: ExportedImplParams::default(),
+        })
+    }
+}
+
+impl ExportedImpl {
+    pub fn generate(self) -> proc_macro2::TokenStream {
+        // Keep the original impl block on errors to avoid spurious errors in code using it
+        let impl_all = self.impl_all.clone();
+
+        match self.generate_inner() {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                let err = e.to_compile_error();
+                quote! {
+                    #impl_all
+                    #err
+                }
+            }
+        }
+    }
+
+    fn generate_inner(self) -> Result<proc_macro2::TokenStream, syn::Error> {
+        let ExportedImpl {
+            impl_all,
+            type_name,
+            methods,
+            params,
+        } = self;
+
+        let self_type = impl_all.self_ty.as_ref();
+
+        // Parse exported methods.
+        let mut fns = Vec::new();
+        for (method, fn_params) in methods {
+            let keep = match (fn_params.skip, &params.scope) {
+                (true, _) => false,
+                (_, ExportScope::PubOnly) => matches!(method.vis, syn::Visibility::Public(_)),
+                (_, ExportScope::Prefix(s)) => method.sig.ident.to_string().starts_with(s),
+                (_, ExportScope::All) => true,
+            };
+            if keep {
+                let mut f = ExportedFn::from_impl_method(&method, self_type)?;
+                f.set_params(fn_params)?;
+                fns.push(f);
+            }
+        }
+
+        // Check for collisions if the "name" attribute was used on methods.
+        crate::rhai_module::check_rename_collisions(&fns)?;
+
+        let mod_name =
+            crate::register::generated_impl_module_name(&type_name, params.name.as_ref());
+        let mod_gen = crate::rhai_module::generate_body(&mut fns, &[], &mut [], &params.scope);
+
+        Ok(quote! {
+            #impl_all
+
+            #[allow(non_snake_case)]
+            pub mod #mod_name {
+                use super::*;
+                #mod_gen
+            }
+        })
+    }
+}
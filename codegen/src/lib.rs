@@ -91,7 +91,10 @@ use quote::quote;
 use syn::parse_macro_input;
 
 mod attrs;
+mod custom_type;
+mod enum_type;
 mod function;
+mod impl_block;
 mod module;
 mod register;
 mod rhai_module;
@@ -128,11 +131,20 @@ pub fn export_fn(
 ) -> proc_macro::TokenStream {
     let mut output = proc_macro2::TokenStream::from(input.clone());
 
-    let parsed_params = match crate::attrs::outer_item_attributes(args.into(), "export_fn") {
-        Ok(args) => args,
-        Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
-    };
+    let parsed_params: function::ExportedFnParams =
+        match crate::attrs::outer_item_attributes(args.into(), "export_fn") {
+            Ok(args) => args,
+            Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
+        };
     let mut function_def = parse_macro_input!(input as function::ExportedFn);
+    if parsed_params.constant {
+        return syn::Error::new(
+            function_def.name().span(),
+            "'const' is only supported on functions inside a plugin module",
+        )
+        .to_compile_error()
+        .into();
+    }
     if let Err(e) = function_def.set_params(parsed_params) {
         return e.to_compile_error().into();
     }
@@ -184,6 +196,313 @@ pub fn export_module(
     proc_macro::TokenStream::from(tokens)
 }
 
+/// Attribute, when put on an `impl` block of a custom type, exposes its methods to Rhai.
+///
+/// Methods taking `&mut self` (or `&self`) are registered as methods of the type, while associated
+/// functions (e.g. constructors) are registered as normal functions.  Use
+/// `#[rhai_fn(get = "...")]` and `#[rhai_fn(set = "...")]` on methods to register property
+/// getters and setters, and the other `#[rhai_fn]` attributes as in a _plugin module_.
+///
+/// Only public methods are exposed, unless `export_all` or `export_prefix = "..."` is specified.
+/// Methods taking `self` by value, generic methods and `async` methods cannot be exposed;
+/// mark them with `#[rhai_fn(skip)]`.
+///
+/// Use [`exported_impl!`][exported_impl] to generate a Rhai `Module` holding the methods.
+///
+/// The generated code is placed in a module named after the type, so only one `#[export_impl]`
+/// block is allowed per type in the same Rust module.  To export several `impl` blocks of a type,
+/// give each additional block a distinct name with `#[export_impl(name = "...")]`, and pass the
+/// same name to [`exported_impl!`][exported_impl].
+///
+/// # Usage
+///
+/// ```
+/// # use rhai::{Engine, EvalAltResult, INT};
+/// use rhai::plugin::*;
+///
+/// #[derive(Debug, Clone)]
+/// pub struct Counter {
+///     count: INT,
+/// }
+///
+/// #[export_impl]
+/// impl Counter {
+///     pub fn new_counter() -> Self {
+///         Self { count: 0 }
+///     }
+///     pub fn increment(&mut self, by: INT) {
+///         self.count += by;
+///     }
+///     #[rhai_fn(get = "count")]
+///     pub fn count(&mut self) -> INT {
+///         self.count
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Box<EvalAltResult>> {
+/// let mut engine = Engine::new();
+///
+/// engine.register_global_module(exported_impl!(Counter).into());
+///
+/// assert_eq!(engine.eval::<INT>("let c = new_counter(); c.increment(42); c.count")?, 42);
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn export_impl(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let parsed_params = match crate::attrs::outer_item_attributes(args.into(), "export_impl") {
+        Ok(args) => args,
+        Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
+    };
+    // Keep the original impl block on errors to avoid spurious errors in code using it
+    let mut impl_def = match syn::parse::<impl_block::ExportedImpl>(input.clone()) {
+        Ok(impl_def) => impl_def,
+        Err(err) => {
+            let mut output = proc_macro2::TokenStream::from(input);
+            output.extend(err.to_compile_error());
+            return proc_macro::TokenStream::from(output);
+        }
+    };
+    if let Err(e) = impl_def.set_params(parsed_params) {
+        return e.to_compile_error().into();
+    }
+
+    let tokens = impl_def.generate();
+    proc_macro::TokenStream::from(tokens)
+}
+
+/// Macro to generate a Rhai `Module` from an `impl` block defined via [`#[export_impl]`][export_impl].
+///
+/// The argument is the path of the type, which must be the same module as the `impl` block,
+/// followed by the name of the block if it is given one via `#[export_impl(name = "...")]`
+/// (e.g. `exported_impl!(Counter, "extra")`).
+///
+/// # Usage
+///
+/// ```
+/// # use rhai::{Engine, EvalAltResult, Scope, INT};
+/// use rhai::plugin::*;
+///
+/// #[derive(Debug, Clone)]
+/// pub struct Counter(INT);
+///
+/// #[export_impl]
+/// impl Counter {
+///     #[rhai_fn(get = "value")]
+///     pub fn value(&mut self) -> INT {
+///         self.0
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Box<EvalAltResult>> {
+/// let mut engine = Engine::new();
+///
+/// engine.register_global_module(exported_impl!(Counter).into());
+///
+/// let mut scope = Scope::new();
+/// scope.push("c", Counter(42));
+///
+/// assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "c.value")?, 42);
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro]
+pub fn exported_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let crate::register::ExportedImplInput { type_path, name } =
+        parse_macro_input!(input as crate::register::ExportedImplInput);
+    let gen_mod_path = crate::register::generated_impl_module_path(&type_path, name.as_ref());
+    let tokens = quote::quote! {
+        #gen_mod_path::rhai_module_generate()
+    };
+    proc_macro::TokenStream::from(tokens)
+}
+
+/// Attribute, when put on a Rust `enum`, exposes its variants to Rhai.
+///
+/// The `CustomType` trait is implemented for the enum, so `Engine::build_type` registers:
+///
+/// * the type name for the `type_of` function (set via `#[export_enum(name = "...")]`, defaults
+///   to the enum name),
+///
+/// * an `enum_type` function and property returning the name of the variant,
+///
+/// * an `is_<variant>` function for each variant (with the variant name in `snake_case`),
+///
+/// * a property getter for each field, named after the field (fields of tuple variants are named
+///   `field0`, `field1` etc.), returning `()` for variants without the field,
+///
+/// * `to_string`, `to_debug`, `print` and `debug` if the enum implements `Debug`, and `==` and `!=`
+///   if it implements `PartialEq`.
+///
+/// Variant constructors are generated into a _plugin module_ (see [`exported_enum!`]): unit
+/// variants are constants and other variants are functions taking all their fields in order.
+///
+/// A `switch` statement on a value of the enum matches the variant names.
+///
+/// # Usage
+///
+/// ```
+/// # use rhai::{Engine, EvalAltResult, INT};
+/// use rhai::plugin::*;
+///
+/// #[export_enum]
+/// #[derive(Debug, Clone, PartialEq)]
+/// pub enum Shape {
+///     Empty,
+///     Square(INT),
+///     Rect { width: INT, height: INT },
+/// }
+///
+/// # fn main() -> Result<(), Box<EvalAltResult>> {
+/// let mut engine = Engine::new();
+///
+/// engine
+///     .build_type::<Shape>()
+///     .register_static_module("Shape", exported_enum!(Shape).into());
+///
+/// let result = engine.eval::<INT>(
+///     r#"
+///         let shape = Shape::Rect(6, 7);
+///
+///         switch shape {
+///             "Empty" => 0,
+///             "Square" => shape.field0 * shape.field0,
+///             "Rect" => shape.width * shape.height
+///         }
+///     "#,
+/// )?;
+///
+/// assert_eq!(result, 42);
+/// assert!(engine.eval::<bool>("Shape::Empty.is_empty()")?);
+/// assert_eq!(engine.eval::<String>("type_of(Shape::Square(1))")?, "Shape");
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn export_enum(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let parsed_params = match crate::attrs::outer_item_attributes(args.into(), "export_enum") {
+        Ok(args) => args,
+        Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
+    };
+    // Keep the original enum on errors to avoid spurious errors in code using it
+    let mut enum_def = match syn::parse::<enum_type::ExportedEnum>(input.clone()) {
+        Ok(enum_def) => enum_def,
+        Err(err) => {
+            let mut output = proc_macro2::TokenStream::from(input);
+            output.extend(err.to_compile_error());
+            return proc_macro::TokenStream::from(output);
+        }
+    };
+    if let Err(e) = enum_def.set_params(parsed_params) {
+        return e.to_compile_error().into();
+    }
+
+    let tokens = enum_def.generate();
+    proc_macro::TokenStream::from(tokens)
+}
+
+/// Macro to generate a Rhai `Module` holding the variant constructors of an `enum` defined via
+/// [`#[export_enum]`][export_enum].
+///
+/// # Usage
+///
+/// ```
+/// # use rhai::{Engine, EvalAltResult};
+/// use rhai::plugin::*;
+///
+/// #[export_enum]
+/// #[derive(Clone)]
+/// pub enum Light {
+///     Red,
+///     Green,
+/// }
+///
+/// # fn main() -> Result<(), Box<EvalAltResult>> {
+/// let mut engine = Engine::new();
+///
+/// engine
+///     .build_type::<Light>()
+///     .register_static_module("Light", exported_enum!(Light).into());
+///
+/// assert!(engine.eval::<bool>("Light::Green.is_green()")?);
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro]
+pub fn exported_enum(type_path: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let type_path = parse_macro_input!(type_path as syn::Path);
+    let gen_mod_path = crate::register::generated_enum_module_path(&type_path);
+    let tokens = quote::quote! {
+        #gen_mod_path::rhai_module_generate()
+    };
+    proc_macro::TokenStream::from(tokens)
+}
+
+/// Macro to derive the `CustomType` trait for a struct, building its API for use with an `Engine`
+/// via `Engine::build_type`.
+///
+/// The type is registered with its name for the `type_of` function, and each public field gets
+/// a property getter and setter.  `to_string`, `to_debug`, `print` and `debug` are registered
+/// if the type implements `Debug`, and `==` and `!=` if it implements `PartialEq`.
+///
+/// Fields of tuple structs are named `field0`, `field1` etc.
+///
+/// # Attributes
+///
+/// * `#[rhai(name = "...")]` on the struct sets the type name (defaults to the struct name).
+///
+/// * `#[rhai(name = "...")]` on a field sets the property name (defaults to the field name).
+///
+/// * `#[rhai(readonly)]` on a field registers only a getter.
+///
+/// * `#[rhai(skip)]` on a field does not expose it.
+///
+/// # Usage
+///
+/// ```
+/// # use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+/// use rhai::CustomType;
+///
+/// #[derive(Debug, Clone, PartialEq, CustomType)]
+/// #[rhai(name = "Point")]
+/// pub struct MyPoint {
+///     pub x: INT,
+///     #[rhai(readonly)]
+///     pub y: INT,
+///     #[rhai(skip)]
+///     pub tag: INT,
+/// }
+///
+/// # fn main() -> Result<(), Box<EvalAltResult>> {
+/// let mut engine = Engine::new();
+///
+/// engine
+///     .build_type::<MyPoint>()
+///     .register_fn("new_point", |x: INT, y: INT| MyPoint { x, y, tag: 0 });
+///
+/// assert_eq!(engine.eval::<INT>("let p = new_point(1, 2); p.x = 40; p.x + p.y")?, 42);
+/// assert_eq!(engine.eval::<String>("type_of(new_point(1, 2))")?, "Point");
+/// assert!(engine.eval::<bool>("new_point(1, 2) == new_point(1, 2)")?);
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_derive(CustomType, attributes(rhai))]
+pub fn derive_custom_type(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    let tokens = match custom_type::derive_custom_type(input) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    };
+    proc_macro::TokenStream::from(tokens)
+}
+
 /// Macro to generate a Rhai `Module` from a _plugin module_ defined via [`#[export_module]`][export_module].
 ///
 /// # Usage
@@ -305,7 +624,7 @@ pub fn register_exported_fn(args: proc_macro::TokenStream) -> proc_macro::TokenS
     proc_macro::TokenStream::from(tokens)
 }
 
-/// Macro to register a _plugin function_ into a Rhai `Module`.
+/// Macro to register a _plugin function_ into a Rhai `Module`, returning its hash key.
 ///
 /// # Usage
 ///
@@ -342,7 +661,7 @@ pub fn set_exported_fn(args: proc_macro::TokenStream) -> proc_macro::TokenStream
         #module_expr.set_fn(#export_name, FnNamespace::Internal, FnAccess::Public,
                             Some(#gen_mod_path::token_input_names().as_ref()),
                             #gen_mod_path::token_input_types().as_ref(),
-                            #gen_mod_path::token_callable());
+                            #gen_mod_path::token_callable())
     };
     proc_macro::TokenStream::from(tokens)
 }
@@ -384,7 +703,7 @@ pub fn set_exported_global_fn(args: proc_macro::TokenStream) -> proc_macro::Toke
         #module_expr.set_fn(#export_name, FnNamespace::Global, FnAccess::Public,
                             Some(#gen_mod_path::token_input_names().as_ref()),
                             #gen_mod_path::token_input_types().as_ref(),
-                            #gen_mod_path::token_callable());
+                            #gen_mod_path::token_callable())
     };
     proc_macro::TokenStream::from(tokens)
 }
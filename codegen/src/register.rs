@@ -48,3 +48,76 @@ pub fn parse_register_macro(
     let module = items.remove(0);
     Ok((module, export_name, rust_modpath))
 }
+
+pub(crate) fn generated_impl_module_name(
+    type_name: &syn::Ident,
+    name: Option<&syn::Ident>,
+) -> syn::Ident {
+    match name {
+        Some(name) => syn::Ident::new(
+            &format!("rhai_impl_{}_{}", type_name, name),
+            type_name.span(),
+        ),
+        None => syn::Ident::new(&format!("rhai_impl_{}", type_name), type_name.span()),
+    }
+}
+
+pub(crate) fn generated_impl_module_path(
+    type_path: &syn::Path,
+    name: Option<&syn::Ident>,
+) -> syn::punctuated::Punctuated<syn::PathSegment, syn::Token![::]> {
+    let mut g = type_path.clone().segments;
+    let last = g.pop().unwrap().into_value();
+    g.push_value(syn::PathSegment {
+        ident: generated_impl_module_name(&last.ident, name),
+        arguments: syn::PathArguments::None,
+    });
+    g
+}
+
+/// Arguments of `exported_impl!`: the path of the type, optionally followed by the name given to
+/// its `#[export_impl(name = "...")]` block.
+pub(crate) struct ExportedImplInput {
+    pub type_path: syn::Path,
+    pub name: Option<syn::Ident>,
+}
+
+impl syn::parse::Parse for ExportedImplInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let type_path = input.parse()?;
+
+        if input.is_empty() {
+            return Ok(Self {
+                type_path,
+                name: None,
+            });
+        }
+
+        input.parse::<syn::Token![,]>()?;
+        let name: syn::LitStr = input.parse()?;
+
+        match syn::parse_str::<syn::Ident>(&name.value()) {
+            Ok(ident) => Ok(Self {
+                type_path,
+                name: Some(syn::Ident::new(&ident.to_string(), name.span())),
+            }),
+            Err(_) => Err(syn::Error::new(name.span(), "name must be an identifier")),
+        }
+    }
+}
+
+pub(crate) fn generated_enum_module_name(type_name: &syn::Ident) -> syn::Ident {
+    syn::Ident::new(&format!("rhai_enum_{}", type_name), type_name.span())
+}
+
+pub(crate) fn generated_enum_module_path(
+    type_path: &syn::Path,
+) -> syn::punctuated::Punctuated<syn::PathSegment, syn::Token![::]> {
+    let mut g = type_path.clone().segments;
+    let last = g.pop().unwrap().into_value();
+    g.push_value(syn::PathSegment {
+        ident: generated_enum_module_name(&last.ident),
+        arguments: syn::PathArguments::None,
+    });
+    g
+}
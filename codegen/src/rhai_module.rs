@@ -169,14 +169,23 @@ pub(crate) fn generate_body(
                 },
                 fn_literal.span(),
             );
-            set_fn_stmts.push(
-                syn::parse2::<syn::Stmt>(quote! {
+            let set_fn_stmt = if function.params().constant {
+                quote! {
+                    {
+                        let hash = m.set_fn(#fn_literal, FnNamespace::#ns_str, FnAccess::Public,
+                                    Some(&[#(#fn_input_names,)* #return_type]), &[#(#fn_input_types),*],
+                                    #fn_token_name().into());
+                        m.update_fn_const(hash, true);
+                    }
+                }
+            } else {
+                quote! {
                     m.set_fn(#fn_literal, FnNamespace::#ns_str, FnAccess::Public,
                                 Some(&[#(#fn_input_names,)* #return_type]), &[#(#fn_input_types),*],
                                 #fn_token_name().into());
-                })
-                .unwrap(),
-            );
+                }
+            };
+            set_fn_stmts.push(syn::parse2::<syn::Stmt>(set_fn_stmt).unwrap());
         }
 
         gen_fn_tokens.push(quote! {
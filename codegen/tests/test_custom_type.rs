@@ -0,0 +1,79 @@
+use rhai::{CustomType, Engine, EvalAltResult, RegisterFn, INT};
+
+#[derive(Debug, Clone, PartialEq, CustomType)]
+#[rhai(name = "Point")]
+pub struct MyPoint {
+    pub x: INT,
+    #[rhai(readonly)]
+    pub y: INT,
+    #[rhai(name = "label")]
+    pub tag: String,
+    #[rhai(skip)]
+    pub hidden: INT,
+    private: INT,
+}
+
+#[derive(Clone, CustomType)]
+pub struct Pair(pub INT, pub INT);
+
+fn make_point(x: INT, y: INT) -> MyPoint {
+    MyPoint {
+        x,
+        y,
+        tag: "hello".into(),
+        hidden: 0,
+        private: x + y,
+    }
+}
+
+#[test]
+fn test_derive_custom_type() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine
+        .build_type::<MyPoint>()
+        .register_fn("point", make_point);
+
+    assert_eq!(engine.eval::<String>("type_of(point(1, 2))")?, "Point");
+    assert!(engine.eval::<bool>("point(1, 2) == point(1, 2)")?);
+    assert!(engine.eval::<bool>("point(1, 2) != point(2, 1)")?);
+    assert_eq!(
+        engine.eval::<String>("point(1, 2).to_debug()")?,
+        format!("{:?}", make_point(1, 2))
+    );
+
+    assert_eq!(
+        engine.eval::<INT>("let p = point(1, 2); p.x = 40; p.x + p.y")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<String>("let p = point(1, 2); p.label += \" world\"; p.label")?,
+        "hello world"
+    );
+    assert!(engine
+        .eval::<INT>("let p = point(1, 2); p.y = 0; p.y")
+        .is_err());
+    assert!(engine.eval::<INT>("point(1, 2).hidden").is_err());
+    assert!(engine.eval::<INT>("point(1, 2).tag").is_err());
+    assert!(engine.eval::<INT>("point(1, 2).private").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_derive_custom_type_tuple() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine
+        .build_type::<Pair>()
+        .register_fn("pair", |a: INT, b: INT| Pair(a, b));
+
+    assert_eq!(engine.eval::<String>("type_of(pair(1, 2))")?, "Pair");
+    assert_eq!(
+        engine.eval::<INT>("let p = pair(1, 2); p.field1 = 41; p.field0 + p.field1")?,
+        42
+    );
+    // Neither Debug nor PartialEq is implemented
+    assert!(!engine.eval::<bool>("pair(1, 2) == pair(1, 2)")?);
+    assert!(engine.eval::<String>("pair(1, 2).to_debug()").is_err());
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use rhai::{Engine, EvalAltResult, Scope, INT};
+
+pub mod shapes {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_enum]
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Shape {
+        Empty,
+        Square(INT),
+        Rect { width: INT, height: INT },
+        Labeled(String, INT),
+    }
+
+    #[export_enum(name = "Light")]
+    #[derive(Clone)]
+    pub enum TrafficLight {
+        Red,
+        Amber,
+        Green,
+        HTTPGreenArrow,
+    }
+}
+
+use shapes::{Shape, TrafficLight};
+
+fn make_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .build_type::<Shape>()
+        .register_static_module("Shape", rhai::exported_enum!(shapes::Shape).into())
+        .build_type::<TrafficLight>()
+        .register_static_module("Light", rhai::exported_enum!(shapes::TrafficLight).into());
+    engine
+}
+
+#[test]
+fn test_enum_constructors() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine();
+
+    assert_eq!(engine.eval::<Shape>("Shape::Empty")?, Shape::Empty);
+    assert_eq!(engine.eval::<Shape>("Shape::Square(2)")?, Shape::Square(2));
+    assert_eq!(
+        engine.eval::<Shape>("Shape::Rect(6, 7)")?,
+        Shape::Rect {
+            width: 6,
+            height: 7
+        }
+    );
+    assert_eq!(
+        engine.eval::<Shape>(r#"Shape::Labeled("x", 1)"#)?,
+        Shape::Labeled("x".into(), 1)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_enum_inspect() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine();
+
+    assert_eq!(engine.eval::<String>("type_of(Shape::Empty)")?, "Shape");
+    assert_eq!(engine.eval::<String>("type_of(Light::Red)")?, "Light");
+    assert_eq!(
+        engine.eval::<String>("Shape::Rect(1, 2).enum_type")?,
+        "Rect"
+    );
+    assert_eq!(engine.eval::<String>("enum_type(Light::Amber)")?, "Amber");
+
+    assert!(engine.eval::<bool>("Shape::Square(1).is_square()")?);
+    assert!(!engine.eval::<bool>("Shape::Square(1).is_rect()")?);
+    assert!(engine.eval::<bool>("Light::HTTPGreenArrow.is_http_green_arrow()")?);
+
+    assert_eq!(engine.eval::<INT>("Shape::Square(5).field0")?, 5);
+    assert_eq!(engine.eval::<INT>("Shape::Rect(6, 7).height")?, 7);
+    assert_eq!(engine.eval::<INT>(r#"Shape::Labeled("x", 9).field1"#)?, 9);
+    assert_eq!(engine.eval::<()>("Shape::Empty.field0")?, ());
+    assert!(engine.eval::<()>("Shape::Empty.field0 = 1").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_enum_equality() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine();
+
+    assert!(engine.eval::<bool>("Shape::Square(1) == Shape::Square(1)")?);
+    assert!(engine.eval::<bool>("Shape::Square(1) != Shape::Square(2)")?);
+    assert!(!engine.eval::<bool>("Shape::Empty == Shape::Square(1)")?);
+    assert_eq!(
+        engine.eval::<String>("Shape::Rect(1, 2).to_string()")?,
+        format!(
+            "{:?}",
+            Shape::Rect {
+                width: 1,
+                height: 2
+            }
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_enum_switch() -> Result<(), Box<EvalAltResult>> {
+    let engine = make_engine();
+    let mut scope = Scope::new();
+
+    let script = r#"
+        switch shape {
+            "Empty" => 0,
+            "Square" => shape.field0 * shape.field0,
+            "Rect" => shape.width * shape.height,
+            _ => 100
+        }
+    "#;
+
+    let ast = engine.compile(script)?;
+
+    for (shape, expected) in vec![
+        (Shape::Empty, 0),
+        (Shape::Square(3), 9),
+        (
+            Shape::Rect {
+                width: 6,
+                height: 7,
+            },
+            42,
+        ),
+        (Shape::Labeled("x".into(), 1), 100),
+    ] {
+        scope.set_value("shape", shape);
+        assert_eq!(
+            engine.eval_ast_with_scope::<INT>(&mut scope, &ast)?,
+            expected
+        );
+    }
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let light = Light::Green;
+                switch light { "Red" => 1, "Amber" => 2, "Green" => 3 }
+            "#
+        )?,
+        3
+    );
+
+    Ok(())
+}
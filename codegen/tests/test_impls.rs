@@ -0,0 +1,196 @@
+use rhai::{Engine, EvalAltResult, Scope, INT};
+
+pub mod counter {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[derive(Debug, Clone)]
+    pub struct Counter {
+        pub count: INT,
+        pub step: INT,
+    }
+
+    #[export_impl]
+    impl Counter {
+        pub fn new_counter(step: INT) -> Self {
+            Self { count: 0, step }
+        }
+        pub fn tick(&mut self) {
+            self.count += self.step;
+        }
+        pub fn add(&mut self, other: Self) -> Self {
+            Self {
+                count: self.count + other.count,
+                step: self.step,
+            }
+        }
+        pub fn is_above(&self, value: INT) -> bool {
+            self.count > value
+        }
+        #[rhai_fn(get = "count", pure)]
+        pub fn get_count(&mut self) -> INT {
+            self.count
+        }
+        #[rhai_fn(set = "count")]
+        pub fn set_count(&mut self, value: INT) {
+            self.count = value;
+        }
+        #[rhai_fn(name = "reset", return_raw)]
+        pub fn checked_reset(&mut self, value: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+            if value < 0 {
+                Err("negative count".into())
+            } else {
+                self.count = value;
+                Ok(Dynamic::UNIT)
+            }
+        }
+        #[rhai_fn(skip)]
+        pub fn into_count(self) -> INT {
+            self.private_helper()
+        }
+        fn private_helper(self) -> INT {
+            self.count
+        }
+    }
+}
+
+#[test]
+fn impl_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.register_global_module(rhai::exported_impl!(counter::Counter).into());
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let c = new_counter(2);
+                c.tick();
+                c.tick();
+                c.count
+            "
+        )?,
+        4
+    );
+    assert!(engine.eval::<bool>("let c = new_counter(5); c.tick(); c.is_above(4)")?);
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let c = new_counter(1);
+                c.count = 40;
+                let d = new_counter(2);
+                d.count = 2;
+                c.add(d).count
+            "
+        )?,
+        42
+    );
+
+    let mut scope = Scope::new();
+    scope.push("c", counter::Counter::new_counter(1));
+
+    engine.consume_with_scope(&mut scope, "c.reset(41); c.tick();")?;
+    assert_eq!(scope.get_value::<counter::Counter>("c").unwrap().count, 42);
+
+    assert!(matches!(
+        *engine
+            .consume_with_scope(&mut scope, "c.reset(-1)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .consume("let c = new_counter(1); into_count(c)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .consume("let c = new_counter(1); private_helper(c)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+pub mod prefixed {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[derive(Debug, Clone)]
+    pub struct Value(pub INT);
+
+    #[export_impl(export_prefix = "rhai_")]
+    impl Value {
+        pub fn rhai_double(&mut self) -> INT {
+            self.0 * 2
+        }
+        pub fn triple(&mut self) -> INT {
+            self.0 * 3
+        }
+    }
+}
+
+#[test]
+fn impl_prefix_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.register_global_module(rhai::exported_impl!(prefixed::Value).into());
+
+    let mut scope = Scope::new();
+    scope.push("v", prefixed::Value(21));
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "v.rhai_double()")?,
+        42
+    );
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<INT>(&mut scope, "v.triple()")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+pub mod split {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[derive(Debug, Clone)]
+    pub struct Point {
+        pub x: INT,
+        pub y: INT,
+    }
+
+    #[export_impl]
+    impl Point {
+        pub fn new_point(x: INT, y: INT) -> Self {
+            Self { x, y }
+        }
+        #[rhai_fn(get = "x")]
+        pub fn get_x(&mut self) -> INT {
+            self.x
+        }
+    }
+
+    #[export_impl(name = "ops")]
+    impl Point {
+        pub fn sum(&mut self) -> INT {
+            self.x + self.y
+        }
+    }
+}
+
+#[test]
+fn impl_named_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.register_global_module(rhai::exported_impl!(split::Point).into());
+    engine.register_global_module(rhai::exported_impl!(split::Point, "ops").into());
+
+    assert_eq!(
+        engine.eval::<INT>("let p = new_point(40, 2); p.x + p.sum() - 40")?,
+        42
+    );
+
+    Ok(())
+}
@@ -417,3 +417,37 @@ fn export_all_test() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+pub mod const_fn_module {
+    use rhai::plugin::*;
+    use rhai::INT;
+
+    #[export_module]
+    pub mod math {
+        #[rhai_fn(pure, const)]
+        pub fn triple(x: INT) -> INT {
+            x * 3
+        }
+        pub fn quadruple(x: INT) -> INT {
+            x * 4
+        }
+    }
+}
+
+#[test]
+fn const_fn_module_test() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let m = rhai::exported_module!(crate::const_fn_module::math);
+    engine.register_global_module(m.into());
+    engine.set_optimization_level(rhai::OptimizationLevel::Full);
+
+    let ast = engine.compile("triple(14)")?;
+    assert!(format!("{:?}", ast).contains("IntegerConstant(42,"));
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+
+    let ast = engine.compile("quadruple(14)")?;
+    assert!(format!("{:?}", ast).contains("FnCall"));
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 56);
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use rhai::CustomType;
+
+#[derive(Clone, CustomType)]
+pub struct Point {
+    #[rhai(skip, readonly)]
+    pub x: i64,
+    #[rhai(hidden)]
+    pub y: i64,
+}
+
+fn main() {}
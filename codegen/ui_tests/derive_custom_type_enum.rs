@@ -0,0 +1,9 @@
+use rhai::CustomType;
+
+#[derive(Clone, CustomType)]
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use rhai::CustomType;
+
+#[derive(Clone, CustomType)]
+pub struct Wrapper<T: Clone> {
+    pub value: T,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use rhai::plugin::*;
+
+#[export_enum]
+#[derive(Clone)]
+pub enum Never {}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use rhai::plugin::*;
+
+#[export_enum]
+#[derive(Clone)]
+pub enum Value<T: Clone> {
+    Nothing,
+    Something(T),
+}
+
+fn main() {}
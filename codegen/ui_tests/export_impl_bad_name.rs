@@ -0,0 +1,19 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_impl(name = "two words")]
+impl Point {
+    pub fn get_x(&mut self) -> f32 {
+        self.x
+    }
+}
+
+fn main() {
+    let n = Point { x: 0.0, y: 0.0 };
+    println!("{}", n.x);
+}
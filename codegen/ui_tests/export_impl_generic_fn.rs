@@ -0,0 +1,21 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_impl]
+impl Point {
+    pub fn scale<T: Into<f32>>(&mut self, factor: T) {
+        let factor = factor.into();
+        self.x *= factor;
+        self.y *= factor;
+    }
+}
+
+fn main() {
+    let mut n = Point { x: 0.0, y: 10.0 };
+    n.scale(2.0);
+}
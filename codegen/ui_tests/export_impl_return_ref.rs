@@ -0,0 +1,19 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_impl]
+impl Point {
+    pub fn x_mut(&mut self) -> &mut f32 {
+        &mut self.x
+    }
+}
+
+fn main() {
+    let mut n = Point { x: 0.0, y: 10.0 };
+    *n.x_mut() = 1.0;
+}
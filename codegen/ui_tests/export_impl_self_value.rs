@@ -0,0 +1,19 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_impl]
+impl Point {
+    pub fn into_x(self) -> f32 {
+        self.x
+    }
+}
+
+fn main() {
+    let n = Point { x: 0.0, y: 10.0 };
+    println!("{}", n.into_x());
+}
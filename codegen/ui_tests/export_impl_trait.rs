@@ -0,0 +1,19 @@
+use rhai::plugin::*;
+
+#[derive(Clone)]
+pub struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[export_impl]
+impl Default for Point {
+    fn default() -> Self {
+        Point { x: 0.0, y: 0.0 }
+    }
+}
+
+fn main() {
+    let n = Point::default();
+    println!("{}", n.x);
+}
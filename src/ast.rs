@@ -1,16 +1,18 @@
 //! Module defining the AST (abstract syntax tree).
 
+use crate::ast_cache::{CacheError, Reader, Writer};
 use crate::dynamic::{AccessMode, Union};
 use crate::fn_native::shared_make_mut;
 use crate::module::NamespaceRef;
 use crate::stdlib::{
     borrow::Cow,
     boxed::Box,
-    fmt,
+    fmt, format,
     hash::Hash,
+    iter::once,
     num::{NonZeroU64, NonZeroUsize},
     ops::{Add, AddAssign},
-    string::String,
+    string::{String, ToString},
     vec,
     vec::Vec,
 };
@@ -72,17 +74,39 @@ pub struct ScriptFnDef {
     /// Encapsulated imported modules.
     #[cfg(not(feature = "no_module"))]
     pub mods: crate::engine::Imports,
+    /// Encapsulated module variables (if any), visible to the function body.
+    #[cfg(not(feature = "no_module"))]
+    pub vars: Option<Shared<StaticVec<(ImmutableString, Dynamic)>>>,
     /// Function name.
     pub name: ImmutableString,
     /// Function access mode.
     pub access: FnAccess,
     /// Names of function parameters.
     pub params: StaticVec<ImmutableString>,
+    /// Type names of function parameters annotated with a type (e.g. `x: int`), or [`None`] for
+    /// parameters without one.
+    ///
+    /// Empty if no parameter has a type annotation.
+    pub param_types: StaticVec<Option<ImmutableString>>,
+    /// Other overloads with the same name and number of parameters, but different parameter types.
+    ///
+    /// The overload to call is selected based on the types of the arguments.
+    pub overloads: Vec<ScriptFnDef>,
     /// Access to external variables.
     #[cfg(not(feature = "no_closure"))]
     pub externals: Vec<ImmutableString>,
     /// Function doc-comments (if any).
     pub comments: Vec<String>,
+    /// Class constructed by the function, if it is the constructor of a script-defined class.
+    #[cfg(not(feature = "no_object"))]
+    pub class: Option<Shared<ClassDef>>,
+    /// Function body compiled into bytecode, on first call.
+    #[cfg(feature = "bytecode")]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) bytecode: crate::bytecode::BytecodeCache,
+    /// Does the function need the global constants at runtime?  Computed on first use.
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) needs_globals: GlobalsFlag,
 }
 
 impl fmt::Display for ScriptFnDef {
@@ -99,13 +123,105 @@ impl fmt::Display for ScriptFnDef {
             self.name,
             self.params
                 .iter()
-                .map(|s| s.as_str())
+                .enumerate()
+                .map(|(i, p)| match self.param_type(i) {
+                    Some(typ) => format!("{}: {}", p, typ),
+                    None => p.to_string(),
+                })
                 .collect::<Vec<_>>()
                 .join(", ")
         )
     }
 }
 
+impl ScriptFnDef {
+    /// Get the type name a parameter is annotated with, if any.
+    #[inline(always)]
+    pub fn param_type(&self, index: usize) -> Option<&str> {
+        self.param_types
+            .get(index)
+            .and_then(|t| t.as_ref())
+            .map(|t| t.as_str())
+    }
+    /// Does the function have any parameter type annotation or other overloads?
+    #[inline(always)]
+    pub fn is_overloaded(&self) -> bool {
+        !self.param_types.is_empty() || !self.overloads.is_empty()
+    }
+    /// Iterate through this function followed by all its other overloads.
+    #[inline(always)]
+    pub fn iter_overloads(&self) -> impl Iterator<Item = &ScriptFnDef> {
+        once(self).chain(self.overloads.iter())
+    }
+    /// Does the function, any of its other overloads, or the class it constructs need the global
+    /// constants at runtime?
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) fn needs_global_constants(&self) -> bool {
+        self.needs_globals.get_or_compute(|| {
+            let mut needed = false;
+            let on_node = &mut |path: &[ASTNode]| needed |= needs_global_constants(path);
+
+            self.body.walk(&mut Default::default(), on_node);
+
+            #[cfg(not(feature = "no_object"))]
+            if let Some(ref class) = self.class {
+                class
+                    .fields
+                    .iter()
+                    .for_each(|(_, expr)| expr.walk(&mut Default::default(), on_node));
+            }
+
+            needed || self.overloads.iter().any(|f| f.needs_global_constants()) || {
+                #[cfg(not(feature = "no_object"))]
+                {
+                    self.class.as_ref().map_or(false, |class| {
+                        class.methods.values().any(|f| f.needs_global_constants())
+                    })
+                }
+                #[cfg(feature = "no_object")]
+                {
+                    false
+                }
+            }
+        })
+    }
+    /// Do the parameter types of this function match those of another overload?
+    #[inline(always)]
+    pub(crate) fn has_same_param_types(&self, other: &ScriptFnDef) -> bool {
+        (0..self.params.len()).all(|i| self.param_type(i) == other.param_type(i))
+    }
+}
+
+/// _(INTERNALS)_ A type containing information on a script-defined class.
+/// Exported under the `internals` feature only.
+///
+/// Not available under `no_object`.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[cfg(not(feature = "no_object"))]
+#[derive(Debug, Clone)]
+pub struct ClassDef {
+    /// Class name.
+    pub name: ImmutableString,
+    /// Names of fields, with the expressions for their default values.
+    pub fields: StaticVec<(ImmutableString, Expr)>,
+    /// Methods shared by all instances of the class, indexed by their hashes.
+    pub methods:
+        crate::stdlib::collections::HashMap<NonZeroU64, ScriptFnDef, StraightHasherBuilder>,
+}
+
+#[cfg(not(feature = "no_object"))]
+impl ClassDef {
+    /// Get a method of the class by name and number of parameters.
+    #[inline(always)]
+    pub fn get_method(&self, name: &str, num_params: usize) -> Option<&ScriptFnDef> {
+        crate::calc_script_fn_hash(crate::stdlib::iter::empty(), name, num_params)
+            .and_then(|h| self.methods.get(&h))
+    }
+}
+
 /// A type containing the metadata of a script-defined function.
 ///
 /// Created by [`AST::iter_functions`].
@@ -126,6 +242,9 @@ pub struct ScriptFnMetadata<'a> {
     pub name: &'a str,
     /// Function parameters (if any).
     pub params: Vec<&'a str>,
+    /// Type names of function parameters annotated with a type, or [`None`] for parameters
+    /// without one.
+    pub param_types: Vec<Option<&'a str>>,
 }
 
 impl fmt::Display for ScriptFnMetadata<'_> {
@@ -140,7 +259,15 @@ impl fmt::Display for ScriptFnMetadata<'_> {
                 ""
             },
             self.name,
-            self.params.iter().cloned().collect::<Vec<_>>().join(", ")
+            self.params
+                .iter()
+                .zip(self.param_types.iter())
+                .map(|(p, t)| match t {
+                    Some(typ) => format!("{}: {}", p, typ),
+                    None => p.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
         )
     }
 }
@@ -153,6 +280,7 @@ impl<'a> Into<ScriptFnMetadata<'a>> for &'a ScriptFnDef {
             access: self.access,
             name: &self.name,
             params: self.params.iter().map(|s| s.as_str()).collect(),
+            param_types: (0..self.params.len()).map(|i| self.param_type(i)).collect(),
         }
     }
 }
@@ -173,6 +301,69 @@ pub struct AST {
     /// Embedded module resolver, if any.
     #[cfg(not(feature = "no_module"))]
     resolver: Option<Shared<crate::module::resolvers::StaticModuleResolver>>,
+    /// Global statements compiled into bytecode, on first evaluation.
+    #[cfg(feature = "bytecode")]
+    bytecode: crate::bytecode::BytecodeCache,
+    /// Does the [`AST`] need the global constants at runtime?  Computed on first evaluation.
+    #[cfg(not(feature = "no_module"))]
+    needs_globals: GlobalsFlag,
+}
+
+/// A lazily-computed flag recording whether an [`AST`] or a script-defined function needs the
+/// global constants at runtime.
+///
+/// Cloning the flag yields an unknown one.
+#[cfg(not(feature = "no_module"))]
+#[derive(Debug, Default)]
+pub(crate) struct GlobalsFlag(
+    #[cfg(not(feature = "sync"))] crate::stdlib::cell::RefCell<Option<bool>>,
+    #[cfg(feature = "sync")] crate::stdlib::sync::RwLock<Option<bool>>,
+);
+
+#[cfg(not(feature = "no_module"))]
+impl Clone for GlobalsFlag {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Default::default()
+    }
+}
+
+#[cfg(not(feature = "no_module"))]
+impl GlobalsFlag {
+    /// Get the flag, computing it with `compute` if not yet known.
+    fn get_or_compute(&self, compute: impl FnOnce() -> bool) -> bool {
+        #[cfg(not(feature = "sync"))]
+        let mut flag = self.0.borrow_mut();
+        #[cfg(feature = "sync")]
+        let mut flag = self.0.write().unwrap();
+
+        *flag.get_or_insert_with(compute)
+    }
+    /// Forget the flag, so that it is computed again.
+    #[inline(always)]
+    fn clear(&mut self) {
+        #[cfg(not(feature = "sync"))]
+        let flag = self.0.get_mut();
+        #[cfg(feature = "sync")]
+        let flag = self.0.get_mut().unwrap();
+
+        *flag = None;
+    }
+}
+
+/// Does the last node in an [`AST`] path need the global constants at runtime?
+///
+/// This is the case for variables in the `global` namespace, and for `import` statements and
+/// `eval` calls because they run other scripts that may refer to the `global` namespace.
+#[cfg(not(feature = "no_module"))]
+fn needs_global_constants(path: &[ASTNode]) -> bool {
+    match path.last() {
+        Some(ASTNode::Stmt(Stmt::Import(_, _, _)))
+        | Some(ASTNode::Stmt(Stmt::ImportMembers(_, _, _))) => true,
+        Some(ASTNode::Expr(Expr::Variable(x))) => matches!(&x.1, Some((_, ns)) if ns.is_global()),
+        Some(ASTNode::Expr(Expr::FnCall(x, _))) => x.name == crate::engine::KEYWORD_EVAL,
+        _ => false,
+    }
 }
 
 impl Default for AST {
@@ -184,6 +375,10 @@ impl Default for AST {
             functions: Default::default(),
             #[cfg(not(feature = "no_module"))]
             resolver: None,
+            #[cfg(feature = "bytecode")]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
         }
     }
 }
@@ -201,6 +396,10 @@ impl AST {
             functions: functions.into(),
             #[cfg(not(feature = "no_module"))]
             resolver: None,
+            #[cfg(feature = "bytecode")]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
         }
     }
     /// Create a new [`AST`] with a source name.
@@ -216,6 +415,10 @@ impl AST {
             functions: functions.into(),
             #[cfg(not(feature = "no_module"))]
             resolver: None,
+            #[cfg(feature = "bytecode")]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
         }
     }
     /// Get the source, if any.
@@ -259,10 +462,38 @@ impl AST {
     pub fn statements(&self) -> &[Stmt] {
         &self.statements
     }
+    /// Get the statements compiled into bytecode, compiling them on first use.
+    #[cfg(feature = "bytecode")]
+    #[inline(always)]
+    pub(crate) fn bytecode(&self) -> Shared<crate::bytecode::Bytecode> {
+        self.bytecode.get_or_compile_statements(&self.statements)
+    }
+    /// Does the [`AST`] need the global constants at runtime?
+    ///
+    /// This is the case when it refers to the `global` namespace, or when it runs other scripts
+    /// (via `import` or `eval`) that may do so.
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) fn needs_global_constants(&self) -> bool {
+        self.needs_globals.get_or_compute(|| {
+            let mut needed = false;
+
+            self.statements.iter().for_each(|stmt| {
+                stmt.walk(&mut Default::default(), &mut |path| {
+                    needed |= needs_global_constants(path)
+                })
+            });
+
+            needed || self.functions.needs_global_constants()
+        })
+    }
     /// Get a mutable reference to the statements.
     #[cfg(not(feature = "no_optimize"))]
     #[inline(always)]
     pub(crate) fn statements_mut(&mut self) -> &mut Vec<Stmt> {
+        #[cfg(feature = "bytecode")]
+        self.bytecode.clear();
+        #[cfg(not(feature = "no_module"))]
+        self.needs_globals.clear();
         &mut self.statements
     }
     /// Get the internal shared [`Module`] containing all script-defined functions.
@@ -324,6 +555,67 @@ impl AST {
         self.resolver = Some(resolver.into());
         self
     }
+    /// Serialize the [`AST`] into a compact binary cache, which can be loaded back via
+    /// [`AST::from_bytes`] to skip parsing and optimization.
+    ///
+    /// The cache is tied to the Rhai version and feature set of the current build.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`CacheError::NotSerializable`] if the [`AST`] contains data that cannot be
+    /// cached, such as custom types or native Rust functions in embedded [modules][Module].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use rhai::{Engine, AST};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let bytes = engine.compile("let x = 21; x * 2")?.to_bytes()?;
+    /// let ast = AST::from_bytes(&bytes)?;
+    ///
+    /// assert_eq!(engine.eval_ast::<i64>(&ast)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CacheError> {
+        let mut writer = Writer::new();
+        writer.write_source(self.source.as_ref());
+        writer.write_statements(&self.statements)?;
+        writer.write_module(&self.functions)?;
+        #[cfg(not(feature = "no_module"))]
+        writer.write_resolver(self.resolver.as_deref())?;
+        Ok(writer.into_bytes())
+    }
+    /// Load an [`AST`] from a binary cache created by [`AST::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the data is not a cache, or if it was written by a different Rhai version or
+    /// with a different set of features.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CacheError> {
+        let mut reader = Reader::new(bytes)?;
+        let source = reader.read_source()?;
+        let statements = reader.read_statements()?;
+        let functions = reader.read_module()?;
+        #[cfg(not(feature = "no_module"))]
+        let resolver = reader.read_resolver()?;
+        reader.finish()?;
+
+        Ok(Self {
+            source,
+            statements,
+            functions,
+            #[cfg(not(feature = "no_module"))]
+            resolver: resolver.map(Into::into),
+            #[cfg(feature = "bytecode")]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
+        })
+    }
     /// Clone the [`AST`]'s functions into a new [`AST`].
     /// No statements are cloned.
     ///
@@ -355,6 +647,10 @@ impl AST {
             functions: functions.into(),
             #[cfg(not(feature = "no_module"))]
             resolver: self.resolver.clone(),
+            #[cfg(feature = "bytecode")]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
         }
     }
     /// Clone the [`AST`]'s script statements into a new [`AST`].
@@ -367,6 +663,10 @@ impl AST {
             functions: Default::default(),
             #[cfg(not(feature = "no_module"))]
             resolver: self.resolver.clone(),
+            #[cfg(feature = "bytecode")]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
         }
     }
     /// Merge two [`AST`] into one.  Both [`AST`]'s are untouched and a new, merged, version
@@ -618,6 +918,10 @@ impl AST {
         mut filter: impl FnMut(FnNamespace, FnAccess, bool, &str, usize) -> bool,
     ) -> &mut Self {
         self.statements.extend(other.statements.into_iter());
+        #[cfg(feature = "bytecode")]
+        self.bytecode.clear();
+        #[cfg(not(feature = "no_module"))]
+        self.needs_globals.clear();
         if !other.functions.is_empty() {
             shared_make_mut(&mut self.functions).merge_filtered(&other.functions, &mut filter);
         }
@@ -668,17 +972,20 @@ impl AST {
     pub(crate) fn iter_fn_def(&self) -> impl Iterator<Item = &ScriptFnDef> {
         self.functions
             .iter_script_fn()
-            .map(|(_, _, _, _, fn_def)| fn_def)
+            .flat_map(|(_, _, _, _, fn_def)| fn_def.iter_overloads())
     }
     /// Iterate through all function definitions.
     ///
+    /// Each overload of a function with parameter type annotations is listed separately.
+    ///
     /// Not available under `no_function`.
     #[cfg(not(feature = "no_function"))]
     #[inline(always)]
     pub fn iter_functions<'a>(&'a self) -> impl Iterator<Item = ScriptFnMetadata> + 'a {
         self.functions
             .iter_script_fn()
-            .map(|(_, _, _, _, fn_def)| fn_def.into())
+            .flat_map(|(_, _, _, _, fn_def)| fn_def.iter_overloads())
+            .map(|fn_def| fn_def.into())
     }
     /// Clear all function definitions in the [`AST`].
     ///
@@ -692,6 +999,10 @@ impl AST {
     #[inline(always)]
     pub fn clear_statements(&mut self) {
         self.statements = vec![];
+        #[cfg(feature = "bytecode")]
+        self.bytecode.clear();
+        #[cfg(not(feature = "no_module"))]
+        self.needs_globals.clear();
     }
     /// Recursively walk the [`AST`], including function bodies (if any).
     #[cfg(not(feature = "internals"))]
@@ -870,6 +1181,11 @@ pub enum Stmt {
     /// `import` expr `as` var
     #[cfg(not(feature = "no_module"))]
     Import(Expr, Option<Box<Ident>>, Position),
+    /// `import` `{` var `as` var `,` ... `}` `from` expr
+    ///
+    /// or `export` `{` var `as` var `,` ... `}` `from` expr if the flag is `true`
+    #[cfg(not(feature = "no_module"))]
+    ImportMembers(Box<(Expr, Vec<(Ident, Option<Ident>)>)>, bool, Position),
     /// `export` var `as` var `,` ...
     #[cfg(not(feature = "no_module"))]
     Export(Vec<(Ident, Option<Ident>)>, Position),
@@ -917,6 +1233,8 @@ impl Stmt {
             #[cfg(not(feature = "no_module"))]
             Self::Import(_, _, pos) => *pos,
             #[cfg(not(feature = "no_module"))]
+            Self::ImportMembers(_, _, pos) => *pos,
+            #[cfg(not(feature = "no_module"))]
             Self::Export(_, pos) => *pos,
 
             #[cfg(not(feature = "no_closure"))]
@@ -948,6 +1266,8 @@ impl Stmt {
             #[cfg(not(feature = "no_module"))]
             Self::Import(_, _, pos) => *pos = new_pos,
             #[cfg(not(feature = "no_module"))]
+            Self::ImportMembers(_, _, pos) => *pos = new_pos,
+            #[cfg(not(feature = "no_module"))]
             Self::Export(_, pos) => *pos = new_pos,
 
             #[cfg(not(feature = "no_closure"))]
@@ -979,7 +1299,7 @@ impl Stmt {
             | Self::Return(_, _, _) => false,
 
             #[cfg(not(feature = "no_module"))]
-            Self::Import(_, _, _) | Self::Export(_, _) => false,
+            Self::Import(_, _, _) | Self::ImportMembers(_, _, _) | Self::Export(_, _) => false,
 
             #[cfg(not(feature = "no_closure"))]
             Self::Share(_) => unreachable!("Stmt::Share should not be parsed"),
@@ -1012,7 +1332,7 @@ impl Stmt {
             Self::TryCatch(x, _, _) => x.0.is_pure() && x.2.is_pure(),
 
             #[cfg(not(feature = "no_module"))]
-            Self::Import(_, _, _) => false,
+            Self::Import(_, _, _) | Self::ImportMembers(_, _, _) => false,
             #[cfg(not(feature = "no_module"))]
             Self::Export(_, _) => false,
 
@@ -1062,6 +1382,8 @@ impl Stmt {
             Self::Expr(e) | Self::Return(_, Some(e), _) => e.walk(path, on_node),
             #[cfg(not(feature = "no_module"))]
             Self::Import(e, _, _) => e.walk(path, on_node),
+            #[cfg(not(feature = "no_module"))]
+            Self::ImportMembers(x, _, _) => x.0.walk(path, on_node),
             _ => (),
         }
 
@@ -1488,10 +1810,15 @@ impl Expr {
             Self::Stmt(x, _) => x.iter().for_each(|s| s.walk(path, on_node)),
             Self::Array(x, _) => x.iter().for_each(|e| e.walk(path, on_node)),
             Self::Map(x, _) => x.iter().for_each(|(_, e)| e.walk(path, on_node)),
-            Self::Index(x, _) | Expr::In(x, _) | Expr::And(x, _) | Expr::Or(x, _) => {
+            Self::Dot(x, _)
+            | Self::Index(x, _)
+            | Expr::In(x, _)
+            | Expr::And(x, _)
+            | Expr::Or(x, _) => {
                 x.lhs.walk(path, on_node);
                 x.rhs.walk(path, on_node);
             }
+            Self::FnCall(x, _) => x.args.iter().for_each(|e| e.walk(path, on_node)),
             Self::Custom(x, _) => x.keywords.iter().for_each(|e| e.walk(path, on_node)),
             _ => (),
         }
@@ -0,0 +1,1457 @@
+//! Module implementing a compact binary format for caching compiled [`AST`][crate::AST]'s.
+//!
+//! # Format
+//!
+//! A cache starts with a header:
+//!
+//! * the magic bytes `RHAIAST`,
+//! * the format version,
+//! * the version of the Rhai crate that wrote it,
+//! * a fingerprint of the features that change the shape of an [`AST`][crate::AST]
+//!   (e.g. `only_i32`, `no_float`), and
+//! * a probe of the function hashing scheme, since pre-calculated hashes are stored verbatim.
+//!
+//! A cache whose header does not match the running build is rejected instead of misloaded.
+//!
+//! The body follows, with all integers in variable-length encoding.  Strings and
+//! [modules][Module] are written once and afterwards referred to by index, so shared data
+//! stays shared when loaded.  The same goes for module variables encapsulated into
+//! script-defined functions, although shared values are written as their current values.
+
+use crate::ast::{BinaryExpr, CustomExpr, Expr, FnCallExpr, Ident, ReturnType, Stmt};
+use crate::dynamic::{AccessMode, Union};
+use crate::module::NamespaceRef;
+use crate::stdlib::{
+    borrow::Cow,
+    boxed::Box,
+    collections::HashMap,
+    error::Error,
+    fmt,
+    iter::empty,
+    num::{NonZeroU64, NonZeroUsize},
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::utils::{HashableHashMap, StraightHasherBuilder};
+use crate::{
+    calc_script_fn_hash, Dynamic, FnPtr, ImmutableString, Module, Position, Shared, StaticVec, INT,
+};
+
+#[cfg(not(feature = "no_function"))]
+use crate::{
+    ast::{FnAccess, ScriptFnDef},
+    FnNamespace,
+};
+
+#[cfg(not(feature = "no_float"))]
+use crate::{ast::FloatWrapper, FLOAT};
+
+#[cfg(not(feature = "no_index"))]
+use crate::Array;
+
+#[cfg(not(feature = "no_object"))]
+use crate::Map;
+
+#[cfg(not(feature = "no_module"))]
+use crate::module::resolvers::StaticModuleResolver;
+
+/// Magic bytes at the start of an [`AST`][crate::AST] cache.
+const MAGIC: &[u8] = b"RHAIAST";
+
+/// Version of the binary format.  Bump whenever the encoding changes.
+const FORMAT_VERSION: u64 = 4;
+
+/// Error when writing an [`AST`][crate::AST] into, or reading it from, a binary cache.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[non_exhaustive]
+pub enum CacheError {
+    /// The data is not an [`AST`][crate::AST] cache.
+    InvalidFormat,
+    /// The cache was written by a different format or Rhai version.
+    /// Wrapped value is the Rhai version that wrote the cache.
+    IncompatibleVersion(String),
+    /// The cache was written by a build with a different set of features
+    /// (e.g. `only_i32` or `no_float`), or a different hashing scheme.
+    IncompatibleFeatures,
+    /// The cache is truncated or corrupted.
+    Corrupted,
+    /// The [`AST`][crate::AST] contains data that cannot be cached, such as native Rust functions
+    /// or custom types in embedded [modules][Module].  Wrapped value describes the data.
+    NotSerializable(String),
+}
+
+impl Error for CacheError {}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => f.write_str("Data is not a compiled script cache"),
+            Self::IncompatibleVersion(v) => write!(
+                f,
+                "Compiled script cache is written by an incompatible version: {}",
+                v
+            ),
+            Self::IncompatibleFeatures => {
+                f.write_str("Compiled script cache is written with incompatible features")
+            }
+            Self::Corrupted => f.write_str("Compiled script cache is corrupted"),
+            Self::NotSerializable(s) => write!(f, "Cannot cache {}", s),
+        }
+    }
+}
+
+/// Calculate a fingerprint of all features that change the shape of an [`AST`][crate::AST].
+fn features_fingerprint() -> u64 {
+    let features = [
+        cfg!(feature = "only_i32"),
+        cfg!(feature = "no_float"),
+        cfg!(feature = "f32_float"),
+        cfg!(feature = "no_index"),
+        cfg!(feature = "no_object"),
+        cfg!(feature = "no_function"),
+        cfg!(feature = "no_closure"),
+        cfg!(feature = "no_module"),
+    ];
+
+    features
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, &on)| if on { bits | (1 << i) } else { bits })
+}
+
+/// Calculate a probe hash to detect a change in the function hashing scheme.
+fn hash_probe() -> u64 {
+    calc_script_fn_hash(empty(), "rhai", 1).unwrap().get()
+}
+
+type Result<T> = crate::stdlib::result::Result<T, CacheError>;
+
+/// Module variables encapsulated into script-defined functions.
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_module"))]
+type FnVars = StaticVec<(ImmutableString, Dynamic)>;
+
+/// Writes an [`AST`][crate::AST] into the binary cache format.
+#[derive(Default)]
+pub(crate) struct Writer {
+    buf: Vec<u8>,
+    strings: HashMap<ImmutableString, u64>,
+    modules: HashMap<*const Module, u64>,
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_module"))]
+    vars: HashMap<*const FnVars, u64>,
+}
+
+impl Writer {
+    /// Create a new [`Writer`] and write the header.
+    pub fn new() -> Self {
+        let mut writer = Self::default();
+        writer.buf.extend_from_slice(MAGIC);
+        writer.write_u64(FORMAT_VERSION);
+        writer.write_str(env!("CARGO_PKG_VERSION"));
+        writer.write_u64(features_fingerprint());
+        writer.write_u64(hash_probe());
+        writer
+    }
+    /// Consume the [`Writer`] and return the bytes written.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+    fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+    fn write_u64(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+    fn write_i64(&mut self, value: i64) {
+        // Zig-zag encoding keeps small negative numbers small
+        self.write_u64(((value << 1) ^ (value >> 63)) as u64);
+    }
+    fn write_len(&mut self, len: usize) {
+        self.write_u64(len as u64);
+    }
+    fn write_str(&mut self, s: &str) {
+        self.write_len(s.len());
+        self.buf.extend_from_slice(s.as_bytes());
+    }
+    /// Write a shared string - the first occurrence is written in full, later ones as an index.
+    fn write_string(&mut self, s: &ImmutableString) {
+        if let Some(&index) = self.strings.get(s) {
+            self.write_u64(index + 1);
+        } else {
+            self.write_u64(0);
+            self.write_str(s);
+            let index = self.strings.len() as u64;
+            self.strings.insert(s.clone(), index);
+        }
+    }
+    fn write_cow(&mut self, s: &str) {
+        self.write_string(&s.into());
+    }
+    fn write_opt_string(&mut self, s: Option<&ImmutableString>) {
+        self.write_bool(s.is_some());
+        if let Some(s) = s {
+            self.write_string(s);
+        }
+    }
+    fn write_pos(&mut self, pos: Position) {
+        self.write_len(pos.line().unwrap_or(0));
+        self.write_len(pos.position().unwrap_or(0));
+    }
+    fn write_ident(&mut self, ident: &Ident) {
+        self.write_string(&ident.name);
+        self.write_pos(ident.pos);
+    }
+    fn write_opt_ident(&mut self, ident: Option<&Ident>) {
+        self.write_bool(ident.is_some());
+        if let Some(ident) = ident {
+            self.write_ident(ident);
+        }
+    }
+    fn write_hash(&mut self, hash: Option<NonZeroU64>) {
+        self.write_u64(hash.map(NonZeroU64::get).unwrap_or(0));
+    }
+    fn write_namespace(&mut self, namespace: Option<&NamespaceRef>) {
+        self.write_bool(namespace.is_some());
+        if let Some(namespace) = namespace {
+            self.write_len(namespace.index().map(NonZeroUsize::get).unwrap_or(0));
+            self.write_len(namespace.len());
+            namespace.iter().for_each(|ident| self.write_ident(ident));
+        }
+    }
+    /// Write a [`Dynamic`] value.  Only primitive values and containers of them can be written.
+    pub fn write_dynamic(&mut self, value: &Dynamic) -> Result<()> {
+        // Shared values are written as their current values
+        #[cfg(not(feature = "no_closure"))]
+        if value.is_shared() {
+            return self.write_dynamic(&value.flatten_clone());
+        }
+
+        let (tag, access) = match &value.0 {
+            Union::Unit(_, access) => (0, access),
+            Union::Bool(_, access) => (1, access),
+            Union::Str(_, access) => (2, access),
+            Union::Char(_, access) => (3, access),
+            Union::Int(_, access) => (4, access),
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(_, access) => (5, access),
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(_, access) => (6, access),
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(_, access) => (7, access),
+            Union::FnPtr(_, access) => (8, access),
+            _ => {
+                return Err(CacheError::NotSerializable(format!(
+                    "value of type '{}'",
+                    value.type_name()
+                )))
+            }
+        };
+
+        self.write_u8(if access.is_read_only() {
+            tag | 0x80
+        } else {
+            tag
+        });
+
+        match &value.0 {
+            Union::Unit(_, _) => (),
+            Union::Bool(x, _) => self.write_bool(*x),
+            Union::Str(x, _) => self.write_string(x),
+            Union::Char(x, _) => self.write_u64(*x as u64),
+            Union::Int(x, _) => self.write_i64(*x as i64),
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(x, _) => self.write_float(**x),
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(x, _) => {
+                self.write_len(x.len());
+                for item in x.iter() {
+                    self.write_dynamic(item)?;
+                }
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(x, _) => {
+                self.write_len(x.len());
+                for (key, item) in x.iter() {
+                    self.write_string(key);
+                    self.write_dynamic(item)?;
+                }
+            }
+            Union::FnPtr(x, _) => {
+                self.write_string(x.get_fn_name());
+                self.write_len(x.curry().len());
+                for item in x.curry() {
+                    self.write_dynamic(item)?;
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+    #[cfg(not(feature = "no_float"))]
+    fn write_float(&mut self, value: FLOAT) {
+        self.write_u64(value.to_bits() as u64);
+    }
+    fn write_opt_dynamic(&mut self, value: Option<&Dynamic>) -> Result<()> {
+        self.write_bool(value.is_some());
+        value.map_or(Ok(()), |v| self.write_dynamic(v))
+    }
+    fn write_stmts<'a>(&mut self, stmts: impl ExactSizeIterator<Item = &'a Stmt>) -> Result<()> {
+        self.write_len(stmts.len());
+        for stmt in stmts {
+            self.write_stmt(stmt)?;
+        }
+        Ok(())
+    }
+    fn write_opt_stmt(&mut self, stmt: Option<&Stmt>) -> Result<()> {
+        self.write_bool(stmt.is_some());
+        stmt.map_or(Ok(()), |s| self.write_stmt(s))
+    }
+    /// Write a [statement][Stmt].
+    pub fn write_stmt(&mut self, stmt: &Stmt) -> Result<()> {
+        match stmt {
+            Stmt::Noop(pos) => {
+                self.write_u8(0);
+                self.write_pos(*pos);
+            }
+            Stmt::If(condition, x, pos) => {
+                self.write_u8(1);
+                self.write_expr(condition)?;
+                self.write_stmt(&x.0)?;
+                self.write_opt_stmt(x.1.as_ref())?;
+                self.write_pos(*pos);
+            }
+            Stmt::Switch(expr, x, pos) => {
+                self.write_u8(2);
+                self.write_expr(expr)?;
+                self.write_len(x.0.len());
+                for (hash, stmt) in x.0.iter() {
+                    self.write_u64(*hash);
+                    self.write_stmt(stmt)?;
+                }
+                self.write_opt_stmt(x.1.as_ref())?;
+                self.write_pos(*pos);
+            }
+            Stmt::While(condition, body, pos) => {
+                self.write_u8(3);
+                self.write_expr(condition)?;
+                self.write_stmt(body)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Do(body, condition, is_while, pos) => {
+                self.write_u8(4);
+                self.write_stmt(body)?;
+                self.write_expr(condition)?;
+                self.write_bool(*is_while);
+                self.write_pos(*pos);
+            }
+            Stmt::For(iterable, x, pos) => {
+                self.write_u8(5);
+                self.write_expr(iterable)?;
+                self.write_str(&x.0);
+                self.write_stmt(&x.1)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Let(var, expr, export, pos) | Stmt::Const(var, expr, export, pos) => {
+                self.write_u8(if let Stmt::Let(_, _, _, _) = stmt {
+                    6
+                } else {
+                    7
+                });
+                self.write_ident(var);
+                self.write_bool(expr.is_some());
+                if let Some(expr) = expr {
+                    self.write_expr(expr)?;
+                }
+                self.write_bool(*export);
+                self.write_pos(*pos);
+            }
+            Stmt::Assignment(x, pos) => {
+                self.write_u8(8);
+                self.write_expr(&x.0)?;
+                self.write_cow(&x.1);
+                self.write_expr(&x.2)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Block(stmts, pos) => {
+                self.write_u8(9);
+                self.write_stmts(stmts.iter())?;
+                self.write_pos(*pos);
+            }
+            Stmt::TryCatch(x, try_pos, catch_pos) => {
+                self.write_u8(10);
+                self.write_stmt(&x.0)?;
+                self.write_opt_ident(x.1.as_ref());
+                self.write_stmt(&x.2)?;
+                self.write_pos(*try_pos);
+                self.write_pos(*catch_pos);
+            }
+            Stmt::Expr(expr) => {
+                self.write_u8(11);
+                self.write_expr(expr)?;
+            }
+            Stmt::Continue(pos) => {
+                self.write_u8(12);
+                self.write_pos(*pos);
+            }
+            Stmt::Break(pos) => {
+                self.write_u8(13);
+                self.write_pos(*pos);
+            }
+            Stmt::Return((return_type, return_pos), expr, pos) => {
+                self.write_u8(14);
+                self.write_bool(*return_type == ReturnType::Exception);
+                self.write_pos(*return_pos);
+                self.write_bool(expr.is_some());
+                if let Some(expr) = expr {
+                    self.write_expr(expr)?;
+                }
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(expr, alias, pos) => {
+                self.write_u8(15);
+                self.write_expr(expr)?;
+                self.write_opt_ident(alias.as_deref());
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(list, pos) => {
+                self.write_u8(16);
+                self.write_len(list.len());
+                for (name, alias) in list {
+                    self.write_ident(name);
+                    self.write_opt_ident(alias.as_ref());
+                }
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::ImportMembers(x, export, pos) => {
+                self.write_u8(18);
+                self.write_expr(&x.0)?;
+                self.write_len(x.1.len());
+                for (name, alias) in &x.1 {
+                    self.write_ident(name);
+                    self.write_opt_ident(alias.as_ref());
+                }
+                self.write_bool(*export);
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(var) => {
+                self.write_u8(17);
+                self.write_ident(var);
+            }
+        }
+
+        Ok(())
+    }
+    fn write_exprs<'a>(&mut self, exprs: impl ExactSizeIterator<Item = &'a Expr>) -> Result<()> {
+        self.write_len(exprs.len());
+        for expr in exprs {
+            self.write_expr(expr)?;
+        }
+        Ok(())
+    }
+    fn write_binary(&mut self, tag: u8, x: &BinaryExpr, pos: Position) -> Result<()> {
+        self.write_u8(tag);
+        self.write_expr(&x.lhs)?;
+        self.write_expr(&x.rhs)?;
+        self.write_pos(pos);
+        Ok(())
+    }
+    /// Write an [expression][Expr].
+    pub fn write_expr(&mut self, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::DynamicConstant(value, pos) => {
+                self.write_u8(0);
+                self.write_dynamic(value)?;
+                self.write_pos(*pos);
+            }
+            Expr::BoolConstant(value, pos) => {
+                self.write_u8(1);
+                self.write_bool(*value);
+                self.write_pos(*pos);
+            }
+            Expr::IntegerConstant(value, pos) => {
+                self.write_u8(2);
+                self.write_i64(*value as i64);
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(value, pos) => {
+                self.write_u8(3);
+                self.write_float(**value);
+                self.write_pos(*pos);
+            }
+            Expr::CharConstant(value, pos) => {
+                self.write_u8(4);
+                self.write_u64(*value as u64);
+                self.write_pos(*pos);
+            }
+            Expr::StringConstant(value, pos) => {
+                self.write_u8(5);
+                self.write_string(value);
+                self.write_pos(*pos);
+            }
+            Expr::FnPointer(value, pos) => {
+                self.write_u8(6);
+                self.write_string(value);
+                self.write_pos(*pos);
+            }
+            Expr::Array(x, pos) => {
+                self.write_u8(7);
+                self.write_exprs(x.iter())?;
+                self.write_pos(*pos);
+            }
+            Expr::Map(x, pos) => {
+                self.write_u8(8);
+                self.write_len(x.len());
+                for (name, expr) in x.iter() {
+                    self.write_ident(name);
+                    self.write_expr(expr)?;
+                }
+                self.write_pos(*pos);
+            }
+            Expr::Unit(pos) => {
+                self.write_u8(9);
+                self.write_pos(*pos);
+            }
+            Expr::Variable(x) => {
+                self.write_u8(10);
+                self.write_len(x.0.map(NonZeroUsize::get).unwrap_or(0));
+                self.write_bool(x.1.is_some());
+                if let Some((hash, namespace)) = &x.1 {
+                    self.write_hash(Some(*hash));
+                    self.write_namespace(Some(namespace));
+                }
+                self.write_ident(&x.2);
+            }
+            Expr::Property(x) => {
+                self.write_u8(11);
+                self.write_string(&x.0);
+                self.write_string(&x.1);
+                self.write_ident(&x.2);
+            }
+            Expr::Stmt(x, pos) => {
+                self.write_u8(12);
+                self.write_stmts(x.iter())?;
+                self.write_pos(*pos);
+            }
+            Expr::FnCall(x, pos) => {
+                self.write_u8(13);
+                self.write_fn_call(x)?;
+                self.write_pos(*pos);
+            }
+            Expr::Dot(x, pos) => self.write_binary(14, x, *pos)?,
+            Expr::Index(x, pos) => self.write_binary(15, x, *pos)?,
+            Expr::In(x, pos) => self.write_binary(16, x, *pos)?,
+            Expr::And(x, pos) => self.write_binary(17, x, *pos)?,
+            Expr::Or(x, pos) => self.write_binary(18, x, *pos)?,
+            Expr::Custom(x, pos) => {
+                self.write_u8(19);
+                self.write_exprs(x.keywords.iter())?;
+                self.write_len(x.tokens.len());
+                x.tokens.iter().for_each(|t| self.write_string(t));
+                self.write_i64(x.scope_delta as i64);
+                self.write_pos(*pos);
+            }
+        }
+
+        Ok(())
+    }
+    fn write_fn_call(&mut self, x: &FnCallExpr) -> Result<()> {
+        self.write_hash(x.hash_script);
+        self.write_bool(x.capture);
+        self.write_opt_dynamic(x.def_value.as_ref())?;
+        self.write_namespace(x.namespace.as_ref());
+        self.write_cow(&x.name);
+        self.write_exprs(x.args.iter())
+    }
+    /// Write a script-defined function.
+    #[cfg(not(feature = "no_function"))]
+    fn write_fn_def(&mut self, fn_def: &ScriptFnDef) -> Result<()> {
+        self.write_string(&fn_def.name);
+        self.write_bool(fn_def.access.is_private());
+        self.write_len(fn_def.params.len());
+        fn_def.params.iter().for_each(|p| self.write_string(p));
+        self.write_len(fn_def.param_types.len());
+        fn_def
+            .param_types
+            .iter()
+            .for_each(|t| self.write_opt_string(t.as_ref()));
+        #[cfg(not(feature = "no_closure"))]
+        {
+            self.write_len(fn_def.externals.len());
+            fn_def.externals.iter().for_each(|p| self.write_string(p));
+        }
+        self.write_len(fn_def.comments.len());
+        fn_def.comments.iter().for_each(|c| self.write_str(c));
+        self.write_stmt(&fn_def.body)?;
+
+        // Encapsulated environment
+        self.write_bool(fn_def.lib.is_some());
+        if let Some(lib) = &fn_def.lib {
+            self.write_module(lib)?;
+        }
+        #[cfg(not(feature = "no_module"))]
+        {
+            self.write_len(fn_def.mods.len());
+            // Imports are iterated in reverse order, so restore the original order
+            let mods: Vec<_> = fn_def.mods.iter_raw().collect();
+            for (alias, module) in mods.into_iter().rev() {
+                self.write_string(alias);
+                self.write_module(module)?;
+            }
+            match &fn_def.vars {
+                Some(vars) => self.write_fn_vars(vars)?,
+                None => self.write_u64(0),
+            }
+        }
+
+        // Other overloads
+        self.write_len(fn_def.overloads.len());
+        for overload in fn_def.overloads.iter() {
+            self.write_fn_def(overload)?;
+        }
+
+        // Class constructed
+        #[cfg(not(feature = "no_object"))]
+        {
+            self.write_bool(fn_def.class.is_some());
+            if let Some(class) = &fn_def.class {
+                self.write_string(&class.name);
+                self.write_len(class.fields.len());
+                for (name, expr) in class.fields.iter() {
+                    self.write_string(name);
+                    self.write_expr(expr)?;
+                }
+                self.write_len(class.methods.len());
+                for method in class.methods.values() {
+                    self.write_fn_def(method)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Write module variables encapsulated into a script-defined function.
+    ///
+    /// Module variables are shared among all functions of a module, so they are written only once.
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_module"))]
+    fn write_fn_vars(&mut self, vars: &Shared<FnVars>) -> Result<()> {
+        let key = Shared::as_ptr(vars);
+
+        if let Some(&index) = self.vars.get(&key) {
+            self.write_u64(index + 2);
+            return Ok(());
+        }
+
+        self.write_u64(1);
+        let index = self.vars.len() as u64;
+        self.vars.insert(key, index);
+
+        self.write_len(vars.len());
+        for (name, value) in vars.iter() {
+            self.write_string(name);
+            self.write_dynamic(value)?;
+        }
+        Ok(())
+    }
+    /// Write a shared [`Module`] - the first occurrence is written in full, later ones as an index.
+    ///
+    /// Only variables, sub-modules and script-defined functions can be written.
+    pub fn write_module(&mut self, module: &Shared<Module>) -> Result<()> {
+        let key = Shared::as_ptr(module);
+
+        if let Some(&index) = self.modules.get(&key) {
+            self.write_u64(index + 1);
+            return Ok(());
+        }
+
+        self.write_u64(0);
+        let index = self.modules.len() as u64;
+        self.modules.insert(key, index);
+
+        if module.count().2 > 0 {
+            return Err(CacheError::NotSerializable("type iterators".into()));
+        }
+
+        self.write_opt_string(module.id_raw());
+
+        let vars: Vec<_> = module.iter_var().collect();
+        self.write_len(vars.len());
+        for (name, value) in vars {
+            self.write_string(&name.into());
+            self.write_dynamic(value)?;
+        }
+
+        let sub_modules: Vec<_> = module.iter_sub_modules().collect();
+        self.write_len(sub_modules.len());
+        for (name, sub_module) in sub_modules {
+            self.write_string(&name.into());
+            self.write_module(&sub_module)?;
+        }
+
+        if let Some(f) = module.iter_fn().find(|f| !f.func.is_script()) {
+            return Err(CacheError::NotSerializable(format!(
+                "native function '{}'",
+                f.name
+            )));
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        {
+            let functions: Vec<_> = module.iter_script_fn().collect();
+            self.write_len(functions.len());
+            for (namespace, _, _, _, fn_def) in functions {
+                self.write_bool(namespace.is_global());
+                self.write_fn_def(fn_def)?;
+            }
+        }
+
+        Ok(())
+    }
+    /// Write a [`StaticModuleResolver`].
+    #[cfg(not(feature = "no_module"))]
+    pub fn write_resolver(&mut self, resolver: Option<&StaticModuleResolver>) -> Result<()> {
+        self.write_bool(resolver.is_some());
+
+        if let Some(resolver) = resolver {
+            let modules: Vec<_> = resolver.iter().collect();
+            self.write_len(modules.len());
+            for (path, module) in modules {
+                self.write_str(path);
+                self.write_module(module)?;
+            }
+        }
+
+        Ok(())
+    }
+    /// Write the source of an [`AST`][crate::AST].
+    pub fn write_source(&mut self, source: Option<&ImmutableString>) {
+        self.write_opt_string(source);
+    }
+    /// Write the global statements of an [`AST`][crate::AST].
+    pub fn write_statements(&mut self, stmts: &[Stmt]) -> Result<()> {
+        self.write_stmts(stmts.iter())
+    }
+}
+
+/// Reads an [`AST`][crate::AST] from the binary cache format.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    strings: Vec<ImmutableString>,
+    modules: Vec<Shared<Module>>,
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_module"))]
+    vars: Vec<Shared<FnVars>>,
+}
+
+impl<'a> Reader<'a> {
+    /// Create a new [`Reader`] and verify the header.
+    pub fn new(bytes: &'a [u8]) -> Result<Self> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(CacheError::InvalidFormat);
+        }
+
+        let mut reader = Self {
+            bytes: &bytes[MAGIC.len()..],
+            strings: Default::default(),
+            modules: Default::default(),
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_module"))]
+            vars: Default::default(),
+        };
+
+        let format_version = reader.read_u64()?;
+        let version = reader.read_str()?;
+
+        if format_version != FORMAT_VERSION || version != env!("CARGO_PKG_VERSION") {
+            return Err(CacheError::IncompatibleVersion(version));
+        }
+        if reader.read_u64()? != features_fingerprint() || reader.read_u64()? != hash_probe() {
+            return Err(CacheError::IncompatibleFeatures);
+        }
+
+        Ok(reader)
+    }
+    /// Make sure that all data has been consumed.
+    pub fn finish(self) -> Result<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(CacheError::Corrupted)
+        }
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        let (&value, rest) = self.bytes.split_first().ok_or(CacheError::Corrupted)?;
+        self.bytes = rest;
+        Ok(value)
+    }
+    fn read_bool(&mut self) -> Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CacheError::Corrupted),
+        }
+    }
+    fn read_u64(&mut self) -> Result<u64> {
+        let mut value = 0_u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+
+        Err(CacheError::Corrupted)
+    }
+    fn read_i64(&mut self) -> Result<i64> {
+        let value = self.read_u64()?;
+        Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+    }
+    fn read_int(&mut self) -> Result<INT> {
+        let value = self.read_i64()?;
+        if value < INT::MIN as i64 || value > INT::MAX as i64 {
+            return Err(CacheError::Corrupted);
+        }
+        Ok(value as INT)
+    }
+    fn read_len(&mut self) -> Result<usize> {
+        let len = self.read_u64()? as usize;
+        // A length can never exceed the number of remaining bytes
+        if len > self.bytes.len() {
+            Err(CacheError::Corrupted)
+        } else {
+            Ok(len)
+        }
+    }
+    fn read_usize(&mut self) -> Result<usize> {
+        Ok(self.read_u64()? as usize)
+    }
+    fn read_char(&mut self) -> Result<char> {
+        let value = self.read_u64()?;
+        crate::stdlib::char::from_u32(value as u32)
+            .filter(|_| value <= u32::MAX as u64)
+            .ok_or(CacheError::Corrupted)
+    }
+    fn read_str(&mut self) -> Result<String> {
+        let len = self.read_len()?;
+        let (s, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        crate::stdlib::str::from_utf8(s)
+            .map(|s| s.to_string())
+            .map_err(|_| CacheError::Corrupted)
+    }
+    fn read_string(&mut self) -> Result<ImmutableString> {
+        match self.read_u64()? {
+            0 => {
+                let s: ImmutableString = self.read_str()?.into();
+                self.strings.push(s.clone());
+                Ok(s)
+            }
+            index => self
+                .strings
+                .get(index as usize - 1)
+                .cloned()
+                .ok_or(CacheError::Corrupted),
+        }
+    }
+    fn read_cow(&mut self) -> Result<Cow<'static, str>> {
+        Ok(self.read_string()?.to_string().into())
+    }
+    fn read_opt_string(&mut self) -> Result<Option<ImmutableString>> {
+        Ok(if self.read_bool()? {
+            Some(self.read_string()?)
+        } else {
+            None
+        })
+    }
+    fn read_pos(&mut self) -> Result<Position> {
+        let line = self.read_u64()?;
+        let pos = self.read_u64()?;
+
+        if line > u16::MAX as u64 || pos > u16::MAX as u64 {
+            Err(CacheError::Corrupted)
+        } else if line == 0 {
+            Ok(Position::NONE)
+        } else {
+            Ok(Position::new(line as u16, pos as u16))
+        }
+    }
+    fn read_ident(&mut self) -> Result<Ident> {
+        Ok(Ident {
+            name: self.read_string()?,
+            pos: self.read_pos()?,
+        })
+    }
+    fn read_opt_ident(&mut self) -> Result<Option<Ident>> {
+        Ok(if self.read_bool()? {
+            Some(self.read_ident()?)
+        } else {
+            None
+        })
+    }
+    fn read_hash(&mut self) -> Result<Option<NonZeroU64>> {
+        Ok(NonZeroU64::new(self.read_u64()?))
+    }
+    fn read_namespace(&mut self) -> Result<Option<NamespaceRef>> {
+        if !self.read_bool()? {
+            return Ok(None);
+        }
+
+        let _index = NonZeroUsize::new(self.read_usize()?);
+        let len = self.read_len()?;
+        let path: StaticVec<_> = (0..len).map(|_| self.read_ident()).collect::<Result<_>>()?;
+        let mut _namespace: NamespaceRef = path.into();
+        #[cfg(not(feature = "no_module"))]
+        _namespace.set_index(_index);
+        Ok(Some(_namespace))
+    }
+    /// Read a [`Dynamic`] value.
+    pub fn read_dynamic(&mut self) -> Result<Dynamic> {
+        let tag = self.read_u8()?;
+
+        let mut value = match tag & 0x7f {
+            0 => Dynamic::UNIT,
+            1 => self.read_bool()?.into(),
+            2 => self.read_string()?.into(),
+            3 => self.read_char()?.into(),
+            4 => self.read_int()?.into(),
+            #[cfg(not(feature = "no_float"))]
+            5 => self.read_float()?.into(),
+            #[cfg(not(feature = "no_index"))]
+            6 => {
+                let len = self.read_len()?;
+                let array: Array = (0..len)
+                    .map(|_| self.read_dynamic())
+                    .collect::<Result<_>>()?;
+                array.into()
+            }
+            #[cfg(not(feature = "no_object"))]
+            7 => {
+                let len = self.read_len()?;
+                let mut map = Map::with_capacity(len);
+                for _ in 0..len {
+                    let key = self.read_string()?;
+                    map.insert(key, self.read_dynamic()?);
+                }
+                map.into()
+            }
+            8 => {
+                let name = self.read_string()?;
+                let len = self.read_len()?;
+                let curry = (0..len)
+                    .map(|_| self.read_dynamic())
+                    .collect::<Result<_>>()?;
+                FnPtr::new_unchecked(name, curry).into()
+            }
+            _ => return Err(CacheError::Corrupted),
+        };
+
+        value.set_access_mode(if tag & 0x80 != 0 {
+            AccessMode::ReadOnly
+        } else {
+            AccessMode::ReadWrite
+        });
+
+        Ok(value)
+    }
+    #[cfg(not(feature = "no_float"))]
+    fn read_float(&mut self) -> Result<FLOAT> {
+        #[cfg(not(feature = "f32_float"))]
+        return Ok(FLOAT::from_bits(self.read_u64()?));
+        #[cfg(feature = "f32_float")]
+        return Ok(FLOAT::from_bits(self.read_u64()? as u32));
+    }
+    fn read_opt_dynamic(&mut self) -> Result<Option<Dynamic>> {
+        Ok(if self.read_bool()? {
+            Some(self.read_dynamic()?)
+        } else {
+            None
+        })
+    }
+    fn read_stmts<T: crate::stdlib::iter::FromIterator<Stmt>>(&mut self) -> Result<T> {
+        let len = self.read_len()?;
+        (0..len).map(|_| self.read_stmt()).collect()
+    }
+    fn read_opt_stmt(&mut self) -> Result<Option<Stmt>> {
+        Ok(if self.read_bool()? {
+            Some(self.read_stmt()?)
+        } else {
+            None
+        })
+    }
+    fn read_opt_expr(&mut self) -> Result<Option<Expr>> {
+        Ok(if self.read_bool()? {
+            Some(self.read_expr()?)
+        } else {
+            None
+        })
+    }
+    /// Read a [statement][Stmt].
+    pub fn read_stmt(&mut self) -> Result<Stmt> {
+        Ok(match self.read_u8()? {
+            0 => Stmt::Noop(self.read_pos()?),
+            1 => {
+                let condition = self.read_expr()?;
+                let if_block = self.read_stmt()?;
+                let else_block = self.read_opt_stmt()?;
+                Stmt::If(
+                    condition,
+                    Box::new((if_block, else_block)),
+                    self.read_pos()?,
+                )
+            }
+            2 => {
+                let expr = self.read_expr()?;
+                let len = self.read_len()?;
+                let mut table = HashMap::with_capacity_and_hasher(len, StraightHasherBuilder);
+                for _ in 0..len {
+                    let hash = self.read_u64()?;
+                    table.insert(hash, self.read_stmt()?);
+                }
+                let def_stmt = self.read_opt_stmt()?;
+                let table: HashableHashMap<_, _, _> = table.into();
+                Stmt::Switch(expr, Box::new((table, def_stmt)), self.read_pos()?)
+            }
+            3 => {
+                let condition = self.read_expr()?;
+                let body = self.read_stmt()?;
+                Stmt::While(condition, Box::new(body), self.read_pos()?)
+            }
+            4 => {
+                let body = self.read_stmt()?;
+                let condition = self.read_expr()?;
+                let is_while = self.read_bool()?;
+                Stmt::Do(Box::new(body), condition, is_while, self.read_pos()?)
+            }
+            5 => {
+                let iterable = self.read_expr()?;
+                let name = self.read_str()?;
+                let body = self.read_stmt()?;
+                Stmt::For(iterable, Box::new((name, body)), self.read_pos()?)
+            }
+            tag @ 6 | tag @ 7 => {
+                let var = Box::new(self.read_ident()?);
+                let expr = self.read_opt_expr()?;
+                let export = self.read_bool()?;
+                let pos = self.read_pos()?;
+                if tag == 6 {
+                    Stmt::Let(var, expr, export, pos)
+                } else {
+                    Stmt::Const(var, expr, export, pos)
+                }
+            }
+            8 => {
+                let lhs = self.read_expr()?;
+                let op = self.read_cow()?;
+                let rhs = self.read_expr()?;
+                Stmt::Assignment(Box::new((lhs, op, rhs)), self.read_pos()?)
+            }
+            9 => {
+                let stmts = self.read_stmts()?;
+                Stmt::Block(stmts, self.read_pos()?)
+            }
+            10 => {
+                let try_block = self.read_stmt()?;
+                let var = self.read_opt_ident()?;
+                let catch_block = self.read_stmt()?;
+                let try_pos = self.read_pos()?;
+                Stmt::TryCatch(
+                    Box::new((try_block, var, catch_block)),
+                    try_pos,
+                    self.read_pos()?,
+                )
+            }
+            11 => Stmt::Expr(self.read_expr()?),
+            12 => Stmt::Continue(self.read_pos()?),
+            13 => Stmt::Break(self.read_pos()?),
+            14 => {
+                let return_type = if self.read_bool()? {
+                    ReturnType::Exception
+                } else {
+                    ReturnType::Return
+                };
+                let return_pos = self.read_pos()?;
+                let expr = self.read_opt_expr()?;
+                Stmt::Return((return_type, return_pos), expr, self.read_pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            15 => {
+                let expr = self.read_expr()?;
+                let alias = self.read_opt_ident()?.map(Box::new);
+                Stmt::Import(expr, alias, self.read_pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            16 => {
+                let len = self.read_len()?;
+                let list = (0..len)
+                    .map(|_| Ok((self.read_ident()?, self.read_opt_ident()?)))
+                    .collect::<Result<_>>()?;
+                Stmt::Export(list, self.read_pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            18 => {
+                let expr = self.read_expr()?;
+                let len = self.read_len()?;
+                let list = (0..len)
+                    .map(|_| Ok((self.read_ident()?, self.read_opt_ident()?)))
+                    .collect::<Result<_>>()?;
+                let export = self.read_bool()?;
+                Stmt::ImportMembers(Box::new((expr, list)), export, self.read_pos()?)
+            }
+            #[cfg(not(feature = "no_closure"))]
+            17 => Stmt::Share(self.read_ident()?),
+            _ => return Err(CacheError::Corrupted),
+        })
+    }
+    fn read_exprs(&mut self) -> Result<StaticVec<Expr>> {
+        let len = self.read_len()?;
+        (0..len).map(|_| self.read_expr()).collect()
+    }
+    fn read_binary(&mut self) -> Result<(Box<BinaryExpr>, Position)> {
+        let lhs = self.read_expr()?;
+        let rhs = self.read_expr()?;
+        Ok((Box::new(BinaryExpr { lhs, rhs }), self.read_pos()?))
+    }
+    /// Read an [expression][Expr].
+    pub fn read_expr(&mut self) -> Result<Expr> {
+        Ok(match self.read_u8()? {
+            0 => {
+                let value = self.read_dynamic()?;
+                Expr::DynamicConstant(Box::new(value), self.read_pos()?)
+            }
+            1 => {
+                let value = self.read_bool()?;
+                Expr::BoolConstant(value, self.read_pos()?)
+            }
+            2 => {
+                let value = self.read_int()?;
+                Expr::IntegerConstant(value, self.read_pos()?)
+            }
+            #[cfg(not(feature = "no_float"))]
+            3 => {
+                let value = FloatWrapper::new(self.read_float()?);
+                Expr::FloatConstant(value, self.read_pos()?)
+            }
+            4 => {
+                let value = self.read_char()?;
+                Expr::CharConstant(value, self.read_pos()?)
+            }
+            5 => {
+                let value = self.read_string()?;
+                Expr::StringConstant(value, self.read_pos()?)
+            }
+            6 => {
+                let value = self.read_string()?;
+                Expr::FnPointer(value, self.read_pos()?)
+            }
+            7 => {
+                let items = self.read_exprs()?;
+                Expr::Array(Box::new(items), self.read_pos()?)
+            }
+            8 => {
+                let len = self.read_len()?;
+                let items = (0..len)
+                    .map(|_| Ok((self.read_ident()?, self.read_expr()?)))
+                    .collect::<Result<_>>()?;
+                Expr::Map(Box::new(items), self.read_pos()?)
+            }
+            9 => Expr::Unit(self.read_pos()?),
+            10 => {
+                let index = NonZeroUsize::new(self.read_usize()?);
+                let qualified = if self.read_bool()? {
+                    let hash = self.read_hash()?.ok_or(CacheError::Corrupted)?;
+                    let namespace = self.read_namespace()?.ok_or(CacheError::Corrupted)?;
+                    Some((hash, namespace))
+                } else {
+                    None
+                };
+                Expr::Variable(Box::new((index, qualified, self.read_ident()?)))
+            }
+            11 => {
+                let getter = self.read_string()?;
+                let setter = self.read_string()?;
+                Expr::Property(Box::new((getter, setter, self.read_ident()?)))
+            }
+            12 => {
+                let stmts = self.read_stmts()?;
+                Expr::Stmt(Box::new(stmts), self.read_pos()?)
+            }
+            13 => {
+                let x = self.read_fn_call()?;
+                Expr::FnCall(Box::new(x), self.read_pos()?)
+            }
+            14 => {
+                let (x, pos) = self.read_binary()?;
+                Expr::Dot(x, pos)
+            }
+            15 => {
+                let (x, pos) = self.read_binary()?;
+                Expr::Index(x, pos)
+            }
+            16 => {
+                let (x, pos) = self.read_binary()?;
+                Expr::In(x, pos)
+            }
+            17 => {
+                let (x, pos) = self.read_binary()?;
+                Expr::And(x, pos)
+            }
+            18 => {
+                let (x, pos) = self.read_binary()?;
+                Expr::Or(x, pos)
+            }
+            19 => {
+                let keywords = self.read_exprs()?;
+                let len = self.read_len()?;
+                let tokens = (0..len)
+                    .map(|_| self.read_string())
+                    .collect::<Result<_>>()?;
+                let scope_delta = self.read_i64()? as isize;
+                let x = CustomExpr {
+                    keywords,
+                    tokens,
+                    scope_delta,
+                };
+                Expr::Custom(Box::new(x), self.read_pos()?)
+            }
+            _ => return Err(CacheError::Corrupted),
+        })
+    }
+    fn read_fn_call(&mut self) -> Result<FnCallExpr> {
+        Ok(FnCallExpr {
+            hash_script: self.read_hash()?,
+            capture: self.read_bool()?,
+            def_value: self.read_opt_dynamic()?,
+            namespace: self.read_namespace()?,
+            name: self.read_cow()?,
+            args: self.read_exprs()?,
+        })
+    }
+    /// Read a script-defined function.
+    #[cfg(not(feature = "no_function"))]
+    fn read_fn_def(&mut self) -> Result<ScriptFnDef> {
+        let name = self.read_string()?;
+        let access = if self.read_bool()? {
+            FnAccess::Private
+        } else {
+            FnAccess::Public
+        };
+        let len = self.read_len()?;
+        let params = (0..len)
+            .map(|_| self.read_string())
+            .collect::<Result<_>>()?;
+        let len = self.read_len()?;
+        let param_types = (0..len)
+            .map(|_| self.read_opt_string())
+            .collect::<Result<_>>()?;
+        #[cfg(not(feature = "no_closure"))]
+        let externals = {
+            let len = self.read_len()?;
+            (0..len)
+                .map(|_| self.read_string())
+                .collect::<Result<_>>()?
+        };
+        let len = self.read_len()?;
+        let comments = (0..len).map(|_| self.read_str()).collect::<Result<_>>()?;
+        let body = self.read_stmt()?;
+
+        let lib = if self.read_bool()? {
+            Some(self.read_module()?)
+        } else {
+            None
+        };
+        #[cfg(not(feature = "no_module"))]
+        let mods = {
+            let mut mods = crate::engine::Imports::default();
+            let len = self.read_len()?;
+            for _ in 0..len {
+                let alias = self.read_string()?;
+                mods.push(alias, self.read_module()?);
+            }
+            mods
+        };
+        #[cfg(not(feature = "no_module"))]
+        let vars = self.read_fn_vars()?;
+
+        let len = self.read_len()?;
+        let overloads = (0..len)
+            .map(|_| self.read_fn_def())
+            .collect::<Result<_>>()?;
+
+        #[cfg(not(feature = "no_object"))]
+        let class = if self.read_bool()? {
+            let name = self.read_string()?;
+            let len = self.read_len()?;
+            let fields = (0..len)
+                .map(|_| Ok((self.read_string()?, self.read_expr()?)))
+                .collect::<Result<_>>()?;
+            let len = self.read_len()?;
+            let methods = (0..len)
+                .map(|_| {
+                    let method = self.read_fn_def()?;
+                    let hash =
+                        calc_script_fn_hash(empty(), &method.name, method.params.len()).unwrap();
+                    Ok((hash, method))
+                })
+                .collect::<Result<_>>()?;
+            Some(
+                crate::ast::ClassDef {
+                    name,
+                    fields,
+                    methods,
+                }
+                .into(),
+            )
+        } else {
+            None
+        };
+
+        Ok(ScriptFnDef {
+            body,
+            lib,
+            #[cfg(not(feature = "no_module"))]
+            mods,
+            #[cfg(not(feature = "no_module"))]
+            vars,
+            name,
+            access,
+            params,
+            param_types,
+            overloads,
+            #[cfg(not(feature = "no_closure"))]
+            externals,
+            comments,
+            #[cfg(not(feature = "no_object"))]
+            class,
+            #[cfg(feature = "bytecode")]
+            #[cfg(not(feature = "no_function"))]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
+        })
+    }
+    /// Read module variables encapsulated into a script-defined function.
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_module"))]
+    fn read_fn_vars(&mut self) -> Result<Option<Shared<FnVars>>> {
+        match self.read_u64()? {
+            0 => Ok(None),
+            1 => {
+                let len = self.read_len()?;
+                let vars: FnVars = (0..len)
+                    .map(|_| {
+                        let name = self.read_string()?;
+                        let value = self.read_dynamic()?;
+                        // Mutable module variables are shared among all functions of the module
+                        #[cfg(not(feature = "no_closure"))]
+                        let value = if value.is_read_only() {
+                            value
+                        } else {
+                            value.into_shared()
+                        };
+                        Ok((name, value))
+                    })
+                    .collect::<Result<_>>()?;
+                let vars: Shared<FnVars> = vars.into();
+                self.vars.push(vars.clone());
+                Ok(Some(vars))
+            }
+            index => self
+                .vars
+                .get(index as usize - 2)
+                .cloned()
+                .map(Some)
+                .ok_or(CacheError::Corrupted),
+        }
+    }
+    /// Read a shared [`Module`].
+    pub fn read_module(&mut self) -> Result<Shared<Module>> {
+        let index = self.read_u64()?;
+
+        if index > 0 {
+            return self
+                .modules
+                .get(index as usize - 1)
+                .cloned()
+                .ok_or(CacheError::Corrupted);
+        }
+
+        // Reserve the index so that references within this module are numbered correctly
+        let slot = self.modules.len();
+        self.modules.push(Default::default());
+
+        let mut module = Module::new();
+        module.set_id(self.read_opt_string()?);
+
+        let len = self.read_len()?;
+        for _ in 0..len {
+            let name = self.read_string()?;
+            module.set_var(name, self.read_dynamic()?);
+        }
+
+        let len = self.read_len()?;
+        for _ in 0..len {
+            let name = self.read_string()?;
+            module.set_sub_module(name, self.read_module()?);
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        {
+            let len = self.read_len()?;
+            for _ in 0..len {
+                let is_global = self.read_bool()?;
+                let hash = module.set_script_fn(self.read_fn_def()?);
+                if is_global {
+                    module.update_fn_namespace(hash, FnNamespace::Global);
+                }
+            }
+        }
+
+        module.build_index();
+
+        let module: Shared<Module> = module.into();
+        self.modules[slot] = module.clone();
+        Ok(module)
+    }
+    /// Read a [`StaticModuleResolver`].
+    #[cfg(not(feature = "no_module"))]
+    pub fn read_resolver(&mut self) -> Result<Option<StaticModuleResolver>> {
+        if !self.read_bool()? {
+            return Ok(None);
+        }
+
+        let mut resolver = StaticModuleResolver::new();
+        let len = self.read_len()?;
+        for _ in 0..len {
+            let path = self.read_str()?;
+            let module = crate::fn_native::shared_take_or_clone(self.read_module()?);
+            resolver.insert(path, module);
+        }
+
+        Ok(Some(resolver))
+    }
+    /// Read the source of an [`AST`][crate::AST].
+    pub fn read_source(&mut self) -> Result<Option<ImmutableString>> {
+        self.read_opt_string()
+    }
+    /// Read the global statements of an [`AST`][crate::AST].
+    pub fn read_statements(&mut self) -> Result<Vec<Stmt>> {
+        self.read_stmts()
+    }
+}
@@ -0,0 +1,220 @@
+//! Module defining the builder API to register custom types with an [`Engine`].
+
+use crate::dynamic::Variant;
+use crate::fn_native::SendSync;
+use crate::stdlib::{any::type_name, marker::PhantomData};
+use crate::{Engine, RegisterFn, RegisterResultFn};
+
+/// Trait to build the API of a custom type for use with an [`Engine`]
+/// (i.e. register the type, its methods, property getters/setters, etc.).
+///
+/// Implement it manually, or derive it via `#[derive(CustomType)]`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{CustomType, Engine, TypeBuilder, INT};
+///
+/// #[derive(Debug, Clone)]
+/// struct TestStruct {
+///     field: INT
+/// }
+///
+/// impl CustomType for TestStruct {
+///     fn build(mut builder: TypeBuilder<Self>) {
+///         builder
+///             .with_name("TestStruct")
+///             .with_fn("new_ts", || TestStruct { field: 1 })
+///             .with_fn("update", |x: &mut TestStruct| x.field += 41);
+///     }
+/// }
+///
+/// let mut engine = Engine::new();
+///
+/// engine.build_type::<TestStruct>();
+///
+/// assert_eq!(engine.eval::<String>("let x = new_ts(); type_of(x)")?, "TestStruct");
+/// assert_eq!(engine.eval::<TestStruct>("let x = new_ts(); update(x); x")?.field, 42);
+/// # Ok(())
+/// # }
+/// ```
+pub trait CustomType: Variant + Clone {
+    /// Build the API of the custom type via a [`TypeBuilder`].
+    fn build(builder: TypeBuilder<Self>);
+}
+
+impl Engine {
+    /// Build the API of a custom type, which implements [`CustomType`], for use with the [`Engine`].
+    ///
+    /// See [`CustomType`] for an example.
+    #[inline(always)]
+    pub fn build_type<T: CustomType>(&mut self) -> &mut Self {
+        T::build(TypeBuilder::new(self));
+        self
+    }
+}
+
+/// Builder to register the API of a custom type with an [`Engine`].
+///
+/// Created by [`Engine::build_type`] for the [`CustomType::build`] method of the type.
+pub struct TypeBuilder<'a, T: Variant + Clone> {
+    engine: &'a mut Engine,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
+    /// Create a [`TypeBuilder`] registering the API of a custom type with an [`Engine`].
+    #[inline(always)]
+    fn new(engine: &'a mut Engine) -> Self {
+        Self {
+            engine,
+            _marker: PhantomData,
+        }
+    }
+    /// Set a pretty-print name for the type, returned by the `type_of` function.
+    #[inline(always)]
+    pub fn with_name(&mut self, name: &str) -> &mut Self {
+        self.engine
+            .type_names
+            .insert(type_name::<T>().into(), name.into());
+        self
+    }
+    /// Register a function (usually a method or a constructor of the type).
+    #[inline(always)]
+    pub fn with_fn<FN, ARGS, RET>(&mut self, name: &str, method: FN) -> &mut Self
+    where
+        Engine: RegisterFn<FN, ARGS, RET>,
+    {
+        self.engine.register_fn(name, method);
+        self
+    }
+    /// Register a function (usually a method or a constructor of the type) returning a
+    /// [`Result`] of a [`Dynamic`][crate::Dynamic] value.
+    #[inline(always)]
+    pub fn with_result_fn<FN, ARGS>(&mut self, name: &str, method: FN) -> &mut Self
+    where
+        Engine: RegisterResultFn<FN, ARGS>,
+    {
+        self.engine.register_result_fn(name, method);
+        self
+    }
+    /// Register a getter function for a property of the type.
+    ///
+    /// Properties cannot be accessed under `no_object`, so getters are not registered.
+    #[inline(always)]
+    pub fn with_get<U: Variant + Clone>(
+        &mut self,
+        name: &str,
+        get_fn: impl Fn(&mut T) -> U + SendSync + 'static,
+    ) -> &mut Self {
+        #[cfg(not(feature = "no_object"))]
+        self.engine.register_get(name, get_fn);
+        #[cfg(feature = "no_object")]
+        let _ = (name, get_fn);
+        self
+    }
+    /// Register a setter function for a property of the type.
+    ///
+    /// Properties cannot be accessed under `no_object`, so setters are not registered.
+    #[inline(always)]
+    pub fn with_set<U: Variant + Clone>(
+        &mut self,
+        name: &str,
+        set_fn: impl Fn(&mut T, U) + SendSync + 'static,
+    ) -> &mut Self {
+        #[cfg(not(feature = "no_object"))]
+        self.engine.register_set(name, set_fn);
+        #[cfg(feature = "no_object")]
+        let _ = (name, set_fn);
+        self
+    }
+    /// Register a getter and a setter function for a property of the type.
+    ///
+    /// Properties cannot be accessed under `no_object`, so getters and setters are not registered.
+    #[inline(always)]
+    pub fn with_get_set<U: Variant + Clone>(
+        &mut self,
+        name: &str,
+        get_fn: impl Fn(&mut T) -> U + SendSync + 'static,
+        set_fn: impl Fn(&mut T, U) + SendSync + 'static,
+    ) -> &mut Self {
+        self.with_get(name, get_fn).with_set(name, set_fn)
+    }
+}
+
+/// Helpers for `#[derive(CustomType)]` to register functions only when the type implements
+/// the traits they require.
+///
+/// Calling a method on `&Probe<T>` resolves to the trait implemented for `Probe<T>` when `T`
+/// satisfies its bounds, otherwise to the fallback trait implemented for `&Probe<T>`, which
+/// does nothing.
+#[doc(hidden)]
+pub mod derive {
+    use super::TypeBuilder;
+    use crate::dynamic::Variant;
+    use crate::engine::{FN_TO_DEBUG, FN_TO_STRING, KEYWORD_DEBUG, KEYWORD_PRINT};
+    use crate::stdlib::{fmt::Debug, format, marker::PhantomData};
+    use crate::ImmutableString;
+
+    /// Probe for the traits implemented by a type.
+    pub struct Probe<T>(PhantomData<T>);
+
+    impl<T> Probe<T> {
+        #[inline(always)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    impl<T> Default for Probe<T> {
+        #[inline(always)]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Register `to_string`, `to_debug`, `print` and `debug` for a type implementing [`Debug`].
+    pub trait BuildDebug<T: Variant + Clone> {
+        fn build_debug(&self, builder: &mut TypeBuilder<T>);
+    }
+
+    impl<T: Variant + Clone + Debug> BuildDebug<T> for Probe<T> {
+        fn build_debug(&self, builder: &mut TypeBuilder<T>) {
+            let to_debug = |x: &mut T| -> ImmutableString { format!("{:?}", x).into() };
+
+            builder
+                .with_fn(FN_TO_STRING, to_debug)
+                .with_fn(FN_TO_DEBUG, to_debug)
+                .with_fn(KEYWORD_PRINT, to_debug)
+                .with_fn(KEYWORD_DEBUG, to_debug);
+        }
+    }
+
+    pub trait BuildDebugFallback<T: Variant + Clone> {
+        #[inline(always)]
+        fn build_debug(&self, _: &mut TypeBuilder<T>) {}
+    }
+
+    impl<T: Variant + Clone> BuildDebugFallback<T> for &Probe<T> {}
+
+    /// Register `==` and `!=` for a type implementing [`PartialEq`].
+    pub trait BuildEq<T: Variant + Clone> {
+        fn build_eq(&self, builder: &mut TypeBuilder<T>);
+    }
+
+    impl<T: Variant + Clone + PartialEq> BuildEq<T> for Probe<T> {
+        fn build_eq(&self, builder: &mut TypeBuilder<T>) {
+            builder
+                .with_fn("==", |x: &mut T, y: T| *x == y)
+                .with_fn("!=", |x: &mut T, y: T| *x != y);
+        }
+    }
+
+    pub trait BuildEqFallback<T: Variant + Clone> {
+        #[inline(always)]
+        fn build_eq(&self, _: &mut TypeBuilder<T>) {}
+    }
+
+    impl<T: Variant + Clone> BuildEqFallback<T> for &Probe<T> {}
+}
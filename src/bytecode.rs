@@ -0,0 +1,1085 @@
+//! Module implementing the bytecode compiler and the register-based virtual machine.
+//!
+//! Statements and expressions are lowered into a flat list of [instructions][Instr] operating on
+//! a small file of registers.  Control flow (conditionals, loops, `switch`, `break`/`continue`,
+//! `return`/`throw`) is turned into jumps; variables with pre-calculated offsets are accessed
+//! directly; and calls to non-qualified functions carry a pre-hashed function name.
+//!
+//! Constructs that do not benefit from lowering (e.g. indexing, property access, closures,
+//! `try`/`catch`, `import`) are kept as [`AST`][crate::AST] nodes and delegated to the
+//! tree-walking evaluator.
+
+use crate::ast::{Expr, FnCallExpr, ReturnType, Stmt};
+use crate::dynamic::AccessMode;
+use crate::engine::{
+    Imports, State, Target, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL,
+    KEYWORD_FN_PTR_CURRY, KEYWORD_IS_DEF_VAR,
+};
+use crate::fn_call::ensure_no_data_race;
+use crate::r#unsafe::unsafe_cast_var_name_to_lifetime;
+use crate::stdlib::{
+    borrow::Cow,
+    boxed::Box,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    iter::once,
+    mem,
+    num::{NonZeroU64, NonZeroUsize},
+    string::ToString,
+    vec::Vec,
+};
+use crate::utils::{get_hasher, NativeFnHashPrefix, StraightHasherBuilder};
+use crate::{
+    Dynamic, Engine, EvalAltResult, ImmutableString, Module, Position, Scope, Shared, StaticVec,
+};
+
+/// The execution backend used by an [`Engine`] to run scripts.
+///
+/// Exported under the `bytecode` feature only.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ExecutionBackend {
+    /// Evaluate scripts by walking the [`AST`][crate::AST] directly.
+    TreeWalking,
+    /// Compile scripts into bytecode and run them on a register-based virtual machine.
+    Bytecode,
+}
+
+impl Default for ExecutionBackend {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Bytecode
+    }
+}
+
+/// Index of a register.
+type Reg = usize;
+
+/// A variable access.
+#[derive(Debug, Clone)]
+struct Var {
+    /// Pre-calculated offset from the end of the [`Scope`], if any.
+    index: Option<NonZeroUsize>,
+    /// Position of the variable.
+    pos: Position,
+    /// The original [`Expr::Variable`], used when the variable must be searched.
+    expr: Expr,
+}
+
+impl Var {
+    fn new(expr: &Expr) -> Self {
+        match expr {
+            Expr::Variable(x) => Self {
+                index: if x.1.is_none() { x.0 } else { None },
+                pos: (x.2).pos,
+                expr: expr.clone(),
+            },
+            _ => unreachable!("Expr::Variable expected, but gets {:?}", expr),
+        }
+    }
+}
+
+/// A variable definition.
+#[derive(Debug, Clone)]
+struct LetDef {
+    name: ImmutableString,
+    access: AccessMode,
+    export: bool,
+}
+
+/// An assignment (or op-assignment) to a variable.
+#[derive(Debug, Clone)]
+struct AssignDef {
+    var: Var,
+    op: Cow<'static, str>,
+    op_pos: Position,
+}
+
+/// A call to a non-qualified function.
+#[derive(Debug, Clone)]
+struct CallDef {
+    name: Cow<'static, str>,
+    hash_script: Option<NonZeroU64>,
+    hash_fn: NativeFnHashPrefix,
+    def_value: Option<Dynamic>,
+    /// First register holding the arguments.
+    args: Reg,
+    /// Number of arguments.
+    argc: usize,
+    /// The first argument, if it is a variable that can be passed by reference.
+    first: Option<Var>,
+    pos: Position,
+}
+
+/// A jump table for a `switch` statement.
+#[derive(Debug, Clone)]
+struct SwitchDef {
+    table: HashMap<u64, usize, StraightHasherBuilder>,
+    default: usize,
+    /// Position of the expression to match.
+    pos: Position,
+}
+
+/// The loop variable of a `for` statement.
+#[derive(Debug, Clone)]
+struct ForDef {
+    name: ImmutableString,
+    /// Position of the expression to iterate over.
+    pos: Position,
+}
+
+/// Jump targets of a loop.
+#[derive(Debug, Clone)]
+struct LoopDef {
+    /// Number of blocks open at the start of each iteration.
+    depth: usize,
+    /// Start of the next iteration.
+    next: usize,
+    /// Exit of the loop.
+    exit: usize,
+}
+
+/// A bytecode instruction.
+#[derive(Debug, Clone)]
+enum Instr {
+    /// Count one operation.
+    Tick(Position),
+    /// Load a constant into a register.
+    Const(Reg, Dynamic),
+    /// Load a constant into a register, checking data size limits.
+    ConstChecked(Reg, Box<(Dynamic, Position)>),
+    /// Load the value of a variable into a register.
+    Load(Reg, Box<Var>),
+    /// Evaluate an expression with the tree-walking evaluator, optionally inside a loop.
+    Eval(Reg, Box<Expr>, Option<usize>),
+    /// Execute a statement with the tree-walking evaluator, optionally inside a loop.
+    Exec(Reg, Box<Stmt>, Option<usize>),
+    /// Define a new variable with the value in a register.
+    Let(Reg, Box<LetDef>),
+    /// Assign the value in a register to a variable.
+    Assign(Reg, Box<AssignDef>),
+    /// Call a function, placing the result into a register.
+    Call(Reg, Box<CallDef>),
+    /// Make sure that the value in a register is a `bool`.
+    AsBool(Reg, Position),
+    /// Jump unconditionally.
+    Jump(usize),
+    /// Jump if the `bool` value in a register matches.
+    JumpIf(Reg, bool, usize, Position),
+    /// Jump according to the hash of the value in a register.
+    Switch(Reg, Box<SwitchDef>),
+    /// Start a new statements block.
+    EnterBlock,
+    /// End the current statements block.
+    LeaveBlock,
+    /// Start iterating over the value in a register.
+    ForInit(Reg, Box<ForDef>),
+    /// Set the loop variable to the next value, or jump out of the loop when done.
+    ForNext(usize, Position),
+    /// Finish iterating.
+    ForEnd,
+    /// Break out of a loop.
+    Break(usize),
+    /// Continue with the next iteration of a loop.
+    Continue(usize),
+    /// Return the value in a register.
+    Return(Reg),
+    /// Throw the value in a register as an exception.
+    Throw(Reg, Position),
+}
+
+/// A list of statements compiled into bytecode.
+#[derive(Debug, Clone)]
+pub(crate) struct Bytecode {
+    code: Vec<Instr>,
+    loops: Vec<LoopDef>,
+    num_regs: usize,
+}
+
+impl Bytecode {
+    /// Compile a list of statements, the result being the value of the last statement.
+    pub fn compile<'a>(statements: impl IntoIterator<Item = &'a Stmt>) -> Self {
+        let statements: StaticVec<_> = statements.into_iter().collect();
+        let mut compiler = Compiler::default();
+        let result = compiler.alloc();
+        compiler.compile_stmts(statements.iter().cloned(), Some(result));
+        compiler.finish()
+    }
+    /// Compile a single statement (e.g. a function body).
+    #[cfg(not(feature = "no_function"))]
+    pub fn compile_stmt(stmt: &Stmt) -> Self {
+        let mut compiler = Compiler::default();
+        let result = compiler.alloc();
+        compiler.compile_stmt(stmt, Some(result));
+        compiler.finish()
+    }
+}
+
+/// A cache holding the [`Bytecode`] of a script-defined function body, or of the statements of an
+/// [`AST`][crate::AST], compiled on first use.
+///
+/// Cloning the cache yields an empty one.
+#[derive(Debug, Default)]
+pub(crate) struct BytecodeCache(
+    #[cfg(not(feature = "sync"))] crate::stdlib::cell::RefCell<Option<Shared<Bytecode>>>,
+    #[cfg(feature = "sync")] crate::stdlib::sync::RwLock<Option<Shared<Bytecode>>>,
+);
+
+impl Clone for BytecodeCache {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Default::default()
+    }
+}
+
+impl BytecodeCache {
+    /// Get the cached [`Bytecode`], compiling it with `compile` if not yet cached.
+    fn get_or_insert_with(&self, compile: impl FnOnce() -> Bytecode) -> Shared<Bytecode> {
+        #[cfg(not(feature = "sync"))]
+        let mut cache = self.0.borrow_mut();
+        #[cfg(feature = "sync")]
+        let mut cache = self.0.write().unwrap();
+
+        cache.get_or_insert_with(|| compile().into()).clone()
+    }
+    /// Get the cached [`Bytecode`], compiling the function body if not yet cached.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn get_or_compile(&self, body: &Stmt) -> Shared<Bytecode> {
+        self.get_or_insert_with(|| Bytecode::compile_stmt(body))
+    }
+    /// Get the cached [`Bytecode`], compiling the list of statements if not yet cached.
+    #[inline(always)]
+    pub fn get_or_compile_statements(&self, statements: &[Stmt]) -> Shared<Bytecode> {
+        self.get_or_insert_with(|| Bytecode::compile(statements))
+    }
+    /// Discard the cached [`Bytecode`], if any.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        #[cfg(not(feature = "sync"))]
+        let cache = self.0.get_mut();
+        #[cfg(feature = "sync")]
+        let cache = self.0.get_mut().unwrap();
+
+        *cache = None;
+    }
+}
+
+/// Is this function name handled specially by [`Engine::make_function_call`]?
+fn is_special_fn(name: &str) -> bool {
+    match name {
+        KEYWORD_FN_PTR | KEYWORD_FN_PTR_CURRY | KEYWORD_FN_PTR_CALL | KEYWORD_IS_DEF_VAR
+        | KEYWORD_EVAL => true,
+        #[cfg(not(feature = "no_closure"))]
+        crate::engine::KEYWORD_IS_SHARED => true,
+        _ => false,
+    }
+}
+
+/// State of the bytecode compiler.
+#[derive(Debug, Default)]
+struct Compiler {
+    code: Vec<Instr>,
+    loops: Vec<LoopDef>,
+    /// Stack of the loops currently being compiled.
+    loop_stack: Vec<usize>,
+    /// Number of blocks currently open.
+    depth: usize,
+    /// Next free register.
+    next_reg: Reg,
+    /// Total number of registers used.
+    num_regs: usize,
+}
+
+impl Compiler {
+    fn finish(self) -> Bytecode {
+        Bytecode {
+            code: self.code,
+            loops: self.loops,
+            num_regs: self.num_regs,
+        }
+    }
+    fn alloc(&mut self) -> Reg {
+        let reg = self.next_reg;
+        self.next_reg += 1;
+        self.num_regs = self.num_regs.max(self.next_reg);
+        reg
+    }
+    fn emit(&mut self, instr: Instr) -> usize {
+        self.code.push(instr);
+        self.code.len() - 1
+    }
+    fn here(&self) -> usize {
+        self.code.len()
+    }
+    fn patch(&mut self, at: usize, target: usize) {
+        match &mut self.code[at] {
+            Instr::Jump(to) | Instr::JumpIf(_, _, to, _) | Instr::ForNext(to, _) => *to = target,
+            instr => unreachable!("jump instruction expected, but gets {:?}", instr),
+        }
+    }
+    fn unit(&mut self, dst: Option<Reg>) {
+        if let Some(dst) = dst {
+            self.emit(Instr::Const(dst, Dynamic::UNIT));
+        }
+    }
+    fn current_loop(&self) -> Option<usize> {
+        self.loop_stack.last().cloned()
+    }
+    fn enter_loop(&mut self, next: usize) {
+        self.loops.push(LoopDef {
+            depth: self.depth,
+            next,
+            exit: 0,
+        });
+        self.loop_stack.push(self.loops.len() - 1);
+    }
+    fn leave_loop(&mut self) {
+        let index = self.loop_stack.pop().unwrap();
+        self.loops[index].exit = self.here();
+    }
+
+    /// Compile a list of statements, placing the value of the last statement into `dst`.
+    fn compile_stmts<'a>(
+        &mut self,
+        statements: impl ExactSizeIterator<Item = &'a Stmt>,
+        dst: Option<Reg>,
+    ) {
+        let len = statements.len();
+
+        if len == 0 {
+            self.unit(dst);
+        }
+
+        statements
+            .enumerate()
+            .for_each(|(i, stmt)| self.compile_stmt(stmt, if i == len - 1 { dst } else { None }));
+    }
+
+    /// Compile a statements block, placing its value into `dst`.
+    fn compile_block(&mut self, statements: &[Stmt], dst: Option<Reg>) {
+        self.emit(Instr::EnterBlock);
+        self.depth += 1;
+        self.compile_stmts(statements.iter(), dst);
+        self.depth -= 1;
+        self.emit(Instr::LeaveBlock);
+    }
+
+    /// Compile a statement, placing its value into `dst` if required.
+    fn compile_stmt(&mut self, stmt: &Stmt, dst: Option<Reg>) {
+        let mark = self.next_reg;
+
+        match stmt {
+            Stmt::Noop(pos) => {
+                self.emit(Instr::Tick(*pos));
+                self.unit(dst);
+            }
+
+            Stmt::Expr(expr) => {
+                self.emit(Instr::Tick(stmt.position()));
+                let dst = dst.unwrap_or_else(|| self.alloc());
+                self.compile_expr(expr, dst);
+            }
+
+            // var op= rhs
+            Stmt::Assignment(x, op_pos) if x.0.get_variable_access(false).is_some() => {
+                let (lhs_expr, op, rhs_expr) = x.as_ref();
+                self.emit(Instr::Tick(stmt.position()));
+                let value = self.alloc();
+                self.compile_expr(rhs_expr, value);
+                self.emit(Instr::Assign(
+                    value,
+                    Box::new(AssignDef {
+                        var: Var::new(lhs_expr),
+                        op: op.clone(),
+                        op_pos: *op_pos,
+                    }),
+                ));
+                self.unit(dst);
+            }
+
+            Stmt::Block(statements, pos) => {
+                self.emit(Instr::Tick(*pos));
+                self.compile_block(statements, dst);
+            }
+
+            Stmt::If(expr, x, pos) => {
+                let (if_block, else_block) = x.as_ref();
+                self.emit(Instr::Tick(*pos));
+                let guard = self.alloc();
+                self.compile_expr(expr, guard);
+                let to_else = self.emit(Instr::JumpIf(guard, false, 0, expr.position()));
+                self.next_reg = mark;
+
+                self.compile_stmt(if_block, dst);
+
+                if else_block.is_some() || dst.is_some() {
+                    let to_end = self.emit(Instr::Jump(0));
+                    self.patch(to_else, self.here());
+                    if let Some(stmt) = else_block {
+                        self.compile_stmt(stmt, dst);
+                    } else {
+                        self.unit(dst);
+                    }
+                    self.patch(to_end, self.here());
+                } else {
+                    self.patch(to_else, self.here());
+                }
+            }
+
+            Stmt::Switch(match_expr, x, pos) => {
+                let (table, def_stmt) = x.as_ref();
+                self.emit(Instr::Tick(*pos));
+                let value = self.alloc();
+                self.compile_expr(match_expr, value);
+                let switch = self.emit(Instr::Switch(
+                    value,
+                    Box::new(SwitchDef {
+                        table: HashMap::with_capacity_and_hasher(
+                            table.len(),
+                            StraightHasherBuilder,
+                        ),
+                        default: 0,
+                        pos: match_expr.position(),
+                    }),
+                ));
+                self.next_reg = mark;
+
+                let mut jump_table =
+                    HashMap::with_capacity_and_hasher(table.len(), StraightHasherBuilder);
+                let mut to_end = Vec::with_capacity(table.len());
+
+                for (&hash, stmt) in table.iter() {
+                    jump_table.insert(hash, self.here());
+                    self.compile_stmt(stmt, dst);
+                    to_end.push(self.emit(Instr::Jump(0)));
+                }
+
+                let default = self.here();
+                if let Some(stmt) = def_stmt {
+                    self.compile_stmt(stmt, dst);
+                } else {
+                    self.unit(dst);
+                }
+
+                let end = self.here();
+                to_end.into_iter().for_each(|at| self.patch(at, end));
+
+                match &mut self.code[switch] {
+                    Instr::Switch(_, x) => {
+                        x.table = jump_table;
+                        x.default = default;
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            Stmt::While(expr, body, pos) => {
+                self.emit(Instr::Tick(*pos));
+                let top = self.here();
+                let guard = self.alloc();
+                self.compile_expr(expr, guard);
+                let to_exit = self.emit(Instr::JumpIf(guard, false, 0, expr.position()));
+                self.next_reg = mark;
+
+                self.enter_loop(top);
+                self.compile_stmt(body, None);
+                self.emit(Instr::Jump(top));
+                self.leave_loop();
+
+                self.patch(to_exit, self.here());
+                self.unit(dst);
+            }
+
+            Stmt::Do(body, expr, is_while, pos) => {
+                self.emit(Instr::Tick(*pos));
+                let top = self.here();
+
+                // `continue` skips the loop condition
+                self.enter_loop(top);
+                self.compile_stmt(body, None);
+                let guard = self.alloc();
+                self.compile_expr(expr, guard);
+                self.emit(Instr::JumpIf(guard, *is_while, top, expr.position()));
+                self.next_reg = mark;
+                self.leave_loop();
+
+                self.unit(dst);
+            }
+
+            Stmt::For(expr, x, pos) => {
+                let (name, body) = x.as_ref();
+                self.emit(Instr::Tick(*pos));
+                let iter_obj = self.alloc();
+                self.compile_expr(expr, iter_obj);
+
+                self.emit(Instr::EnterBlock);
+                self.depth += 1;
+                self.emit(Instr::ForInit(
+                    iter_obj,
+                    Box::new(ForDef {
+                        name: name.into(),
+                        pos: expr.position(),
+                    }),
+                ));
+                self.next_reg = mark;
+
+                let top = self.emit(Instr::ForNext(0, body.position()));
+                self.enter_loop(top);
+                self.compile_stmt(body, None);
+                self.emit(Instr::Jump(top));
+                self.leave_loop();
+
+                let exit = self.emit(Instr::ForEnd);
+                self.patch(top, exit);
+                self.depth -= 1;
+                self.emit(Instr::LeaveBlock);
+
+                self.unit(dst);
+            }
+
+            Stmt::Continue(pos) | Stmt::Break(pos) if self.current_loop().is_some() => {
+                let index = self.current_loop().unwrap();
+                self.emit(Instr::Tick(*pos));
+                self.emit(match stmt {
+                    Stmt::Continue(_) => Instr::Continue(index),
+                    _ => Instr::Break(index),
+                });
+            }
+
+            Stmt::Return((return_type, pos), expr, _) => {
+                self.emit(Instr::Tick(*pos));
+                let value = self.alloc();
+                if let Some(expr) = expr {
+                    self.compile_expr(expr, value);
+                } else {
+                    self.unit(Some(value));
+                }
+                self.emit(match return_type {
+                    ReturnType::Return => Instr::Return(value),
+                    ReturnType::Exception => Instr::Throw(value, *pos),
+                });
+            }
+
+            Stmt::Let(var_def, expr, export, pos) | Stmt::Const(var_def, expr, export, pos) => {
+                let access = match stmt {
+                    Stmt::Let(_, _, _, _) => AccessMode::ReadWrite,
+                    _ => AccessMode::ReadOnly,
+                };
+                self.emit(Instr::Tick(*pos));
+                let value = self.alloc();
+                if let Some(expr) = expr {
+                    self.compile_expr(expr, value);
+                } else {
+                    self.unit(Some(value));
+                }
+                self.emit(Instr::Let(
+                    value,
+                    Box::new(LetDef {
+                        name: var_def.name.clone(),
+                        access,
+                        export: *export,
+                    }),
+                ));
+                self.unit(dst);
+            }
+
+            // Everything else is delegated to the tree-walking evaluator
+            _ => {
+                let dst = dst.unwrap_or_else(|| self.alloc());
+                let in_loop = self.current_loop();
+                self.emit(Instr::Exec(dst, Box::new(stmt.clone()), in_loop));
+            }
+        }
+
+        self.next_reg = mark;
+    }
+
+    /// Compile an expression, placing its value into `dst`.
+    fn compile_expr(&mut self, expr: &Expr, dst: Reg) {
+        let mark = self.next_reg;
+
+        match expr {
+            Expr::IntegerConstant(_, pos)
+            | Expr::BoolConstant(_, pos)
+            | Expr::CharConstant(_, pos)
+            | Expr::FnPointer(_, pos)
+            | Expr::Unit(pos) => {
+                self.emit(Instr::Tick(*pos));
+                self.emit(Instr::Const(dst, expr.get_constant_value().unwrap()));
+            }
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(_, pos) => {
+                self.emit(Instr::Tick(*pos));
+                self.emit(Instr::Const(dst, expr.get_constant_value().unwrap()));
+            }
+            Expr::StringConstant(_, pos) | Expr::DynamicConstant(_, pos) => {
+                self.emit(Instr::Tick(*pos));
+                let value = expr.get_constant_value().unwrap();
+                self.emit(Instr::ConstChecked(dst, Box::new((value, *pos))));
+            }
+
+            Expr::Variable(x) => {
+                self.emit(Instr::Tick((x.2).pos));
+                self.emit(Instr::Load(dst, Box::new(Var::new(expr))));
+            }
+
+            Expr::Stmt(statements, pos) => {
+                self.emit(Instr::Tick(*pos));
+                self.compile_block(statements, Some(dst));
+            }
+
+            // Normal function call
+            Expr::FnCall(x, pos)
+                if x.namespace.is_none() && !x.capture && !is_special_fn(&x.name) =>
+            {
+                let FnCallExpr {
+                    name,
+                    hash_script,
+                    args,
+                    def_value,
+                    ..
+                } = x.as_ref();
+
+                self.emit(Instr::Tick(*pos));
+
+                let base = self.next_reg;
+                args.iter().for_each(|_| {
+                    self.alloc();
+                });
+
+                // If the first argument is a variable, it is passed by reference
+                let first = args
+                    .first()
+                    .filter(|expr| expr.get_variable_access(false).is_some())
+                    .map(Var::new);
+                let skip = if first.is_some() { 1 } else { 0 };
+
+                args.iter()
+                    .enumerate()
+                    .skip(skip)
+                    .for_each(|(i, expr)| self.compile_expr(expr, base + i));
+
+                self.emit(Instr::Call(
+                    dst,
+                    Box::new(CallDef {
+                        name: name.clone(),
+                        hash_script: *hash_script,
+                        hash_fn: NativeFnHashPrefix::new(name),
+                        def_value: def_value.clone(),
+                        args: base,
+                        argc: args.len(),
+                        first,
+                        pos: *pos,
+                    }),
+                ));
+            }
+
+            Expr::And(x, pos) | Expr::Or(x, pos) => {
+                let is_and = match expr {
+                    Expr::And(_, _) => true,
+                    _ => false,
+                };
+                self.emit(Instr::Tick(*pos));
+                self.compile_expr(&x.lhs, dst);
+                self.emit(Instr::AsBool(dst, x.lhs.position()));
+                // Short-circuit
+                let to_end = self.emit(Instr::JumpIf(dst, !is_and, 0, x.lhs.position()));
+                self.compile_expr(&x.rhs, dst);
+                self.emit(Instr::AsBool(dst, x.rhs.position()));
+                self.patch(to_end, self.here());
+            }
+
+            // Everything else is delegated to the tree-walking evaluator
+            _ => {
+                let in_loop = self.current_loop();
+                self.emit(Instr::Eval(dst, Box::new(expr.clone()), in_loop));
+            }
+        }
+
+        self.next_reg = mark;
+    }
+}
+
+/// A statements block opened by the virtual machine.
+#[derive(Debug)]
+struct Block {
+    scope_len: usize,
+    mods_len: usize,
+    always_search: bool,
+}
+
+/// Runtime frames of the virtual machine.
+#[derive(Default)]
+struct Frames {
+    blocks: Vec<Block>,
+    iterators: Vec<(Box<dyn Iterator<Item = Dynamic>>, usize)>,
+}
+
+impl Frames {
+    fn enter(&mut self, scope: &Scope, mods: &Imports, state: &mut State) {
+        self.blocks.push(Block {
+            scope_len: scope.len(),
+            mods_len: mods.len(),
+            always_search: state.always_search,
+        });
+        state.scope_level += 1;
+    }
+    fn leave(&mut self, scope: &mut Scope, mods: &mut Imports, state: &mut State) {
+        let block = self.blocks.pop().unwrap();
+
+        scope.rewind(block.scope_len);
+        if mods.len() != block.mods_len {
+            // If imports list is modified, clear the functions lookup cache
+            state.functions_cache.clear();
+        }
+        mods.truncate(block.mods_len);
+        state.scope_level -= 1;
+
+        // The impact of new local variables goes away at the end of a block
+        // because any new variables introduced will go out of scope
+        state.always_search = block.always_search;
+    }
+    fn unwind(&mut self, scope: &mut Scope, mods: &mut Imports, state: &mut State, depth: usize) {
+        while self.blocks.len() > depth {
+            self.leave(scope, mods, state);
+        }
+    }
+}
+
+impl Engine {
+    /// Run [`Bytecode`] on the virtual machine.
+    ///
+    /// `return` statements are mapped to return values.
+    pub(crate) fn run_bytecode(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        bytecode: &Bytecode,
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let mut frames: Frames = Default::default();
+
+        let result = self.run_bytecode_raw(
+            scope,
+            mods,
+            state,
+            lib,
+            this_ptr,
+            bytecode,
+            &mut frames,
+            level,
+        );
+
+        // Close all blocks left open by an early return or an error
+        frames.unwind(scope, mods, state, 0);
+
+        result
+    }
+
+    /// Search for the target of a variable access, using the pre-calculated offset if possible.
+    fn search_var<'s>(
+        &self,
+        scope: &'s mut Scope,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        this_ptr: &'s mut Option<&mut Dynamic>,
+        var: &Var,
+    ) -> Result<Target<'s>, Box<EvalAltResult>> {
+        match var.index {
+            Some(index) if !state.always_search && self.resolve_var.is_none() => {
+                let offset = scope.len() - index.get();
+                Ok(scope.get_mut_by_index(offset).into())
+            }
+            _ => self
+                .search_namespace(scope, mods, state, lib, this_ptr, &var.expr)
+                .map(|(target, _)| target),
+        }
+    }
+
+    /// Handle an error from the tree-walking evaluator, jumping to the appropriate target
+    /// if it is a `break` or `continue` inside a loop.
+    fn catch_loop_break(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut State,
+        bytecode: &Bytecode,
+        frames: &mut Frames,
+        in_loop: Option<usize>,
+        err: Box<EvalAltResult>,
+    ) -> Result<usize, Box<EvalAltResult>> {
+        match *err {
+            EvalAltResult::LoopBreak(is_break, _) if in_loop.is_some() => {
+                let def = &bytecode.loops[in_loop.unwrap()];
+                frames.unwind(scope, mods, state, def.depth);
+                Ok(if is_break { def.exit } else { def.next })
+            }
+            _ => Err(err),
+        }
+    }
+
+    /// Execute instructions until the end of the [`Bytecode`] or a `return`.
+    fn run_bytecode_raw(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        bytecode: &Bytecode,
+        frames: &mut Frames,
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let code = &bytecode.code;
+        let mut regs: StaticVec<Dynamic> = (0..bytecode.num_regs).map(|_| Dynamic::UNIT).collect();
+        let mut pc = 0;
+
+        while let Some(instr) = code.get(pc) {
+            pc += 1;
+
+            match instr {
+                Instr::Tick(pos) => self.inc_operations(state, *pos)?,
+
+                Instr::Const(dst, value) => regs[*dst] = value.clone(),
+
+                Instr::ConstChecked(dst, x) => {
+                    regs[*dst] = self.check_data_size(Ok(x.0.clone()), x.1)?;
+                }
+
+                Instr::Load(dst, var) => {
+                    let value = self
+                        .search_var(scope, mods, state, lib, this_ptr, var)?
+                        .take_or_clone();
+                    regs[*dst] = self.check_data_size(Ok(value), var.pos)?;
+                }
+
+                Instr::Eval(dst, expr, in_loop) => {
+                    match self.eval_expr(scope, mods, state, lib, this_ptr, expr, level) {
+                        Ok(value) => regs[*dst] = value,
+                        Err(err) => {
+                            pc = self.catch_loop_break(
+                                scope, mods, state, bytecode, frames, *in_loop, err,
+                            )?
+                        }
+                    }
+                }
+
+                Instr::Exec(dst, stmt, in_loop) => {
+                    match self.eval_stmt(scope, mods, state, lib, this_ptr, stmt, level) {
+                        Ok(value) => regs[*dst] = value,
+                        Err(err) => {
+                            pc = self.catch_loop_break(
+                                scope, mods, state, bytecode, frames, *in_loop, err,
+                            )?
+                        }
+                    }
+                }
+
+                Instr::Let(value, def) => {
+                    let value = mem::take(&mut regs[*value]).flatten();
+
+                    let (var_name, _alias): (Cow<'_, str>, _) = if state.is_global() {
+                        (
+                            def.name.to_string().into(),
+                            if def.export {
+                                Some(def.name.clone())
+                            } else {
+                                None
+                            },
+                        )
+                    } else {
+                        (unsafe_cast_var_name_to_lifetime(&def.name).into(), None)
+                    };
+
+                    // Constants at global level are also global constants
+                    #[cfg(not(feature = "no_module"))]
+                    if state.is_global() && def.access.is_read_only() {
+                        state.push_global_constant(def.name.clone(), value.clone());
+                    }
+
+                    scope.push_dynamic_value(var_name, def.access, value);
+
+                    #[cfg(not(feature = "no_module"))]
+                    if let Some(alias) = _alias {
+                        scope.add_entry_alias(scope.len() - 1, alias);
+                    }
+                }
+
+                Instr::Assign(value, def) => {
+                    let value = mem::take(&mut regs[*value]).flatten();
+                    let lhs_ptr = self.search_var(scope, mods, state, lib, this_ptr, &def.var)?;
+
+                    self.eval_op_assignment(
+                        mods,
+                        state,
+                        lib,
+                        &def.var.expr,
+                        &def.op,
+                        def.op_pos,
+                        lhs_ptr,
+                        def.var.pos,
+                        value,
+                        level,
+                    )?;
+                }
+
+                Instr::Call(dst, def) => {
+                    let value = {
+                        let arg_values = &mut regs[def.args..def.args + def.argc];
+                        let mut is_ref = false;
+
+                        let mut args: StaticVec<_> = if let Some(ref var) = def.first {
+                            // func(x, ...) -> x.func(...)
+                            let mut target =
+                                self.search_var(scope, mods, state, lib, this_ptr, var)?;
+
+                            if target.as_ref().is_read_only() {
+                                target = target.into_owned();
+                            }
+
+                            self.inc_operations(state, var.pos)?;
+
+                            let (first, rest) = arg_values.split_first_mut().unwrap();
+
+                            if target.is_shared() || target.is_value() {
+                                *first = target.take_or_clone().flatten();
+                                once(first).chain(rest.iter_mut()).collect()
+                            } else {
+                                // Turn it into a method call only if the object is not shared and not a simple value
+                                is_ref = true;
+                                once(target.take_ref().unwrap())
+                                    .chain(rest.iter_mut())
+                                    .collect()
+                            }
+                        } else {
+                            arg_values.iter_mut().collect()
+                        };
+
+                        // Check for data race.
+                        if cfg!(not(feature = "no_closure")) {
+                            ensure_no_data_race(&def.name, &args, is_ref)?;
+                        }
+
+                        let hash_fn = def.hash_fn.complete(args.iter().map(|a| a.type_id()));
+
+                        let (value, _) = self.exec_fn_call_with_hash(
+                            mods,
+                            state,
+                            lib,
+                            &def.name,
+                            hash_fn,
+                            def.hash_script,
+                            args.as_mut(),
+                            is_ref,
+                            false,
+                            false,
+                            def.pos,
+                            None,
+                            def.def_value.as_ref(),
+                            level,
+                        )?;
+                        value
+                    };
+
+                    regs[*dst] = self.check_data_size(Ok(value), def.pos)?;
+                }
+
+                Instr::AsBool(reg, pos) => {
+                    let value = regs[*reg]
+                        .as_bool()
+                        .map_err(|err| self.make_type_mismatch_err::<bool>(err, *pos))?;
+                    regs[*reg] = value.into();
+                }
+
+                Instr::Jump(target) => pc = *target,
+
+                Instr::JumpIf(reg, condition, target, pos) => {
+                    let value = regs[*reg]
+                        .as_bool()
+                        .map_err(|err| self.make_type_mismatch_err::<bool>(err, *pos))?;
+                    if value == *condition {
+                        pc = *target;
+                    }
+                }
+
+                Instr::Switch(reg, x) => {
+                    let value = mem::take(&mut regs[*reg]);
+                    let value = self.switch_key(mods, state, lib, value, x.pos)?;
+
+                    let hasher = &mut get_hasher();
+                    value.hash(hasher);
+                    let hash = hasher.finish();
+                    pc = x.table.get(&hash).cloned().unwrap_or(x.default);
+                }
+
+                Instr::EnterBlock => frames.enter(scope, mods, state),
+
+                Instr::LeaveBlock => frames.leave(scope, mods, state),
+
+                Instr::ForInit(reg, def) => {
+                    let iter_obj = mem::take(&mut regs[*reg]);
+                    let iter_type = iter_obj.type_id();
+
+                    let func = self
+                        .global_namespace
+                        .get_iter(iter_type)
+                        .or_else(|| {
+                            self.global_modules
+                                .iter()
+                                .find_map(|m| m.get_iter(iter_type))
+                        })
+                        .or_else(|| mods.get_iter(iter_type))
+                        .ok_or_else(|| Box::new(EvalAltResult::ErrorFor(def.pos)))?;
+
+                    // Add the loop variable
+                    scope.push(unsafe_cast_var_name_to_lifetime(&def.name), ());
+                    frames.iterators.push((func(iter_obj), scope.len() - 1));
+                }
+
+                Instr::ForNext(exit, pos) => {
+                    let (iter, index) = frames.iterators.last_mut().unwrap();
+
+                    if let Some(iter_value) = iter.next() {
+                        let loop_var = scope.get_mut_by_index(*index);
+                        let value = iter_value.flatten();
+
+                        if cfg!(not(feature = "no_closure")) && loop_var.is_shared() {
+                            *loop_var.write_lock().unwrap() = value;
+                        } else {
+                            *loop_var = value;
+                        }
+
+                        self.inc_operations(state, *pos)?;
+                    } else {
+                        pc = *exit;
+                    }
+                }
+
+                Instr::ForEnd => {
+                    frames.iterators.pop();
+                }
+
+                Instr::Break(index) | Instr::Continue(index) => {
+                    let def = &bytecode.loops[*index];
+                    frames.unwind(scope, mods, state, def.depth);
+                    pc = match instr {
+                        Instr::Break(_) => def.exit,
+                        _ => def.next,
+                    };
+                }
+
+                Instr::Return(reg) => return Ok(mem::take(&mut regs[*reg])),
+
+                Instr::Throw(reg, pos) => {
+                    return EvalAltResult::ErrorRuntime(mem::take(&mut regs[*reg]), *pos).into()
+                }
+            }
+        }
+
+        Ok(regs.get_mut(0).map(mem::take).unwrap_or_default())
+    }
+}
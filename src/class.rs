@@ -0,0 +1,90 @@
+//! Module defining instances of script-defined classes.
+
+use crate::ast::ClassDef;
+use crate::stdlib::fmt;
+use crate::{Dynamic, Map, Shared};
+
+/// An instance of a class defined in script via a `class` declaration.
+///
+/// Instances are created by calling the constructor of the class, e.g. `new Point(1, 2)`.
+/// They hold the values of the fields declared by the class, and share its methods.
+///
+/// Not available under `no_function` or `no_object`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{ClassInstance, Engine, INT};
+///
+/// let engine = Engine::new();
+///
+/// let point = engine.eval::<ClassInstance>(
+///     "
+///         class Point {
+///             let x = 0;
+///             let y = 0;
+///
+///             fn new(x, y) { this.x = x; this.y = y; }
+///         }
+///
+///         new Point(40, 2)
+///     ",
+/// )?;
+///
+/// assert_eq!(point.class_name(), "Point");
+/// assert_eq!(point.get("x").unwrap().as_int().unwrap(), 40);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClassInstance {
+    /// Definition of the class.
+    class: Shared<ClassDef>,
+    /// Values of the fields.
+    fields: Map,
+}
+
+impl fmt::Debug for ClassInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.class.name)?;
+        f.write_str(" ")?;
+        f.debug_map().entries(self.iter_fields()).finish()
+    }
+}
+
+impl ClassInstance {
+    /// Create a new instance of a class, with the fields set to the values provided.
+    #[inline(always)]
+    pub(crate) fn new(class: Shared<ClassDef>, fields: Map) -> Self {
+        Self { class, fields }
+    }
+    /// Get the definition of the class.
+    #[inline(always)]
+    pub(crate) fn class(&self) -> &Shared<ClassDef> {
+        &self.class
+    }
+    /// Get the name of the class.
+    #[inline(always)]
+    pub fn class_name(&self) -> &str {
+        &self.class.name
+    }
+    /// Get the value of a field.
+    #[inline(always)]
+    pub fn get(&self, field: &str) -> Option<&Dynamic> {
+        self.fields.get(field)
+    }
+    /// Get a mutable reference to the value of a field.
+    #[inline(always)]
+    pub fn get_mut(&mut self, field: &str) -> Option<&mut Dynamic> {
+        self.fields.get_mut(field)
+    }
+    /// Get an iterator over the fields and their values, in the order they are declared.
+    #[inline(always)]
+    pub fn iter_fields(&self) -> impl Iterator<Item = (&str, &Dynamic)> {
+        self.class
+            .fields
+            .iter()
+            .filter_map(move |(name, _)| self.fields.get(name).map(|value| (name.as_str(), value)))
+    }
+}
@@ -420,6 +420,10 @@ pub(crate) fn map_std_type_name(name: &str) -> &str {
         if name == type_name::<Instant>() {
             return "timestamp";
         }
+        #[cfg(feature = "regex")]
+        if name == type_name::<regex::Regex>() {
+            return "Regex";
+        }
 
         name
     }
@@ -4,8 +4,8 @@ use crate::ast::{Expr, FnCallExpr, Ident, ReturnType, Stmt};
 use crate::dynamic::{map_std_type_name, AccessMode, Union, Variant};
 use crate::fn_call::run_builtin_op_assignment;
 use crate::fn_native::{
-    CallableFunction, IteratorFn, OnDebugCallback, OnPrintCallback, OnProgressCallback,
-    OnVarCallback,
+    CallableFunction, FnConversions, FnConvert, IteratorFn, OnDebugCallback, OnPrintCallback,
+    OnProgressCallback, OnVarCallback,
 };
 use crate::module::NamespaceRef;
 use crate::optimize::OptimizationLevel;
@@ -26,8 +26,8 @@ use crate::stdlib::{
 use crate::syntax::CustomSyntax;
 use crate::utils::{get_hasher, StraightHasherBuilder};
 use crate::{
-    calc_native_fn_hash, Dynamic, EvalAltResult, FnPtr, ImmutableString, Module, Position, Scope,
-    Shared, StaticVec,
+    calc_native_fn_hash, calc_script_fn_hash, Dynamic, EvalAltResult, FnPtr, ImmutableString,
+    Module, Position, Scope, Shared, StaticVec,
 };
 
 #[cfg(not(feature = "no_index"))]
@@ -42,6 +42,10 @@ use crate::Map;
 #[cfg(not(feature = "no_object"))]
 pub const TYPICAL_MAP_SIZE: usize = 8; // Small maps are typical
 
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+use crate::ClassInstance;
+
 /// _(INTERNALS)_ A stack of imported [modules][Module].
 /// Exported under the `internals` feature only.
 ///
@@ -170,6 +174,82 @@ impl FromIterator<(ImmutableString, Shared<Module>)> for Imports {
     }
 }
 
+/// _(INTERNALS)_ Chain of `import` statements currently being resolved.
+/// Exported under the `internals` feature only.
+///
+/// Each entry holds the source of the importing script, the path imported and the position of
+/// the `import` statement.
+///
+/// # Volatile Data Structure
+///
+/// This type is volatile and may change.
+#[cfg(not(feature = "no_module"))]
+pub type ImportChain = StaticVec<(Option<ImmutableString>, ImmutableString, Position)>;
+
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_std"))]
+thread_local! {
+    /// [`ImportChain`] handed over to the evaluation of a [module][Module] being resolved on this thread.
+    ///
+    /// Module resolvers evaluate [modules][Module] with fresh states, so the chain of the importing
+    /// evaluation is passed on through here for the duration of the resolution.
+    static HANDED_OVER_IMPORT_CHAIN: crate::stdlib::cell::RefCell<Option<ImportChain>> = Default::default();
+}
+
+/// Restores the previously handed-over [`ImportChain`] when dropped, even when unwinding.
+#[cfg(not(feature = "no_module"))]
+struct ImportChainHandOver<'e> {
+    engine: &'e Engine,
+    previous: Option<ImportChain>,
+}
+
+#[cfg(not(feature = "no_module"))]
+impl Drop for ImportChainHandOver<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        self.engine
+            .with_handed_over_import_chain(|chain| *chain = previous);
+    }
+}
+
+/// Find the cycle formed by adding an `import` to an [`ImportChain`], if any.
+///
+/// A relative path (starting with `./` or `../`) is only considered the same when imported from
+/// the same source.
+#[cfg(not(feature = "no_module"))]
+fn find_import_cycle(
+    chain: &ImportChain,
+    source: Option<&ImmutableString>,
+    path: &ImmutableString,
+    pos: Position,
+) -> Option<Vec<(String, Position)>> {
+    let is_relative = path.starts_with("./") || path.starts_with("../");
+
+    chain
+        .iter()
+        .position(|(src, p, _)| p == path && (!is_relative || src.as_ref() == source))
+        .map(|index| {
+            chain[index..]
+                .iter()
+                .map(|(_, p, pos)| (p.to_string(), *pos))
+                .chain(once((path.to_string(), pos)))
+                .collect()
+        })
+}
+
+/// Report a circular import directly instead of wrapped inside the modules in the cycle.
+#[cfg(not(feature = "no_module"))]
+fn unwrap_circular_import(err: Box<EvalAltResult>) -> Box<EvalAltResult> {
+    match *err {
+        EvalAltResult::ErrorInModule(name, err, pos) => match *unwrap_circular_import(err) {
+            err @ EvalAltResult::ErrorCircularImport(_, _) => err.into(),
+            err => EvalAltResult::ErrorInModule(name, err.into(), pos).into(),
+        },
+        err => err.into(),
+    }
+}
+
 #[cfg(not(feature = "unchecked"))]
 #[cfg(debug_assertions)]
 #[cfg(not(feature = "no_function"))]
@@ -194,8 +274,21 @@ pub const MAX_EXPR_DEPTH: usize = 128;
 #[cfg(not(debug_assertions))]
 pub const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
 
+#[cfg(not(feature = "no_function"))]
+pub const MAX_INLINE_FN_SIZE: usize = 16;
+
+pub const MAX_CONVERSION_CHAIN: usize = 2;
+
+#[cfg(feature = "regex")]
+pub const MAX_REGEX_CACHE_SIZE: usize = 64;
+
 pub const KEYWORD_PRINT: &str = "print";
 pub const KEYWORD_DEBUG: &str = "debug";
+pub const FN_TO_STRING: &str = "to_string";
+pub const FN_TO_DEBUG: &str = "to_debug";
+pub const FN_HASH: &str = "hash";
+pub const FN_FORMAT: &str = "format";
+pub const FN_FMT: &str = "fmt";
 pub const KEYWORD_TYPE_OF: &str = "type_of";
 pub const KEYWORD_EVAL: &str = "eval";
 pub const KEYWORD_FN_PTR: &str = "Fn";
@@ -205,6 +298,9 @@ pub const KEYWORD_FN_PTR_CURRY: &str = "curry";
 pub const KEYWORD_IS_SHARED: &str = "is_shared";
 pub const KEYWORD_IS_DEF_VAR: &str = "is_def_var";
 pub const KEYWORD_THIS: &str = "this";
+#[cfg(not(feature = "no_module"))]
+pub const KEYWORD_GLOBAL: &str = "global";
+pub const FN_ENUM_TYPE: &str = "enum_type";
 #[cfg(not(feature = "no_object"))]
 pub const FN_GET: &str = "get$";
 #[cfg(not(feature = "no_object"))]
@@ -215,6 +311,10 @@ pub const FN_IDX_GET: &str = "index$get$";
 pub const FN_IDX_SET: &str = "index$set$";
 #[cfg(not(feature = "no_function"))]
 pub const FN_ANONYMOUS: &str = "anon$";
+#[cfg(not(feature = "no_module"))]
+pub const IMPORTED_MEMBERS: &str = "import$";
+#[cfg(not(feature = "no_module"))]
+pub const EXPORTED_MEMBERS: &str = "export$";
 #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
 pub const OP_EQUALS: &str = "==";
 
@@ -519,10 +619,19 @@ pub struct State {
     /// Embedded module resolver.
     #[cfg(not(feature = "no_module"))]
     pub resolver: Option<Shared<crate::module::resolvers::StaticModuleResolver>>,
-    /// Cached lookup values for function hashes.
+    /// Chain of `import` statements being resolved, including those of the evaluations that
+    /// imported the current script as a [module][Module].
+    #[cfg(not(feature = "no_module"))]
+    pub import_chain: ImportChain,
+    /// Global constants, accessible via the `global` namespace.
+    ///
+    /// They are shared with the states of `eval` calls, and only copied when modified.
+    #[cfg(not(feature = "no_module"))]
+    pub global_constants: Shared<StaticVec<(ImmutableString, Dynamic)>>,
+    /// Cached lookup values for function hashes, with the conversions of arguments (if any).
     pub functions_cache: HashMap<
         NonZeroU64,
-        Option<(CallableFunction, Option<ImmutableString>)>,
+        Option<(CallableFunction, Option<ImmutableString>, FnConversions)>,
         StraightHasherBuilder,
     >,
 }
@@ -533,6 +642,30 @@ impl State {
     pub fn is_global(&self) -> bool {
         self.scope_level == 0
     }
+    /// Add a global constant, accessible via the `global` namespace.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub fn push_global_constant(&mut self, name: impl Into<ImmutableString>, value: Dynamic) {
+        crate::fn_native::shared_make_mut(&mut self.global_constants).push((name.into(), value));
+    }
+    /// Add all the constants in a [`Scope`] as global constants.
+    #[cfg(not(feature = "no_module"))]
+    pub fn push_global_constants(&mut self, scope: &Scope) {
+        scope
+            .iter_raw()
+            .filter(|(_, constant, _)| *constant)
+            .for_each(|(name, _, value)| self.push_global_constant(name, value.flatten_clone()));
+    }
+    /// Find a global constant by name.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub fn find_global_constant(&self, name: &str) -> Option<&Dynamic> {
+        self.global_constants
+            .iter()
+            .rev()
+            .find(|(n, _)| n.as_str() == name)
+            .map(|(_, value)| value)
+    }
 }
 
 /// _(INTERNALS)_ A type containing all the limits imposed by the [`Engine`].
@@ -579,6 +712,11 @@ pub struct Limits {
     /// Not available under `no_object`.
     #[cfg(not(feature = "no_object"))]
     pub max_map_size: Option<NonZeroUsize>,
+    /// Maximum size (in bytes) of a compiled regular expression.
+    ///
+    /// Only available under `regex`.
+    #[cfg(feature = "regex")]
+    pub max_regex_size: Option<NonZeroUsize>,
 }
 
 /// Context of a script evaluation process.
@@ -692,6 +830,17 @@ pub struct Engine {
 
     /// A hashmap mapping type names to pretty-print names.
     pub(crate) type_names: HashMap<String, String>,
+    /// A hashmap mapping types to the types they can be converted into, in order of registration.
+    pub(crate) type_conversions: HashMap<TypeId, StaticVec<(TypeId, Shared<FnConvert>)>>,
+    /// Max number of conversions chained to convert a function argument.
+    pub(crate) max_conversion_chain: usize,
+    /// Compiled regular expressions, keyed by pattern.
+    #[cfg(feature = "regex")]
+    pub(crate) regex_cache: crate::fn_native::Locked<HashMap<ImmutableString, regex::Regex>>,
+    /// [`ImportChain`] handed over to the evaluation of a [module][Module] being resolved.
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(feature = "no_std")]
+    handed_over_import_chain: crate::stdlib::cell::RefCell<Option<ImportChain>>,
 
     /// A hashset containing symbols to disable.
     pub(crate) disabled_symbols: HashSet<String>,
@@ -711,6 +860,9 @@ pub struct Engine {
 
     /// Optimize the AST after compilation.
     pub(crate) optimization_level: OptimizationLevel,
+    /// Max size (in number of expression nodes) of script-defined functions to inline.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) max_inline_fn_size: usize,
 
     /// Max limits.
     #[cfg(not(feature = "unchecked"))]
@@ -718,6 +870,14 @@ pub struct Engine {
 
     /// Disable doc-comments?
     pub(crate) disable_doc_comments: bool,
+
+    /// Make non-exported module variables visible to module functions?
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) private_module_vars: bool,
+
+    /// Backend used to run scripts.
+    #[cfg(feature = "bytecode")]
+    pub(crate) execution_backend: crate::ExecutionBackend,
 }
 
 impl fmt::Debug for Engine {
@@ -752,6 +912,19 @@ pub fn make_setter(id: &str) -> String {
     format!("{}{}", FN_SET, id)
 }
 
+/// Does the value have fields that are accessed directly as properties, i.e. is it an object map
+/// or an instance of a script-defined class?
+#[cfg(not(feature = "no_object"))]
+#[inline(always)]
+fn has_fields(value: &Dynamic) -> bool {
+    #[cfg(not(feature = "no_function"))]
+    if value.is::<ClassInstance>() {
+        return true;
+    }
+
+    value.is::<Map>()
+}
+
 /// Is this function an anonymous function?
 #[cfg(not(feature = "no_function"))]
 #[inline(always)]
@@ -801,7 +974,9 @@ pub fn search_imports(
     } else {
         mods.find(root)
             .map(|n| mods.get(n).expect("invalid index in Imports"))
-            .ok_or_else(|| EvalAltResult::ErrorModuleNotFound(root.to_string(), *pos))?
+            .ok_or_else(|| {
+                EvalAltResult::ErrorModuleNotFound(root.to_string(), Default::default(), *pos)
+            })?
     })
 }
 
@@ -826,6 +1001,13 @@ impl Engine {
             module_resolver: Box::new(crate::module::resolvers::DummyModuleResolver::new()),
 
             type_names: Default::default(),
+            type_conversions: Default::default(),
+            max_conversion_chain: MAX_CONVERSION_CHAIN,
+            #[cfg(feature = "regex")]
+            regex_cache: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            #[cfg(feature = "no_std")]
+            handed_over_import_chain: Default::default(),
             disabled_symbols: Default::default(),
             custom_keywords: Default::default(),
             custom_syntax: Default::default(),
@@ -846,6 +1028,8 @@ impl Engine {
             } else {
                 OptimizationLevel::Simple
             },
+            #[cfg(not(feature = "no_function"))]
+            max_inline_fn_size: MAX_INLINE_FN_SIZE,
 
             #[cfg(not(feature = "unchecked"))]
             limits: Limits {
@@ -862,9 +1046,17 @@ impl Engine {
                 max_array_size: None,
                 #[cfg(not(feature = "no_object"))]
                 max_map_size: None,
+                #[cfg(feature = "regex")]
+                max_regex_size: None,
             },
 
             disable_doc_comments: false,
+
+            #[cfg(not(feature = "no_module"))]
+            private_module_vars: false,
+
+            #[cfg(feature = "bytecode")]
+            execution_backend: Default::default(),
         };
 
         engine.register_global_module(StandardPackage::new().as_shared_module());
@@ -888,6 +1080,13 @@ impl Engine {
             module_resolver: Box::new(crate::module::resolvers::DummyModuleResolver::new()),
 
             type_names: Default::default(),
+            type_conversions: Default::default(),
+            max_conversion_chain: MAX_CONVERSION_CHAIN,
+            #[cfg(feature = "regex")]
+            regex_cache: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            #[cfg(feature = "no_std")]
+            handed_over_import_chain: Default::default(),
             disabled_symbols: Default::default(),
             custom_keywords: Default::default(),
             custom_syntax: Default::default(),
@@ -903,6 +1102,8 @@ impl Engine {
             } else {
                 OptimizationLevel::Simple
             },
+            #[cfg(not(feature = "no_function"))]
+            max_inline_fn_size: MAX_INLINE_FN_SIZE,
 
             #[cfg(not(feature = "unchecked"))]
             limits: Limits {
@@ -919,9 +1120,17 @@ impl Engine {
                 max_array_size: None,
                 #[cfg(not(feature = "no_object"))]
                 max_map_size: None,
+                #[cfg(feature = "regex")]
+                max_regex_size: None,
             },
 
             disable_doc_comments: false,
+
+            #[cfg(not(feature = "no_module"))]
+            private_module_vars: false,
+
+            #[cfg(feature = "bytecode")]
+            execution_backend: Default::default(),
         }
     }
 
@@ -938,6 +1147,21 @@ impl Engine {
     ) -> Result<(Target<'s>, Position), Box<EvalAltResult>> {
         match expr {
             Expr::Variable(v) => match v.as_ref() {
+                // Global constant
+                #[cfg(not(feature = "no_module"))]
+                (_, Some((_, modules)), Ident { name, pos }) if modules.is_global() => {
+                    let mut target =
+                        state.find_global_constant(name).cloned().ok_or_else(|| {
+                            EvalAltResult::ErrorVariableNotFound(
+                                format!("{}{}", modules, name),
+                                *pos,
+                            )
+                        })?;
+
+                    // Global constants are read-only
+                    target.set_access_mode(AccessMode::ReadOnly);
+                    Ok((target.into(), *pos))
+                }
                 // Qualified variable
                 (_, Some((hash_var, modules)), Ident { name, pos }) => {
                     let module = search_imports(mods, state, modules)?;
@@ -1171,7 +1395,7 @@ impl Engine {
                         unreachable!("function call in dot chain should not be namespace-qualified")
                     }
                     // {xxx:map}.id = ???
-                    Expr::Property(x) if target_val.is::<Map>() && new_val.is_some() => {
+                    Expr::Property(x) if has_fields(target_val) && new_val.is_some() => {
                         let Ident { name, pos } = &x.2;
                         let index = name.clone().into();
                         let mut val = self.get_indexed_mut(
@@ -1184,7 +1408,7 @@ impl Engine {
                         Ok((Default::default(), true))
                     }
                     // {xxx:map}.id
-                    Expr::Property(x) if target_val.is::<Map>() => {
+                    Expr::Property(x) if has_fields(target_val) => {
                         let Ident { name, pos } = &x.2;
                         let index = name.clone().into();
                         let val = self.get_indexed_mut(
@@ -1215,7 +1439,7 @@ impl Engine {
                         .map(|(v, _)| (v, false))
                     }
                     // {xxx:map}.sub_lhs[expr] | {xxx:map}.sub_lhs.expr
-                    Expr::Index(x, x_pos) | Expr::Dot(x, x_pos) if target_val.is::<Map>() => {
+                    Expr::Index(x, x_pos) | Expr::Dot(x, x_pos) if has_fields(target_val) => {
                         let mut val = match &x.lhs {
                             Expr::Property(p) => {
                                 let Ident { name, pos } = &p.2;
@@ -1544,6 +1768,31 @@ impl Engine {
                 }
             }
 
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_object"))]
+            Dynamic(Union::Variant(_, _)) if target.is::<ClassInstance>() => {
+                // val_obj[field]
+                let obj = target.downcast_mut::<ClassInstance>().unwrap();
+                let field = idx
+                    .read_lock::<ImmutableString>()
+                    .ok_or_else(|| self.make_type_mismatch_err::<ImmutableString>("", idx_pos))?;
+
+                // Classes have a fixed set of fields
+                if obj.get(&field).is_none() {
+                    return EvalAltResult::ErrorDotExpr(
+                        format!(
+                            "Unknown property '{}' for class '{}'",
+                            *field,
+                            obj.class_name()
+                        ),
+                        idx_pos,
+                    )
+                    .into();
+                }
+
+                Ok(obj.get_mut(&field).unwrap().into())
+            }
+
             #[cfg(not(feature = "no_object"))]
             Dynamic(Union::Map(map, _)) => {
                 // val_map[idx]
@@ -1882,6 +2131,187 @@ impl Engine {
         result
     }
 
+    /// Assign a value to a variable, optionally via a compound assignment operator (e.g. `+=`).
+    pub(crate) fn eval_op_assignment(
+        &self,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        lhs_expr: &Expr,
+        op: &str,
+        op_pos: Position,
+        mut lhs_ptr: Target,
+        pos: Position,
+        mut rhs_val: Dynamic,
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        if !lhs_ptr.is_ref() {
+            return EvalAltResult::ErrorAssignmentToConstant(
+                lhs_expr.get_variable_access(false).unwrap().to_string(),
+                pos,
+            )
+            .into();
+        }
+
+        self.inc_operations(state, pos)?;
+
+        if lhs_ptr.as_ref().is_read_only() {
+            // Assignment to constant variable
+            Err(Box::new(EvalAltResult::ErrorAssignmentToConstant(
+                lhs_expr.get_variable_access(false).unwrap().to_string(),
+                pos,
+            )))
+        } else if op.is_empty() {
+            // Normal assignment
+            if cfg!(not(feature = "no_closure")) && lhs_ptr.is_shared() {
+                *lhs_ptr.as_mut().write_lock::<Dynamic>().unwrap() = rhs_val;
+            } else {
+                *lhs_ptr.as_mut() = rhs_val;
+            }
+            Ok(Dynamic::UNIT)
+        } else {
+            // Op-assignment - in order of precedence:
+            // 1) Script-defined operator - map to `var = var op rhs`
+            // 2) Native registered overriding function
+            // 3) Built-in implementation
+            // 4) Map to `var = var op rhs`
+
+            let arg_types = [lhs_ptr.as_mut().type_id(), rhs_val.type_id()];
+
+            // Script-defined operator?  Check the argument types first because it is cheaper.
+            #[cfg(not(feature = "no_function"))]
+            let overloaded = crate::fn_call::can_overload_operator(
+                &op[..op.len() - 1],
+                arg_types.iter().cloned(),
+            ) && self.has_override(
+                Some(mods),
+                lib,
+                None,
+                calc_script_fn_hash(empty(), &op[..op.len() - 1], 2),
+                false,
+            );
+            #[cfg(feature = "no_function")]
+            let overloaded = false;
+
+            // Qualifiers (none) + function name + number of arguments + argument `TypeId`'s.
+            let hash_fn = calc_native_fn_hash(empty(), op, arg_types.iter().cloned()).unwrap();
+
+            match if overloaded {
+                None
+            } else {
+                self.global_namespace
+                    .get_fn(hash_fn, false)
+                    .map(|f| (f, None))
+                    .or_else(|| {
+                        self.global_modules
+                            .iter()
+                            .find_map(|m| m.get_fn(hash_fn, false).map(|f| (f, m.id_raw())))
+                    })
+                    .or_else(|| mods.get_fn(hash_fn))
+            } {
+                // op= function registered as method
+                Some((func, source)) if func.is_method() => {
+                    let mut lock_guard;
+                    let lhs_ptr_inner;
+
+                    if cfg!(not(feature = "no_closure")) && lhs_ptr.is_shared() {
+                        lock_guard = lhs_ptr.as_mut().write_lock::<Dynamic>().unwrap();
+                        lhs_ptr_inner = lock_guard.deref_mut();
+                    } else {
+                        lhs_ptr_inner = lhs_ptr.as_mut();
+                    }
+
+                    let args = &mut [lhs_ptr_inner, &mut rhs_val];
+
+                    // Overriding exact implementation
+                    let source = source.or_else(|| state.source.as_ref()).map(|s| s.as_str());
+                    if func.is_plugin_fn() {
+                        func.get_plugin_fn()
+                            .call((self, op, source, &*mods, lib).into(), args)?;
+                    } else {
+                        func.get_native_fn()((self, op, source, &*mods, lib).into(), args)?;
+                    }
+                }
+                // Built-in op-assignment function
+                _ if !overloaded
+                    && run_builtin_op_assignment(op, lhs_ptr.as_mut(), &rhs_val)?.is_some() => {}
+                // Not built-in: expand to `var = var op rhs`
+                _ => {
+                    let op = &op[..op.len() - 1]; // extract operator without =
+                    let hash_script = calc_script_fn_hash(empty(), op, 2);
+
+                    // Clone the LHS value
+                    let args = &mut [&mut lhs_ptr.as_mut().clone(), &mut rhs_val];
+
+                    // Run function
+                    let (value, _) = self.exec_fn_call(
+                        mods,
+                        state,
+                        lib,
+                        op,
+                        hash_script,
+                        args,
+                        false,
+                        false,
+                        false,
+                        op_pos,
+                        None,
+                        None,
+                        level,
+                    )?;
+
+                    let value = value.flatten();
+
+                    if cfg!(not(feature = "no_closure")) && lhs_ptr.is_shared() {
+                        *lhs_ptr.as_mut().write_lock::<Dynamic>().unwrap() = value;
+                    } else {
+                        *lhs_ptr.as_mut() = value;
+                    }
+                }
+            }
+            Ok(Dynamic::UNIT)
+        }
+    }
+
+    /// Map the value of a `switch` statement to the key matched against its cases.
+    ///
+    /// A custom type with an `enum_type` function registered for it (e.g. a Rust enum exported
+    /// via `#[export_enum]`) is matched by the result of that function, usually the variant name.
+    pub(crate) fn switch_key(
+        &self,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        value: Dynamic,
+        pos: Position,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let mut value = value.flatten();
+
+        if !value.is_variant() {
+            return Ok(value);
+        }
+
+        let hash_fn = calc_native_fn_hash(empty(), FN_ENUM_TYPE, once(value.type_id()));
+
+        match hash_fn {
+            Some(hash_fn) if self.has_override(Some(mods), lib, Some(hash_fn), None, false) => self
+                .call_native_fn(
+                    mods,
+                    state,
+                    lib,
+                    FN_ENUM_TYPE,
+                    hash_fn,
+                    &mut [&mut value],
+                    false,
+                    false,
+                    pos,
+                    None,
+                )
+                .map(|(v, _)| v),
+            _ => Ok(value),
+        }
+    }
+
     /// Evaluate a statement.
     ///
     /// # Safety
@@ -1910,111 +2340,15 @@ impl Engine {
             // var op= rhs
             Stmt::Assignment(x, op_pos) if x.0.get_variable_access(false).is_some() => {
                 let (lhs_expr, op, rhs_expr) = x.as_ref();
-                let mut rhs_val = self
+                let rhs_val = self
                     .eval_expr(scope, mods, state, lib, this_ptr, rhs_expr, level)?
                     .flatten();
-                let (mut lhs_ptr, pos) =
+                let (lhs_ptr, pos) =
                     self.search_namespace(scope, mods, state, lib, this_ptr, lhs_expr)?;
 
-                if !lhs_ptr.is_ref() {
-                    return EvalAltResult::ErrorAssignmentToConstant(
-                        lhs_expr.get_variable_access(false).unwrap().to_string(),
-                        pos,
-                    )
-                    .into();
-                }
-
-                self.inc_operations(state, pos)?;
-
-                if lhs_ptr.as_ref().is_read_only() {
-                    // Assignment to constant variable
-                    Err(Box::new(EvalAltResult::ErrorAssignmentToConstant(
-                        lhs_expr.get_variable_access(false).unwrap().to_string(),
-                        pos,
-                    )))
-                } else if op.is_empty() {
-                    // Normal assignment
-                    if cfg!(not(feature = "no_closure")) && lhs_ptr.is_shared() {
-                        *lhs_ptr.as_mut().write_lock::<Dynamic>().unwrap() = rhs_val;
-                    } else {
-                        *lhs_ptr.as_mut() = rhs_val;
-                    }
-                    Ok(Dynamic::UNIT)
-                } else {
-                    // Op-assignment - in order of precedence:
-                    // 1) Native registered overriding function
-                    // 2) Built-in implementation
-                    // 3) Map to `var = var op rhs`
-
-                    // Qualifiers (none) + function name + number of arguments + argument `TypeId`'s.
-                    let arg_types = once(lhs_ptr.as_mut().type_id()).chain(once(rhs_val.type_id()));
-                    let hash_fn = calc_native_fn_hash(empty(), op, arg_types).unwrap();
-
-                    match self
-                        .global_namespace
-                        .get_fn(hash_fn, false)
-                        .map(|f| (f, None))
-                        .or_else(|| {
-                            self.global_modules
-                                .iter()
-                                .find_map(|m| m.get_fn(hash_fn, false).map(|f| (f, m.id_raw())))
-                        })
-                        .or_else(|| mods.get_fn(hash_fn))
-                    {
-                        // op= function registered as method
-                        Some((func, source)) if func.is_method() => {
-                            let mut lock_guard;
-                            let lhs_ptr_inner;
-
-                            if cfg!(not(feature = "no_closure")) && lhs_ptr.is_shared() {
-                                lock_guard = lhs_ptr.as_mut().write_lock::<Dynamic>().unwrap();
-                                lhs_ptr_inner = lock_guard.deref_mut();
-                            } else {
-                                lhs_ptr_inner = lhs_ptr.as_mut();
-                            }
-
-                            let args = &mut [lhs_ptr_inner, &mut rhs_val];
-
-                            // Overriding exact implementation
-                            let source =
-                                source.or_else(|| state.source.as_ref()).map(|s| s.as_str());
-                            if func.is_plugin_fn() {
-                                func.get_plugin_fn()
-                                    .call((self, op.as_ref(), source, &*mods, lib).into(), args)?;
-                            } else {
-                                func.get_native_fn()(
-                                    (self, op.as_ref(), source, &*mods, lib).into(),
-                                    args,
-                                )?;
-                            }
-                        }
-                        // Built-in op-assignment function
-                        _ if run_builtin_op_assignment(op, lhs_ptr.as_mut(), &rhs_val)?
-                            .is_some() => {}
-                        // Not built-in: expand to `var = var op rhs`
-                        _ => {
-                            let op = &op[..op.len() - 1]; // extract operator without =
-
-                            // Clone the LHS value
-                            let args = &mut [&mut lhs_ptr.as_mut().clone(), &mut rhs_val];
-
-                            // Run function
-                            let (value, _) = self.exec_fn_call(
-                                mods, state, lib, op, None, args, false, false, false, *op_pos,
-                                None, None, level,
-                            )?;
-
-                            let value = value.flatten();
-
-                            if cfg!(not(feature = "no_closure")) && lhs_ptr.is_shared() {
-                                *lhs_ptr.as_mut().write_lock::<Dynamic>().unwrap() = value;
-                            } else {
-                                *lhs_ptr.as_mut() = value;
-                            }
-                        }
-                    }
-                    Ok(Dynamic::UNIT)
-                }
+                self.eval_op_assignment(
+                    mods, state, lib, lhs_expr, op, *op_pos, lhs_ptr, pos, rhs_val, level,
+                )
             }
 
             // lhs op= rhs
@@ -2034,9 +2368,11 @@ impl Engine {
                         &mut rhs_val,
                     ];
 
+                    let hash = calc_script_fn_hash(empty(), op, 2);
+
                     Some(
                         self.exec_fn_call(
-                            mods, state, lib, op, None, args, false, false, false, *op_pos, None,
+                            mods, state, lib, op, hash, args, false, false, false, *op_pos, None,
                             None, level,
                         )
                         .map(|(v, _)| (v, rhs_expr.position()))?,
@@ -2095,9 +2431,11 @@ impl Engine {
             Stmt::Switch(match_expr, x, _) => {
                 let (table, def_stmt) = x.as_ref();
 
+                let value = self.eval_expr(scope, mods, state, lib, this_ptr, match_expr, level)?;
+                let value = self.switch_key(mods, state, lib, value, match_expr.position())?;
+
                 let hasher = &mut get_hasher();
-                self.eval_expr(scope, mods, state, lib, this_ptr, match_expr, level)?
-                    .hash(hasher);
+                value.hash(hasher);
                 let hash = hasher.finish();
 
                 if let Some(stmt) = table.get(&hash) {
@@ -2318,6 +2656,13 @@ impl Engine {
                 } else {
                     (unsafe_cast_var_name_to_lifetime(&var_def.name).into(), None)
                 };
+
+                // Constants at global level are also global constants
+                #[cfg(not(feature = "no_module"))]
+                if state.is_global() && entry_type.is_read_only() {
+                    state.push_global_constant(var_def.name.clone(), val.clone());
+                }
+
                 scope.push_dynamic_value(var_name, entry_type, val);
 
                 #[cfg(not(feature = "no_module"))]
@@ -2329,52 +2674,91 @@ impl Engine {
 
             // Import statement
             #[cfg(not(feature = "no_module"))]
-            Stmt::Import(expr, alias, _pos) => {
-                // Guard against too many modules
-                #[cfg(not(feature = "unchecked"))]
-                if state.modules >= self.max_modules() {
-                    return EvalAltResult::ErrorTooManyModules(*_pos).into();
+            Stmt::Import(expr, alias, pos) => {
+                let module =
+                    self.eval_import_path(scope, mods, state, lib, this_ptr, expr, *pos, level)?;
+
+                if let Some(name_def) = alias {
+                    if !module.is_indexed() {
+                        // Index the module (making a clone copy if necessary) if it is not indexed
+                        let mut module = crate::fn_native::shared_take_or_clone(module);
+                        module.build_index();
+                        mods.push(name_def.name.clone(), module);
+                    } else {
+                        mods.push(name_def.name.clone(), module);
+                    }
+                    // When imports list is modified, clear the functions lookup cache
+                    state.functions_cache.clear();
                 }
 
-                if let Some(path) = self
-                    .eval_expr(scope, mods, state, lib, this_ptr, &expr, level)?
-                    .try_cast::<ImmutableString>()
-                {
-                    use crate::ModuleResolver;
+                Ok(Dynamic::UNIT)
+            }
 
-                    let expr_pos = expr.position();
+            // Import members statement
+            #[cfg(not(feature = "no_module"))]
+            Stmt::ImportMembers(x, export, pos) => {
+                use crate::FnNamespace;
 
-                    let module = state
-                        .resolver
-                        .as_ref()
-                        .and_then(|r| match r.resolve(self, &path, expr_pos) {
-                            Ok(m) => return Some(Ok(m)),
-                            Err(err) => match *err {
-                                EvalAltResult::ErrorModuleNotFound(_, _) => None,
-                                _ => return Some(Err(err)),
-                            },
-                        })
-                        .unwrap_or_else(|| self.module_resolver.resolve(self, &path, expr_pos))?;
-
-                    if let Some(name_def) = alias {
-                        if !module.is_indexed() {
-                            // Index the module (making a clone copy if necessary) if it is not indexed
-                            let mut module = crate::fn_native::shared_take_or_clone(module);
-                            module.build_index();
-                            mods.push(name_def.name.clone(), module);
+                let (expr, list) = x.as_ref();
+                let module =
+                    self.eval_import_path(scope, mods, state, lib, this_ptr, expr, *pos, level)?;
+
+                let mut members = Module::new();
+
+                for (Ident { name, pos: id_pos }, alias) in list.iter() {
+                    let alias = alias.as_ref().map(|x| &x.name).unwrap_or_else(|| name);
+                    let mut found = false;
+
+                    // Variables become constants
+                    if let Some(value) = module.get_var(name) {
+                        let var_name: Cow<'_, str> = if state.is_global() {
+                            alias.to_string().into()
                         } else {
-                            mods.push(name_def.name.clone(), module);
+                            unsafe_cast_var_name_to_lifetime(alias).into()
+                        };
+                        scope.push_dynamic_value(var_name, AccessMode::ReadOnly, value);
+                        if *export {
+                            scope.add_entry_alias(scope.len() - 1, alias.clone());
                         }
-                        // When imports list is modified, clear the functions lookup cache
-                        state.functions_cache.clear();
+                        found = true;
                     }
 
-                    state.modules += 1;
+                    // Functions are callable without qualifiers
+                    if members.copy_fn_from(&module, name, alias, FnNamespace::Global) {
+                        found = true;
+                    }
 
-                    Ok(Dynamic::UNIT)
-                } else {
-                    Err(self.make_type_mismatch_err::<ImmutableString>("", expr.position()))
+                    // Sub-modules are imported under the alias
+                    if let Some(sub_module) = module.get_sub_module_shared(name) {
+                        mods.push(alias.clone(), sub_module);
+                        found = true;
+                    }
+
+                    if !found {
+                        return EvalAltResult::ErrorVariableNotFound(name.to_string(), *id_pos)
+                            .into();
+                    }
+                }
+
+                if !members.is_empty() {
+                    members.build_index();
+                    mods.push(
+                        if *export {
+                            EXPORTED_MEMBERS
+                        } else {
+                            IMPORTED_MEMBERS
+                        },
+                        members,
+                    );
                 }
+
+                // When imports list is modified, clear the functions lookup cache
+                state.functions_cache.clear();
+                // The new variables and modules shift the pre-calculated offsets,
+                // so always search for them by name from now on
+                state.always_search = true;
+
+                Ok(Dynamic::UNIT)
             }
 
             // Export statement
@@ -2411,11 +2795,108 @@ impl Engine {
         self.check_data_size(result, stmt.position())
     }
 
+    /// Evaluate the path of an `import` statement and resolve it into a [`Module`].
+    #[cfg(not(feature = "no_module"))]
+    fn eval_import_path(
+        &self,
+        scope: &mut Scope,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        this_ptr: &mut Option<&mut Dynamic>,
+        expr: &Expr,
+        _pos: Position,
+        level: usize,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        use crate::ModuleResolver;
+
+        // Guard against too many modules
+        #[cfg(not(feature = "unchecked"))]
+        if state.modules >= self.max_modules() {
+            return EvalAltResult::ErrorTooManyModules(_pos).into();
+        }
+
+        let path = self
+            .eval_expr(scope, mods, state, lib, this_ptr, expr, level)?
+            .try_cast::<ImmutableString>()
+            .ok_or_else(|| self.make_type_mismatch_err::<ImmutableString>("", expr.position()))?;
+
+        let expr_pos = expr.position();
+
+        // Guard against circular imports
+        if let Some(cycle) =
+            find_import_cycle(&state.import_chain, state.source.as_ref(), &path, expr_pos)
+        {
+            return EvalAltResult::ErrorCircularImport(cycle, expr_pos).into();
+        }
+
+        state
+            .import_chain
+            .push((state.source.clone(), path.clone(), expr_pos));
+
+        let result = {
+            let _hand_over = self.hand_over_import_chain(state.import_chain.clone());
+            let source = state.source.as_ref().map(|s| s.as_str());
+
+            state
+                .resolver
+                .as_ref()
+                .and_then(|r| match r.resolve(self, &path, expr_pos) {
+                    Ok(m) => return Some(Ok(m)),
+                    Err(err) => match *err {
+                        EvalAltResult::ErrorModuleNotFound(_, _, _) => None,
+                        _ => return Some(Err(err)),
+                    },
+                })
+                .unwrap_or_else(|| {
+                    self.module_resolver
+                        .resolve_from_source(self, source, &path, expr_pos)
+                })
+        };
+
+        state.import_chain.pop();
+
+        let module = result.map_err(unwrap_circular_import)?;
+
+        state.modules += 1;
+
+        Ok(module)
+    }
+    /// Run a function with the [`ImportChain`] handed over to the evaluation of [modules][Module]
+    /// being resolved.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    fn with_handed_over_import_chain<T>(&self, f: impl FnOnce(&mut Option<ImportChain>) -> T) -> T {
+        #[cfg(not(feature = "no_std"))]
+        return HANDED_OVER_IMPORT_CHAIN.with(|chain| f(&mut chain.borrow_mut()));
+        #[cfg(feature = "no_std")]
+        return f(&mut self.handed_over_import_chain.borrow_mut());
+    }
+    /// Hand over an [`ImportChain`] to the evaluation of [modules][Module] resolved until the
+    /// returned guard is dropped.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    fn hand_over_import_chain(&self, chain: ImportChain) -> ImportChainHandOver<'_> {
+        let previous = self.with_handed_over_import_chain(|c| c.replace(chain));
+
+        ImportChainHandOver {
+            engine: self,
+            previous,
+        }
+    }
+    /// Take the [`ImportChain`] handed over by an `import` statement currently being resolved, if any.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub(crate) fn take_handed_over_import_chain(&self) -> ImportChain {
+        self.with_handed_over_import_chain(|chain| chain.take())
+            .unwrap_or_default()
+    }
+
     /// Check a result to ensure that the data size is within allowable limit.
     /// [`Position`] in [`EvalAltResult`] may be None and should be set afterwards.
     #[cfg(feature = "unchecked")]
     #[inline(always)]
-    fn check_data_size(
+    pub(crate) fn check_data_size(
         &self,
         result: Result<Dynamic, Box<EvalAltResult>>,
         _pos: Position,
@@ -2425,7 +2906,7 @@ impl Engine {
 
     /// Check a result to ensure that the data size is within allowable limit.
     #[cfg(not(feature = "unchecked"))]
-    fn check_data_size(
+    pub(crate) fn check_data_size(
         &self,
         result: Result<Dynamic, Box<EvalAltResult>>,
         pos: Position,
@@ -2570,6 +3051,21 @@ impl Engine {
             .unwrap_or_else(|| map_std_type_name(name))
     }
 
+    /// Get the type name of a value, as returned by `type_of`.
+    ///
+    /// This is the mapped name of its type, except for instances of script-defined classes, which
+    /// are named after their classes.
+    #[inline(always)]
+    pub(crate) fn map_value_type_name<'a>(&'a self, value: &'a Dynamic) -> &'a str {
+        #[cfg(not(feature = "no_function"))]
+        #[cfg(not(feature = "no_object"))]
+        if let Some(obj) = value.downcast_ref::<ClassInstance>() {
+            return obj.class_name();
+        }
+
+        self.map_type_name(value.type_name())
+    }
+
     /// Make a `Box<`[`EvalAltResult<ErrorMismatchDataType>`][EvalAltResult::ErrorMismatchDataType]`>`.
     #[inline(always)]
     pub(crate) fn make_type_mismatch_err<T>(&self, typ: &str, pos: Position) -> Box<EvalAltResult> {
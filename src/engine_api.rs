@@ -2,12 +2,14 @@
 
 use crate::dynamic::Variant;
 use crate::engine::{EvalContext, Imports, State};
-use crate::fn_native::{FnCallArgs, SendSync};
+use crate::fn_native::{FnCallArgs, FnConvert, SendSync};
 use crate::optimize::OptimizationLevel;
 use crate::stdlib::{
     any::{type_name, TypeId},
     boxed::Box,
-    format,
+    fmt, format,
+    hash::Hash,
+    ops::{Add, Div, Mul, Neg, Sub},
     string::String,
     vec::Vec,
 };
@@ -156,6 +158,61 @@ impl Engine {
         self.global_namespace.set_iterable::<T>();
         self
     }
+    /// Register an implicit conversion from one type into another with the [`Engine`].
+    ///
+    /// When no native Rust function matches the types of the arguments of a function call
+    /// (and it is not a built-in operator), arguments are converted into other types, possibly
+    /// via a chain of conversions, to find a matching function.  The maximum length of a chain is
+    /// set via [`set_max_conversion_chain`][Engine::set_max_conversion_chain].
+    ///
+    /// Calls with fewer conversions are preferred.  Among those with the same number of
+    /// conversions, later arguments are converted first, then shorter chains and, finally,
+    /// conversions registered earlier are preferred.
+    ///
+    /// Conversions only apply to native Rust functions.  A converted first argument is a copy,
+    /// so a method modifying it does not modify the original value.
+    ///
+    /// If there is an existing conversion between the same types, it is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// struct Meters(f64);
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn, INT};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// # #[cfg(not(feature = "no_object"))]
+    /// engine.register_type::<Meters>();
+    ///
+    /// engine
+    ///     .register_fn("double", |m: Meters| Meters(m.0 * 2.0))
+    ///     .register_conversion::<INT, Meters>(|x| Meters(x as f64));
+    ///
+    /// assert_eq!(engine.eval::<Meters>("double(21)")?, Meters(42.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn register_conversion<A: Variant + Clone, B: Variant + Clone>(
+        &mut self,
+        func: impl Fn(A) -> B + SendSync + 'static,
+    ) -> &mut Self {
+        let to_type = TypeId::of::<B>();
+        let convert: Shared<FnConvert> =
+            Shared::new(move |value: Dynamic| Dynamic::from(func(value.cast::<A>())));
+
+        let rules = self.type_conversions.entry(TypeId::of::<A>()).or_default();
+
+        match rules.iter_mut().find(|(t, _)| *t == to_type) {
+            Some((_, f)) => *f = convert,
+            None => rules.push((to_type, convert)),
+        }
+        self
+    }
     /// Register a getter function for a member of a registered type with the [`Engine`].
     ///
     /// The function signature must start with `&mut self` and not `&self`.
@@ -705,6 +762,177 @@ impl Engine {
         self.register_indexer_get(getter)
             .register_indexer_set(setter)
     }
+    /// Register `to_string` and `print` for a custom type with the [`Engine`], formatting it via
+    /// its [`Display`][std::fmt::Display] implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fmt;
+    ///
+    /// #[derive(Clone)]
+    /// struct Celsius(i64);
+    ///
+    /// impl fmt::Display for Celsius {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         write!(f, "{}°C", self.0)
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// # #[cfg(not(feature = "no_object"))]
+    /// engine.register_type::<Celsius>();
+    ///
+    /// engine
+    ///     .register_fn("celsius", Celsius)
+    ///     .register_display::<Celsius>();
+    ///
+    /// assert_eq!(engine.eval::<String>("to_string(celsius(42))")?, "42°C");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn register_display<T: Variant + Clone + fmt::Display>(&mut self) -> &mut Self {
+        self.global_namespace.set_display_fns::<T>();
+        self
+    }
+    /// Register `to_debug` and `debug` for a custom type with the [`Engine`], formatting it via
+    /// its [`Debug`][std::fmt::Debug] implementation.
+    #[inline(always)]
+    pub fn register_debug<T: Variant + Clone + fmt::Debug>(&mut self) -> &mut Self {
+        self.global_namespace.set_debug_fns::<T>();
+        self
+    }
+    /// Register the equality operators `==` and `!=` for a custom type with the [`Engine`], via
+    /// its [`PartialEq`] implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Clone, PartialEq)]
+    /// struct Handle(i64);
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// # #[cfg(not(feature = "no_object"))]
+    /// engine.register_type::<Handle>();
+    ///
+    /// engine
+    ///     .register_fn("handle", Handle)
+    ///     .register_equality::<Handle>();
+    ///
+    /// assert!(engine.eval::<bool>("handle(1) == handle(1)")?);
+    /// assert!(engine.eval::<bool>("handle(1) != handle(2)")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn register_equality<T: Variant + Clone + PartialEq>(&mut self) -> &mut Self {
+        self.global_namespace.set_equality_fns::<T>();
+        self
+    }
+    /// Register the comparison operators `==`, `!=`, `<`, `<=`, `>` and `>=` for a custom type
+    /// with the [`Engine`], via its [`PartialOrd`] implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Clone, PartialEq, PartialOrd)]
+    /// struct Version(i64, i64);
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// # #[cfg(not(feature = "no_object"))]
+    /// engine.register_type::<Version>();
+    ///
+    /// engine
+    ///     .register_fn("version", Version)
+    ///     .register_comparison::<Version>();
+    ///
+    /// assert!(engine.eval::<bool>("version(1, 2) < version(1, 10)")?);
+    /// assert!(engine.eval::<bool>("version(1, 2) == version(1, 2)")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn register_comparison<T: Variant + Clone + PartialOrd>(&mut self) -> &mut Self {
+        self.global_namespace.set_comparison_fns::<T>();
+        self
+    }
+    /// Register the arithmetic operators `+`, `-`, `*`, `/` and unary `-` for a custom type with
+    /// the [`Engine`], via its [`Add`][std::ops::Add], [`Sub`][std::ops::Sub],
+    /// [`Mul`][std::ops::Mul], [`Div`][std::ops::Div] and [`Neg`][std::ops::Neg] implementations.
+    ///
+    /// Compound assignments such as `+=` use the operators as well.
+    ///
+    /// For a type implementing only some of these traits, set the operators into a [`Module`]
+    /// with [`Module::set_add_fn`] and the like, then register it via
+    /// [`register_global_module`][Engine::register_global_module].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::ops::{Add, Div, Mul, Neg, Sub};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq)]
+    /// struct Money(i64);
+    ///
+    /// impl Add for Money { type Output = Self; fn add(self, y: Self) -> Self { Money(self.0 + y.0) } }
+    /// impl Sub for Money { type Output = Self; fn sub(self, y: Self) -> Self { Money(self.0 - y.0) } }
+    /// impl Mul for Money { type Output = Self; fn mul(self, y: Self) -> Self { Money(self.0 * y.0) } }
+    /// impl Div for Money { type Output = Self; fn div(self, y: Self) -> Self { Money(self.0 / y.0) } }
+    /// impl Neg for Money { type Output = Self; fn neg(self) -> Self { Money(-self.0) } }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// # #[cfg(not(feature = "no_object"))]
+    /// engine.register_type::<Money>();
+    ///
+    /// engine
+    ///     .register_fn("money", Money)
+    ///     .register_arithmetic::<Money>();
+    ///
+    /// assert_eq!(engine.eval::<Money>("let x = money(40); x += money(2); x")?, Money(42));
+    /// assert_eq!(engine.eval::<Money>("-money(6) * money(7)")?, Money(-42));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn register_arithmetic<T>(&mut self) -> &mut Self
+    where
+        T: Variant
+            + Clone
+            + Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Neg<Output = T>,
+    {
+        self.global_namespace.set_arithmetic_fns::<T>();
+        self
+    }
+    /// Register a `hash` function for a custom type with the [`Engine`], returning the hash of a
+    /// value (as an integer) via its [`Hash`][std::hash::Hash] implementation.
+    ///
+    /// The hash value is only stable within the same process.
+    #[inline(always)]
+    pub fn register_hash<T: Variant + Clone + Hash>(&mut self) -> &mut Self {
+        self.global_namespace.set_hash_fn::<T>();
+        self
+    }
     /// Register a shared [`Module`] into the global namespace of [`Engine`].
     ///
     /// All functions and type iterators are automatically available to scripts without namespace
@@ -915,14 +1143,22 @@ impl Engine {
             resolver: &StaticModuleResolver,
             imports: &mut HashSet<ImmutableString>,
         ) {
-            ast.walk(&mut |path| match path.last().unwrap() {
+            ast.walk(&mut |path| {
+                let path_expr = match path.last().unwrap() {
+                    ASTNode::Stmt(Stmt::Import(expr, _, _)) => expr,
+                    ASTNode::Stmt(Stmt::ImportMembers(x, _, _)) => &x.0,
+                    _ => return,
+                };
+
                 // Collect all `import` statements with a string constant path
-                ASTNode::Stmt(Stmt::Import(Expr::StringConstant(s, _), _, _))
-                    if !resolver.contains_path(s) && !imports.contains(s) =>
-                {
-                    imports.insert(s.clone());
+                match path_expr {
+                    Expr::StringConstant(s, _)
+                        if !resolver.contains_path(s) && !imports.contains(s) =>
+                    {
+                        imports.insert(s.clone());
+                    }
+                    _ => (),
                 }
-                _ => (),
             });
         }
 
@@ -1531,9 +1767,15 @@ impl Engine {
             source: ast.clone_source(),
             #[cfg(not(feature = "no_module"))]
             resolver: ast.resolver(),
+            #[cfg(not(feature = "no_module"))]
+            import_chain: self.take_handed_over_import_chain(),
             ..Default::default()
         };
-        self.eval_statements_raw(scope, mods, state, ast.statements(), &[ast.lib()], level)
+        #[cfg(not(feature = "no_module"))]
+        if ast.needs_global_constants() {
+            state.push_global_constants(scope);
+        }
+        self.eval_statements_raw(scope, mods, state, ast, &[ast.lib()], level)
     }
     /// Evaluate a file, but throw away the result and only return error (if any).
     /// Useful for when you don't need the result, but still need to keep track of possible errors.
@@ -1602,7 +1844,11 @@ impl Engine {
             resolver: ast.resolver(),
             ..Default::default()
         };
-        self.eval_statements_raw(scope, mods, state, ast.statements(), &[ast.lib()], 0)?;
+        #[cfg(not(feature = "no_module"))]
+        if ast.needs_global_constants() {
+            state.push_global_constants(scope);
+        }
+        self.eval_statements_raw(scope, mods, state, ast, &[ast.lib()], 0)?;
         Ok(())
     }
     /// Call a script function defined in an [`AST`] with multiple arguments.
@@ -1754,9 +2000,14 @@ impl Engine {
             .find_map(|&m| m.get_script_fn(name, args.len(), true))
             .ok_or_else(|| EvalAltResult::ErrorFunctionNotFound(name.into(), Position::NONE))?;
 
-        let mut state = Default::default();
+        let mut state: State = Default::default();
         let mut mods = (&self.global_sub_modules).into();
 
+        #[cfg(not(feature = "no_module"))]
+        if lib.iter().any(|m| m.needs_global_constants()) {
+            state.push_global_constants(scope);
+        }
+
         // Check for data race.
         if cfg!(not(feature = "no_closure")) {
             crate::fn_call::ensure_no_data_race(name, args, false)?;
@@ -32,12 +32,80 @@ impl Engine {
     pub fn optimization_level(&self) -> crate::OptimizationLevel {
         self.optimization_level
     }
+    /// Set the maximum size, in number of expression nodes, of script-defined functions that are
+    /// inlined at call sites under [`OptimizationLevel::Full`][crate::OptimizationLevel::Full].
+    /// Zero disables inlining.
+    ///
+    /// Not available under `no_optimize` or `no_function`.
+    #[cfg(not(feature = "no_optimize"))]
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn set_max_inline_fn_size(&mut self, size: usize) -> &mut Self {
+        self.max_inline_fn_size = size;
+        self
+    }
+    /// The maximum size, in number of expression nodes, of script-defined functions that are
+    /// inlined at call sites (zero if inlining is disabled).
+    ///
+    /// Not available under `no_optimize` or `no_function`.
+    #[cfg(not(feature = "no_optimize"))]
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn max_inline_fn_size(&self) -> usize {
+        self.max_inline_fn_size
+    }
+    /// Set the maximum number of implicit conversions chained to convert an argument of a function
+    /// call into another type.  Zero disables implicit conversions.
+    ///
+    /// See [`register_conversion`][Engine::register_conversion].
+    #[inline(always)]
+    pub fn set_max_conversion_chain(&mut self, length: usize) -> &mut Self {
+        self.max_conversion_chain = length;
+        self
+    }
+    /// The maximum number of implicit conversions chained to convert an argument of a function
+    /// call into another type (zero if implicit conversions are disabled).
+    #[inline(always)]
+    pub fn max_conversion_chain(&self) -> usize {
+        self.max_conversion_chain
+    }
     /// Enable/disable doc-comments.
     #[inline(always)]
     pub fn enable_doc_comments(&mut self, enable: bool) -> &mut Self {
         self.disable_doc_comments = !enable;
         self
     }
+    /// Enable/disable visibility of non-exported variables of a [module][crate::Module] to the
+    /// functions defined in it.
+    ///
+    /// Functions in a [module][crate::Module] created by
+    /// [`Module::eval_ast_as_new`][crate::Module::eval_ast_as_new] can always see the exported
+    /// variables of the [module][crate::Module]. This is disabled by default.
+    ///
+    /// Not available under `no_module`.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub fn enable_private_module_vars(&mut self, enable: bool) -> &mut Self {
+        self.private_module_vars = enable;
+        self
+    }
+    /// Set the backend used by the [`Engine`] to run scripts.
+    ///
+    /// Only available under `bytecode`.
+    #[cfg(feature = "bytecode")]
+    #[inline(always)]
+    pub fn set_execution_backend(&mut self, backend: crate::ExecutionBackend) -> &mut Self {
+        self.execution_backend = backend;
+        self
+    }
+    /// The backend used by the [`Engine`] to run scripts.
+    ///
+    /// Only available under `bytecode`.
+    #[cfg(feature = "bytecode")]
+    #[inline(always)]
+    pub fn execution_backend(&self) -> crate::ExecutionBackend {
+        self.execution_backend
+    }
     /// Set the maximum levels of function calls allowed for a script in order to avoid
     /// infinite recursion and stack overflows.
     ///
@@ -186,6 +254,29 @@ impl Engine {
     pub fn max_map_size(&self) -> usize {
         self.limits.max_map_size.map_or(0, NonZeroUsize::get)
     }
+    /// Set the maximum size (in bytes) of compiled regular expressions (0 for the default
+    /// limit of the [`regex`](https://crates.io/crates/regex) crate).
+    ///
+    /// Regular expressions already compiled are discarded from the cache.
+    ///
+    /// Only available under `regex`. Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(feature = "regex")]
+    #[inline(always)]
+    pub fn set_max_regex_size(&mut self, max_size: usize) -> &mut Self {
+        self.limits.max_regex_size = NonZeroUsize::new(max_size);
+        self.regex_cache = Default::default();
+        self
+    }
+    /// The maximum size (in bytes) of compiled regular expressions (0 for the default limit).
+    ///
+    /// Only available under `regex`. Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(feature = "regex")]
+    #[inline(always)]
+    pub fn max_regex_size(&self) -> usize {
+        self.limits.max_regex_size.map_or(0, NonZeroUsize::get)
+    }
     /// Set the module resolution service used by the [`Engine`].
     ///
     /// Not available under `no_module`.
@@ -0,0 +1,306 @@
+//! Module which defines support for asynchronous native functions and evaluation.
+//!
+//! The evaluator is not resumable, so each asynchronous evaluation spawns a dedicated OS thread
+//! that runs the script synchronously.  Whenever an asynchronous native function is called, its
+//! future is handed back to the task awaiting [`EvalAsync`], which polls it on the host's own
+//! executor, while the evaluation thread blocks until the result is sent back.
+//!
+//! Every [`EvalAsync`] therefore costs one OS thread (with its stack) for as long as the script
+//! runs, plus a thread hand-over for each asynchronous function call.  The host's executor is
+//! never blocked.
+//!
+//! Synchronous evaluation is unaffected - nothing in this module runs unless a script calls an
+//! asynchronous native function.
+
+use crate::dynamic::Variant;
+use crate::stdlib::{
+    any::type_name,
+    boxed::Box,
+    cell::RefCell,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    panic,
+    pin::Pin,
+    sync::{
+        mpsc::{channel, Receiver, Sender, TryRecvError},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread::{self, JoinHandle},
+};
+use crate::{Dynamic, Engine, EvalAltResult, NativeCallContext, Position, Scope, Shared, AST};
+
+/// A boxed future returned by an asynchronous native function.
+pub type AsyncFnFuture = Pin<Box<dyn Future<Output = Result<Dynamic, Box<EvalAltResult>>> + Send>>;
+
+/// Message sent from the evaluation thread to the awaiting task.
+enum Request {
+    /// An asynchronous native function is pending - poll this future and send back its result.
+    Await(AsyncFnFuture),
+    /// Evaluation has completed.
+    Done(Result<Dynamic, Box<EvalAltResult>>, Scope<'static>),
+}
+
+/// Slot holding the [`Waker`] of the task awaiting an [`EvalAsync`].
+type WakerSlot = Arc<Mutex<Option<Waker>>>;
+
+/// Wake up the task awaiting an [`EvalAsync`], if any.
+fn wake(slot: &WakerSlot) {
+    if let Some(waker) = slot.lock().unwrap().take() {
+        waker.wake();
+    }
+}
+
+/// Connection between the evaluation thread and the task awaiting an [`EvalAsync`].
+struct Bridge {
+    requests: Sender<Request>,
+    results: Receiver<Result<Dynamic, Box<EvalAltResult>>>,
+    waker: WakerSlot,
+}
+
+thread_local! {
+    /// The [`Bridge`] of the asynchronous evaluation running on the current thread, if any.
+    static BRIDGE: RefCell<Option<Bridge>> = RefCell::new(None);
+}
+
+/// Wakes the awaiting task when the evaluation thread finishes, even when it panics.
+struct WakeOnDrop(WakerSlot);
+
+impl Drop for WakeOnDrop {
+    fn drop(&mut self) {
+        wake(&self.0);
+    }
+}
+
+/// Suspend the current evaluation until a future returned by an asynchronous native function completes.
+///
+/// Fails when not running within an asynchronous evaluation (e.g. when called via [`Engine::eval`]),
+/// or when the [`EvalAsync`] driving the evaluation has been dropped.
+pub(crate) fn await_future(
+    ctx: NativeCallContext,
+    future: AsyncFnFuture,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    BRIDGE.with(|bridge| match bridge.borrow().as_ref() {
+        None => EvalAltResult::ErrorRuntime(
+            format!(
+                "Asynchronous function '{}' cannot be called outside an asynchronous evaluation",
+                ctx.fn_name()
+            )
+            .into(),
+            Position::NONE,
+        )
+        .into(),
+        Some(bridge) => {
+            if bridge.requests.send(Request::Await(future)).is_err() {
+                return EvalAltResult::ErrorTerminated(Dynamic::UNIT, Position::NONE).into();
+            }
+            wake(&bridge.waker);
+
+            // Block the evaluation thread (not the host's executor) until the result arrives
+            bridge.results.recv().unwrap_or_else(|_| {
+                EvalAltResult::ErrorTerminated(Dynamic::UNIT, Position::NONE).into()
+            })
+        }
+    })
+}
+
+/// A [`Future`] that evaluates an [`AST`] on a dedicated OS thread, which blocks whenever an
+/// asynchronous native function registered via [`RegisterAsyncFn`][crate::RegisterAsyncFn] is pending.
+///
+/// It is executor-agnostic - futures of asynchronous functions are polled by whichever task
+/// awaits the [`EvalAsync`], not on the evaluation thread.
+///
+/// Dropping an [`EvalAsync`] before it completes terminates the script with
+/// [`ErrorTerminated`][EvalAltResult::ErrorTerminated].
+///
+/// Created via [`Engine::eval_ast_async`] or [`Engine::eval_ast_with_scope_async`].
+///
+/// Available under the `async` feature only.
+#[must_use = "futures do nothing unless polled"]
+pub struct EvalAsync<T: Variant + Clone> {
+    engine: Shared<Engine>,
+    requests: Receiver<Request>,
+    results: Sender<Result<Dynamic, Box<EvalAltResult>>>,
+    waker: WakerSlot,
+    pending: Option<AsyncFnFuture>,
+    thread: Option<JoinHandle<()>>,
+    scope: Option<Scope<'static>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Variant + Clone> fmt::Debug for EvalAsync<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvalAsync")
+            .field("pending", &self.pending.is_some())
+            .field("completed", &self.thread.is_none())
+            .finish()
+    }
+}
+
+impl<T: Variant + Clone> EvalAsync<T> {
+    /// Start evaluating an [`AST`] on a new evaluation thread.
+    fn new(engine: Shared<Engine>, mut scope: Scope<'static>, ast: AST) -> Self {
+        let (request_tx, request_rx) = channel();
+        let (result_tx, result_rx) = channel();
+        let waker: WakerSlot = Default::default();
+
+        let bridge = Bridge {
+            requests: request_tx.clone(),
+            results: result_rx,
+            waker: waker.clone(),
+        };
+        let guard = WakeOnDrop(waker.clone());
+        let eval_engine = engine.clone();
+
+        let thread = thread::spawn(move || {
+            let _guard = guard;
+            BRIDGE.with(|b| *b.borrow_mut() = Some(bridge));
+            let result = eval_engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast);
+            BRIDGE.with(|b| *b.borrow_mut() = None);
+            let _ = request_tx.send(Request::Done(result, scope));
+        });
+
+        Self {
+            engine,
+            requests: request_rx,
+            results: result_tx,
+            waker,
+            pending: None,
+            thread: Some(thread),
+            scope: None,
+            _marker: PhantomData,
+        }
+    }
+    /// Consume this [`EvalAsync`] and return the [`Scope`] as modified by the script.
+    ///
+    /// Returns [`None`] if the evaluation has not yet completed.
+    #[inline(always)]
+    pub fn into_scope(self) -> Option<Scope<'static>> {
+        self.scope
+    }
+    /// Wait for the evaluation thread to finish, propagating any panic.
+    fn join(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            if let Err(err) = thread.join() {
+                panic::resume_unwind(err);
+            }
+        }
+    }
+}
+
+impl<T: Variant + Clone> Future for EvalAsync<T> {
+    type Output = Result<T, Box<EvalAltResult>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            // Drive the pending asynchronous native function, if any
+            if let Some(future) = self.pending.as_mut() {
+                match future.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(result) => {
+                        self.pending = None;
+                        // The evaluation thread only goes away after sending `Done`
+                        let _ = self.results.send(result);
+                    }
+                }
+            }
+
+            // Register the waker before checking for requests so that no wake-up is lost
+            *self.waker.lock().unwrap() = Some(cx.waker().clone());
+
+            match self.requests.try_recv() {
+                Ok(Request::Await(future)) => self.pending = Some(future),
+                Ok(Request::Done(result, scope)) => {
+                    self.join();
+                    self.scope = Some(scope);
+
+                    return Poll::Ready(result.and_then(|r| {
+                        let typ = self.engine.map_type_name(r.type_name());
+
+                        r.try_cast::<T>().ok_or_else(|| {
+                            EvalAltResult::ErrorMismatchOutputType(
+                                self.engine.map_type_name(type_name::<T>()).into(),
+                                typ.into(),
+                                Position::NONE,
+                            )
+                            .into()
+                        })
+                    }));
+                }
+                Err(TryRecvError::Empty) => return Poll::Pending,
+                Err(TryRecvError::Disconnected) => {
+                    // The evaluation thread can only disconnect without `Done` by panicking
+                    self.join();
+                    unreachable!("evaluation thread disconnected without a result");
+                }
+            }
+        }
+    }
+}
+
+/// Engine public API for asynchronous evaluation.
+impl Engine {
+    /// Evaluate an [`AST`] asynchronously.
+    ///
+    /// The script runs on a new OS thread spawned for this evaluation, which blocks whenever an
+    /// asynchronous native function (registered via [`RegisterAsyncFn`][crate::RegisterAsyncFn])
+    /// is pending, and continues when its future, polled by the task awaiting the returned
+    /// [`EvalAsync`], completes.  The calling task and its executor are never blocked.
+    ///
+    /// As each call spawns a thread, this is not suitable for running large numbers of short
+    /// scripts concurrently.
+    ///
+    /// Available under the `async` feature only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterAsyncFn, Shared};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_async_fn("fetch", |x: i64| async move { x * 2 });
+    ///
+    /// let engine = Shared::new(engine);
+    /// let ast = engine.compile("fetch(20) + 2")?;
+    ///
+    /// let result = engine.eval_ast_async::<i64>(&ast);
+    /// # let result = futures_executor_block_on(result);
+    /// # fn futures_executor_block_on<F: std::future::Future>(f: F) -> F::Output {
+    /// #     use std::task::{Context, Poll, Wake};
+    /// #     struct Unpark(std::thread::Thread);
+    /// #     impl Wake for Unpark { fn wake(self: std::sync::Arc<Self>) { self.0.unpark(); } }
+    /// #     let waker = std::sync::Arc::new(Unpark(std::thread::current())).into();
+    /// #     let mut cx = Context::from_waker(&waker);
+    /// #     let mut f = Box::pin(f);
+    /// #     loop {
+    /// #         if let Poll::Ready(r) = f.as_mut().poll(&mut cx) { return r; }
+    /// #         std::thread::park();
+    /// #     }
+    /// # }
+    ///
+    /// assert_eq!(result?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn eval_ast_async<T: Variant + Clone>(self: &Shared<Self>, ast: &AST) -> EvalAsync<T> {
+        EvalAsync::new(self.clone(), Default::default(), ast.clone())
+    }
+    /// Evaluate an [`AST`] asynchronously with own scope.
+    ///
+    /// The scope, as modified by the script, can be retrieved via [`EvalAsync::into_scope`]
+    /// once evaluation completes.
+    ///
+    /// Available under the `async` feature only.
+    #[inline(always)]
+    pub fn eval_ast_with_scope_async<T: Variant + Clone>(
+        self: &Shared<Self>,
+        scope: Scope<'static>,
+        ast: &AST,
+    ) -> EvalAsync<T> {
+        EvalAsync::new(self.clone(), scope, ast.clone())
+    }
+}
@@ -1,11 +1,11 @@
 //! Implement function-calling mechanism for [`Engine`].
 
-use crate::ast::{Expr, Stmt};
+use crate::ast::{Expr, AST};
 use crate::engine::{
-    search_imports, Imports, State, KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR,
+    search_imports, Imports, State, FN_FMT, FN_FORMAT, KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR,
     KEYWORD_FN_PTR_CALL, KEYWORD_FN_PTR_CURRY, KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_TYPE_OF,
 };
-use crate::fn_native::FnCallArgs;
+use crate::fn_native::{CallableFunction, FnCallArgs, FnConversions};
 use crate::module::NamespaceRef;
 use crate::optimize::OptimizationLevel;
 use crate::stdlib::{
@@ -29,6 +29,9 @@ use crate::{
 #[cfg(not(feature = "no_float"))]
 use crate::FLOAT;
 
+#[cfg(not(feature = "no_function"))]
+use crate::token::Token;
+
 #[cfg(not(feature = "no_object"))]
 use crate::Map;
 
@@ -36,6 +39,9 @@ use crate::Map;
 #[cfg(not(feature = "no_float"))]
 use num_traits::float::Float;
 
+/// Max number of combinations of argument types tried when converting function call arguments.
+pub const MAX_CONVERSION_COMBINATIONS: usize = 256;
+
 /// Extract the property name from a getter function name.
 #[cfg(not(feature = "no_object"))]
 #[inline(always)]
@@ -152,7 +158,189 @@ pub fn ensure_no_data_race(
     Ok(())
 }
 
+/// Can a script-defined function overload the operator (if it is one) for these arguments?
+///
+/// Standard operators on primitive values (e.g. adding two integers) cannot be overloaded
+/// by scripts, so the script-defined operators themselves can use them freely.
+///
+/// This is checked before searching for script-defined functions, so it must be cheap.
+#[cfg(not(feature = "no_function"))]
+pub(crate) fn can_overload_operator(
+    fn_name: &str,
+    arg_types: impl IntoIterator<Item = TypeId>,
+) -> bool {
+    // Standard operators are never named like identifiers
+    if fn_name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        return true;
+    }
+
+    match Token::lookup_from_syntax(fn_name) {
+        Some(token) if token.can_override_operator() => !arg_types.into_iter().all(|type_id| {
+            #[cfg(not(feature = "no_float"))]
+            if type_id == TypeId::of::<FLOAT>() {
+                return true;
+            }
+
+            type_id == TypeId::of::<INT>()
+                || type_id == TypeId::of::<bool>()
+                || type_id == TypeId::of::<char>()
+                || type_id == TypeId::of::<ImmutableString>()
+                || type_id == TypeId::of::<()>()
+        }),
+        _ => true,
+    }
+}
+
 impl Engine {
+    /// Search for a native Rust function, together with the source of the module holding it.
+    ///
+    /// Registered functions are searched first (they can override packages), then packages,
+    /// and finally imported modules.
+    #[inline]
+    fn find_native_fn(
+        &self,
+        mods: &Imports,
+        hash_fn: NonZeroU64,
+        pub_only: bool,
+    ) -> Option<(CallableFunction, Option<ImmutableString>)> {
+        self.global_namespace
+            .get_fn(hash_fn, pub_only)
+            .cloned()
+            .map(|f| (f, None))
+            .or_else(|| {
+                self.global_modules.iter().find_map(|m| {
+                    m.get_fn(hash_fn, false)
+                        .map(|f| (f.clone(), m.id_raw().cloned()))
+                })
+            })
+            .or_else(|| {
+                mods.get_fn(hash_fn)
+                    .map(|(f, source)| (f.clone(), source.cloned()))
+            })
+    }
+
+    /// Search for a native Rust function, converting arguments into other types via the
+    /// conversions registered with [`register_conversion`][Engine::register_conversion].
+    ///
+    /// Each argument is converted via a chain of at most
+    /// [`max_conversion_chain`][Engine::max_conversion_chain] conversions.
+    ///
+    /// Combinations of argument types are tried in order of the total number of conversions.
+    /// Among combinations with the same number, those converting later arguments are tried first,
+    /// then types reachable with shorter chains and, finally, conversions registered earlier.
+    ///
+    /// No conversions are attempted if there are more than [`MAX_CONVERSION_COMBINATIONS`]
+    /// combinations of argument types.
+    fn find_native_fn_with_conversions(
+        &self,
+        mods: &Imports,
+        fn_name: &str,
+        args: &FnCallArgs,
+        pub_only: bool,
+    ) -> Option<(CallableFunction, Option<ImmutableString>, FnConversions)> {
+        if self.type_conversions.is_empty() || self.max_conversion_chain == 0 {
+            return None;
+        }
+
+        // Types each argument can be converted into, with the conversions required
+        let candidates: StaticVec<StaticVec<(TypeId, FnConversions)>> = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                let mut types: StaticVec<(TypeId, FnConversions)> = Default::default();
+                types.push((arg.type_id(), Default::default()));
+
+                let mut level = 0;
+
+                for _ in 0..self.max_conversion_chain {
+                    let end = types.len();
+
+                    for n in level..end {
+                        let rules = match self.type_conversions.get(&types[n].0) {
+                            Some(rules) => rules,
+                            None => continue,
+                        };
+
+                        for (to_type, convert) in rules.iter() {
+                            if types.iter().all(|(t, _)| t != to_type) {
+                                let mut chain = types[n].1.clone();
+                                chain.push((index, convert.clone()));
+                                types.push((*to_type, chain));
+                            }
+                        }
+                    }
+
+                    if types.len() == end {
+                        break;
+                    }
+                    level = end;
+                }
+
+                types
+            })
+            .collect();
+
+        let num_combinations = candidates
+            .iter()
+            .try_fold(1_usize, |total, types| total.checked_mul(types.len()))
+            .unwrap_or(usize::MAX);
+
+        if num_combinations <= 1 || num_combinations > MAX_CONVERSION_COMBINATIONS {
+            return None;
+        }
+
+        // List all combinations, with the first argument varying slowest
+        let mut combinations: Vec<(usize, StaticVec<usize>)> = Vec::with_capacity(num_combinations);
+        let mut indices: StaticVec<usize> = candidates.iter().map(|_| 0).collect();
+
+        'outer: loop {
+            let cost = indices
+                .iter()
+                .zip(candidates.iter())
+                .map(|(&i, types)| types[i].1.len())
+                .sum();
+
+            if cost > 0 {
+                combinations.push((cost, indices.clone()));
+            }
+
+            let mut pos = indices.len();
+
+            loop {
+                if pos == 0 {
+                    break 'outer;
+                }
+                pos -= 1;
+                indices[pos] += 1;
+                if indices[pos] < candidates[pos].len() {
+                    break;
+                }
+                indices[pos] = 0;
+            }
+        }
+
+        // Stable sort keeps the order among combinations with the same number of conversions
+        combinations.sort_by_key(|(cost, _)| *cost);
+
+        combinations.into_iter().find_map(|(_, indices)| {
+            let arg_types = indices
+                .iter()
+                .zip(candidates.iter())
+                .map(|(&i, types)| types[i].0);
+            let hash_fn = calc_native_fn_hash(empty(), fn_name, arg_types)?;
+
+            self.find_native_fn(mods, hash_fn, pub_only)
+                .map(|(func, source)| {
+                    let conversions = indices
+                        .iter()
+                        .zip(candidates.iter())
+                        .flat_map(|(&i, types)| types[i].1.iter().cloned())
+                        .collect();
+                    (func, source, conversions)
+                })
+        })
+    }
+
     /// Call a native Rust function registered with the [`Engine`].
     ///
     /// # WARNING
@@ -176,34 +364,54 @@ impl Engine {
         self.inc_operations(state, pos)?;
 
         // Check if function access already in the cache
-        let func = &*state.functions_cache.entry(hash_fn).or_insert_with(|| {
-            // Search for the native function
-            // First search registered functions (can override packages)
-            // Then search packages
-            // Finally search modules
-
-            //lib.get_fn(hash_fn, pub_only)
-            self.global_namespace
-                .get_fn(hash_fn, pub_only)
-                .cloned()
-                .map(|f| (f, None))
-                .or_else(|| {
-                    self.global_modules.iter().find_map(|m| {
-                        m.get_fn(hash_fn, false)
-                            .map(|f| (f.clone(), m.id_raw().cloned()))
-                    })
-                })
-                .or_else(|| {
-                    mods.get_fn(hash_fn)
-                        .map(|(f, source)| (f.clone(), source.cloned()))
-                })
-        });
+        if !state.functions_cache.contains_key(&hash_fn) {
+            let func = match self.find_native_fn(mods, hash_fn, pub_only) {
+                Some((func, source)) => Some((func, source, Default::default())),
+                None => {
+                    // See if it is built in - built-in operators are preferred over conversions
+                    if args.len() == 2 {
+                        if let Some(v) = run_builtin_binary_op(fn_name, args[0], args[1])? {
+                            state.functions_cache.insert(hash_fn, None);
+                            return Ok((v, false));
+                        }
+                    }
 
-        if let Some((func, source)) = func {
+                    // Search for the function with arguments converted into other types
+                    self.find_native_fn_with_conversions(mods, fn_name, args, pub_only)
+                }
+            };
+
+            // Cache the function with the conversions (if any), or the failed search
+            state.functions_cache.insert(hash_fn, func);
+        }
+
+        let func = state.functions_cache.get(&hash_fn).unwrap();
+
+        // See if it is built in.
+        if func.is_none() && args.len() == 2 {
+            match run_builtin_binary_op(fn_name, args[0], args[1])? {
+                Some(v) => return Ok((v, false)),
+                None => (),
+            }
+        }
+
+        if let Some((func, source, conversions)) = func {
             assert!(func.is_native());
 
-            // Calling pure function but the first argument is a reference?
             let mut backup: ArgBackup = Default::default();
+
+            // Convert arguments in place - a first argument passed by reference is copied first
+            let first_converted = conversions.iter().any(|(index, _)| *index == 0);
+            backup.change_first_arg_to_copy(is_ref && first_converted, args);
+
+            conversions.iter().for_each(|(index, convert)| {
+                let value = mem::take(args[*index]).flatten();
+                *args[*index] = convert(value);
+            });
+
+            let is_ref = is_ref && !first_converted;
+
+            // Calling pure function but the first argument is a reference?
             backup.change_first_arg_to_copy(is_ref && func.is_pure(), args);
 
             // Run external function
@@ -248,18 +456,10 @@ impl Engine {
                     let source = state.source.as_ref().map(|s| s.as_str());
                     ((self.debug)(text, source, pos).into(), false)
                 }
-                _ => (result, func.is_method()),
+                _ => (result, func.is_method() && !first_converted),
             });
         }
 
-        // See if it is built in.
-        if args.len() == 2 {
-            match run_builtin_binary_op(fn_name, args[0], args[1])? {
-                Some(v) => return Ok((v, false)),
-                None => (),
-            }
-        }
-
         // Return default value (if any)
         if let Some(val) = def_val {
             return Ok((val.clone(), false));
@@ -341,6 +541,55 @@ impl Engine {
         .into()
     }
 
+    /// Select the overload of a script-defined function whose parameter types match the types of
+    /// the arguments.
+    ///
+    /// A type annotation matches arguments whose `type_of` name is the same, while `int` and
+    /// `float` match the integer and floating-point types in use.
+    /// Parameters without type annotations match arguments of any type.
+    ///
+    /// Overloads with more parameters annotated are preferred; among those, the one defined first.
+    #[cfg(not(feature = "no_function"))]
+    fn select_script_overload<'a>(
+        &self,
+        fn_def: &'a crate::ast::ScriptFnDef,
+        args: &FnCallArgs,
+    ) -> Option<&'a crate::ast::ScriptFnDef> {
+        let mut selected = None;
+
+        for f in fn_def.iter_overloads() {
+            let mut num_typed = 0;
+
+            let matched = args
+                .iter()
+                .enumerate()
+                .all(|(i, arg)| match f.param_type(i) {
+                    Some("int") => {
+                        num_typed += 1;
+                        arg.is::<INT>()
+                    }
+                    #[cfg(not(feature = "no_float"))]
+                    Some("float") => {
+                        num_typed += 1;
+                        arg.is::<FLOAT>()
+                    }
+                    Some(typ) => {
+                        num_typed += 1;
+                        self.map_value_type_name(arg) == typ
+                    }
+                    None => true,
+                });
+
+            match selected {
+                _ if !matched => (),
+                Some((n, _)) if n >= num_typed => (),
+                _ => selected = Some((num_typed, f)),
+            }
+        }
+
+        selected.map(|(_, f)| f)
+    }
+
     /// Call a script-defined function.
     ///
     /// # WARNING
@@ -370,12 +619,48 @@ impl Engine {
             return Err(Box::new(EvalAltResult::ErrorStackOverflow(pos)));
         }
 
+        // Select the overload matching the types of the arguments
+        let fn_def = if fn_def.is_overloaded() {
+            match self.select_script_overload(fn_def, args) {
+                Some(f) => f,
+                None => {
+                    return EvalAltResult::ErrorFunctionNotFound(
+                        format!(
+                            "{} ({})",
+                            fn_def.name,
+                            args.iter()
+                                .map(|a| self.map_value_type_name(a))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                        pos,
+                    )
+                    .into()
+                }
+            }
+        } else {
+            fn_def
+        };
+
         let orig_scope_level = state.scope_level;
         state.scope_level += 1;
 
         let prev_scope_len = scope.len();
         let prev_mods_len = mods.len();
 
+        // Put encapsulated module variables into scope, shadowed by the arguments
+        #[cfg(not(feature = "no_module"))]
+        if let Some(ref vars) = fn_def.vars {
+            scope.extend(vars.iter().map(|(name, value)| {
+                let var_name: crate::stdlib::borrow::Cow<'_, str> =
+                    crate::r#unsafe::unsafe_cast_var_name_to_lifetime(name).into();
+                // Keep constants read-only
+                let mut var_value = value.clone();
+                var_value.set_access_mode(value.access_mode());
+                (var_name, var_value)
+            }));
+        }
+
         // Put arguments into scope as variables
         // Actually consume the arguments instead of cloning them
         scope.extend(
@@ -410,44 +695,48 @@ impl Engine {
             mods.extend(fn_def.mods.iter_raw().map(|(n, m)| (n.clone(), m.clone())));
         }
 
+        // Create a new object for a class constructor, bound to `this`
+        #[cfg(not(feature = "no_object"))]
+        let mut new_obj = match fn_def.class {
+            Some(ref class) => {
+                Some(self.make_class_instance(mods, state, unified_lib, class, level)?)
+            }
+            None => None,
+        };
+        #[cfg(not(feature = "no_object"))]
+        let this_ptr = &mut match new_obj {
+            Some(ref mut obj) => Some(obj),
+            None => this_ptr.as_mut().map(|v| &mut **v),
+        };
+
         // Evaluate the function
         let stmt = &fn_def.body;
 
-        let result = self
-            .eval_stmt(scope, mods, state, unified_lib, this_ptr, stmt, level)
-            .or_else(|err| match *err {
-                // Convert return statement to return value
-                EvalAltResult::Return(x, _) => Ok(x),
-                EvalAltResult::ErrorInFunctionCall(name, src, err, _) => {
-                    EvalAltResult::ErrorInFunctionCall(
-                        format!(
-                            "{}{} < {}",
-                            name,
-                            if src.is_empty() {
-                                "".to_string()
-                            } else {
-                                format!(" @ '{}'", src)
-                            },
-                            fn_def.name
-                        ),
-                        fn_def
-                            .lib
-                            .as_ref()
-                            .map(|m| m.id())
-                            .flatten()
-                            .or_else(|| state.source.as_ref().map(|s| s.as_str()))
-                            .unwrap_or("")
-                            .to_string(),
-                        err,
-                        pos,
-                    )
-                    .into()
-                }
-                // System errors are passed straight-through
-                err if err.is_system_exception() => Err(Box::new(err)),
-                // Other errors are wrapped in `ErrorInFunctionCall`
-                _ => EvalAltResult::ErrorInFunctionCall(
-                    fn_def.name.to_string(),
+        #[cfg(feature = "bytecode")]
+        let result = if self.execution_backend == crate::ExecutionBackend::Bytecode {
+            let bytecode = fn_def.bytecode.get_or_compile(stmt);
+            self.run_bytecode(scope, mods, state, unified_lib, this_ptr, &bytecode, level)
+        } else {
+            self.eval_stmt(scope, mods, state, unified_lib, this_ptr, stmt, level)
+        };
+        #[cfg(not(feature = "bytecode"))]
+        let result = self.eval_stmt(scope, mods, state, unified_lib, this_ptr, stmt, level);
+
+        let result = result.or_else(|err| match *err {
+            // Convert return statement to return value
+            EvalAltResult::Return(x, _) => Ok(x),
+            EvalAltResult::ErrorInFunctionCall(name, src, err, _) => {
+                EvalAltResult::ErrorInFunctionCall(
+                    format!(
+                        "{}{} < {}",
+                        name,
+                        if src.is_empty() {
+                            "".to_string()
+                        } else {
+                            format!(" @ '{}'", src)
+                        },
+                        fn_def.name
+                    ),
                     fn_def
                         .lib
                         .as_ref()
@@ -459,17 +748,74 @@ impl Engine {
                     err,
                     pos,
                 )
-                .into(),
-            });
+                .into()
+            }
+            // System errors are passed straight-through
+            err if err.is_system_exception() => Err(Box::new(err)),
+            // Other errors are wrapped in `ErrorInFunctionCall`
+            _ => EvalAltResult::ErrorInFunctionCall(
+                fn_def.name.to_string(),
+                fn_def
+                    .lib
+                    .as_ref()
+                    .map(|m| m.id())
+                    .flatten()
+                    .or_else(|| state.source.as_ref().map(|s| s.as_str()))
+                    .unwrap_or("")
+                    .to_string(),
+                err,
+                pos,
+            )
+            .into(),
+        });
 
         // Remove all local variables
         scope.rewind(prev_scope_len);
         mods.truncate(prev_mods_len);
         state.scope_level = orig_scope_level;
 
+        // A class constructor returns the new object
+        #[cfg(not(feature = "no_object"))]
+        if let Some(obj) = new_obj {
+            return result.map(|_| obj);
+        }
+
         result
     }
 
+    /// Create a new instance of a script-defined class, with its fields set to their default
+    /// values.
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    fn make_class_instance(
+        &self,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        class: &crate::Shared<crate::ast::ClassDef>,
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let mut fields = Map::with_capacity(class.fields.len());
+
+        for (name, expr) in class.fields.iter() {
+            let value = self.eval_expr(
+                &mut Default::default(),
+                mods,
+                state,
+                lib,
+                &mut None,
+                expr,
+                level,
+            )?;
+            fields.insert(name.clone(), value.flatten());
+        }
+
+        Ok(Dynamic::from(crate::ClassInstance::new(
+            class.clone(),
+            fields,
+        )))
+    }
+
     // Has a system function an override?
     #[inline(always)]
     pub(crate) fn has_override_by_name_and_arguments(
@@ -543,14 +889,68 @@ impl Engine {
         let arg_types = args.iter().map(|a| a.type_id());
         let hash_fn = calc_native_fn_hash(empty(), fn_name, arg_types);
 
+        self.exec_fn_call_with_hash(
+            mods,
+            state,
+            lib,
+            fn_name,
+            hash_fn,
+            hash_script,
+            args,
+            is_ref,
+            _is_method,
+            pub_only,
+            pos,
+            _capture_scope,
+            def_val,
+            _level,
+        )
+    }
+
+    /// Perform an actual function call, with the hash of the native function (calculated from
+    /// the argument types) already known.
+    ///
+    /// Arguments must already be checked for data races.
+    ///
+    /// # WARNING
+    ///
+    /// Function call arguments may be _consumed_ when the function requires them to be passed by value.
+    /// All function arguments not in the first position are always passed by value and thus consumed.
+    /// **DO NOT** reuse the argument values unless for the first `&mut` argument - all others are silently replaced by `()`!
+    pub(crate) fn exec_fn_call_with_hash(
+        &self,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        fn_name: &str,
+        hash_fn: Option<NonZeroU64>,
+        hash_script: Option<NonZeroU64>,
+        args: &mut FnCallArgs,
+        is_ref: bool,
+        _is_method: bool,
+        pub_only: bool,
+        pos: Position,
+        _capture_scope: Option<Scope>,
+        def_val: Option<&Dynamic>,
+        _level: usize,
+    ) -> Result<(Dynamic, bool), Box<EvalAltResult>> {
         match fn_name {
             // type_of
             KEYWORD_TYPE_OF
                 if args.len() == 1
                     && !self.has_override(Some(mods), lib, hash_fn, hash_script, pub_only) =>
+            {
+                Ok((self.map_value_type_name(args[0]).to_string().into(), false))
+            }
+
+            // format/fmt
+            FN_FORMAT | FN_FMT
+                if !args.is_empty()
+                    && args[0].is::<ImmutableString>()
+                    && !self.has_override(Some(mods), lib, hash_fn, hash_script, pub_only) =>
             {
                 Ok((
-                    self.map_type_name(args[0].type_name()).to_string().into(),
+                    self.format_string(mods, state, lib, args, pos, _level)?,
                     false,
                 ))
             }
@@ -575,6 +975,7 @@ impl Engine {
             // Script-like function found
             #[cfg(not(feature = "no_function"))]
             _ if hash_script.is_some()
+                && can_overload_operator(fn_name, args.iter().map(|a| a.type_id()))
                 && self.has_override(Some(mods), lib, None, hash_script, pub_only) =>
             {
                 let hash_script = hash_script.unwrap();
@@ -593,7 +994,12 @@ impl Engine {
                                 .map(|f| (f, m.id_raw().cloned()))
                         })
                     })
-                    //.or_else(|| mods.iter().find_map(|(_, m)| m.get_qualified_fn(hash_script).map(|f| (f, m.id_raw().clone()))))
+                    .map(|(f, s)| (f.clone(), s))
+                    // Script functions imported into the global namespace
+                    .or_else(|| {
+                        mods.get_fn(hash_script)
+                            .map(|(f, s)| (f.clone(), s.cloned()))
+                    })
                     .unwrap();
 
                 assert!(func.is_script());
@@ -681,20 +1087,34 @@ impl Engine {
         }
     }
 
-    /// Evaluate a list of statements with an empty state and no `this` pointer.
-    /// This is commonly used to evaluate a list of statements in an [`AST`] or a script function body.
+    /// Evaluate the statements of an [`AST`] with an empty state and no `this` pointer.
     #[inline]
-    pub(crate) fn eval_statements_raw<'a>(
+    pub(crate) fn eval_statements_raw(
         &self,
         scope: &mut Scope,
         mods: &mut Imports,
         state: &mut State,
-        statements: impl IntoIterator<Item = &'a Stmt>,
+        ast: &AST,
         lib: &[&Module],
         level: usize,
     ) -> Result<Dynamic, Box<EvalAltResult>> {
-        statements
-            .into_iter()
+        #[cfg(feature = "bytecode")]
+        if self.execution_backend == crate::ExecutionBackend::Bytecode {
+            let bytecode = ast.bytecode();
+
+            return self
+                .run_bytecode(scope, mods, state, lib, &mut None, &bytecode, level)
+                .or_else(|err| match *err {
+                    EvalAltResult::Return(out, _) => Ok(out),
+                    EvalAltResult::LoopBreak(_, _) => {
+                        unreachable!("no outer loop scope to break out of")
+                    }
+                    _ => Err(err),
+                });
+        }
+
+        ast.statements()
+            .iter()
             .try_fold(Dynamic::UNIT, |_, stmt| {
                 self.eval_stmt(scope, mods, state, lib, &mut None, stmt, level)
             })
@@ -742,11 +1162,12 @@ impl Engine {
         let mut new_state = State {
             source: state.source.clone(),
             operations: state.operations,
+            #[cfg(not(feature = "no_module"))]
+            global_constants: state.global_constants.clone(),
             ..Default::default()
         };
 
-        let result =
-            self.eval_statements_raw(scope, mods, &mut new_state, ast.statements(), lib, level);
+        let result = self.eval_statements_raw(scope, mods, &mut new_state, &ast, lib, level);
 
         state.operations = new_state.operations;
         result
@@ -849,6 +1270,34 @@ impl Engine {
             let _redirected;
             let mut hash = hash_script;
 
+            // Check if it is a method of a script-defined class
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_object"))]
+            if let Some(class) = obj
+                .read_lock::<crate::ClassInstance>()
+                .map(|obj| obj.class().clone())
+            {
+                if let Some(method) = class.get_method(fn_name, call_args.len()) {
+                    let mut args: StaticVec<_> = call_args.iter_mut().collect();
+                    let result = self.call_script_fn(
+                        &mut Default::default(),
+                        mods,
+                        state,
+                        lib,
+                        &mut Some(obj),
+                        method,
+                        args.as_mut(),
+                        pos,
+                        level + 1,
+                    )?;
+
+                    // The object may have been changed by the method
+                    target.propagate_changed_value();
+
+                    return Ok((result, true));
+                }
+            }
+
             // Check if it is a map method call in OOP style
             #[cfg(not(feature = "no_object"))]
             if let Some(map) = obj.read_lock::<Map>() {
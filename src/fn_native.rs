@@ -7,15 +7,16 @@ use crate::stdlib::{
     boxed::Box,
     convert::{TryFrom, TryInto},
     fmt,
-    iter::empty,
+    iter::{empty, FromIterator},
     mem,
+    ops::{Deref, DerefMut},
     string::String,
     vec::Vec,
 };
 use crate::token::is_valid_identifier;
 use crate::{
     calc_script_fn_hash, Dynamic, Engine, EvalAltResult, EvalContext, ImmutableString, Module,
-    Position,
+    Position, StaticVec,
 };
 
 #[cfg(not(feature = "sync"))]
@@ -45,11 +46,11 @@ pub type Shared<T> = Rc<T>;
 pub type Shared<T> = Arc<T>;
 
 /// Synchronized shared object.
-#[cfg(not(feature = "no_closure"))]
+#[cfg(any(not(feature = "no_closure"), feature = "regex"))]
 #[cfg(not(feature = "sync"))]
 pub type Locked<T> = crate::stdlib::cell::RefCell<T>;
 /// Synchronized shared object.
-#[cfg(not(feature = "no_closure"))]
+#[cfg(any(not(feature = "no_closure"), feature = "regex"))]
 #[cfg(feature = "sync")]
 pub type Locked<T> = crate::stdlib::sync::RwLock<T>;
 
@@ -391,6 +392,50 @@ pub type FnAny = dyn Fn(NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, B
 pub type FnAny =
     dyn Fn(NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>> + Send + Sync;
 
+/// A standard function converting a value into another type.
+#[cfg(not(feature = "sync"))]
+pub type FnConvert = dyn Fn(Dynamic) -> Dynamic;
+/// A standard function converting a value into another type.
+#[cfg(feature = "sync")]
+pub type FnConvert = dyn Fn(Dynamic) -> Dynamic + Send + Sync;
+
+/// Conversions of function call arguments, as pairs of argument position and conversion function,
+/// applied in order.
+#[derive(Clone, Default)]
+pub(crate) struct FnConversions(StaticVec<(usize, Shared<FnConvert>)>);
+
+impl fmt::Debug for FnConversions {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|(index, _)| index))
+            .finish()
+    }
+}
+
+impl Deref for FnConversions {
+    type Target = StaticVec<(usize, Shared<FnConvert>)>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for FnConversions {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl FromIterator<(usize, Shared<FnConvert>)> for FnConversions {
+    #[inline(always)]
+    fn from_iter<T: IntoIterator<Item = (usize, Shared<FnConvert>)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 /// A standard function that gets an iterator from a type.
 pub type IteratorFn = fn(Dynamic) -> Box<dyn Iterator<Item = Dynamic>>;
 
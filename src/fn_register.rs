@@ -10,6 +10,19 @@ use crate::{
     Dynamic, Engine, EvalAltResult, FnAccess, FnNamespace, ImmutableString, NativeCallContext,
 };
 
+#[cfg(feature = "async")]
+use crate::fn_async::{await_future, AsyncFnFuture};
+
+#[cfg(feature = "async")]
+use crate::stdlib::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "async")]
+use crate::StaticVec;
+
 /// Trait to register custom functions with the [`Engine`].
 pub trait RegisterFn<FN, ARGS, RET> {
     /// Register a custom function with the [`Engine`].
@@ -40,6 +53,28 @@ pub trait RegisterFn<FN, ARGS, RET> {
     /// # }
     /// ```
     fn register_fn(&mut self, name: &str, f: FN) -> &mut Self;
+    /// Register a custom function with the [`Engine`], marking it as `const`.
+    ///
+    /// A `const` function must be deterministic and free of side effects: its result must depend only
+    /// on its arguments and it must not read the time, random numbers or any other host state.
+    /// Under [`OptimizationLevel::Full`][crate::OptimizationLevel::Full], calls to it with constant
+    /// arguments are evaluated at compile time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_const_fn("add", |x: i64, y: i64| x + y);
+    ///
+    /// assert_eq!(engine.eval::<i64>("add(40, 2)")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn register_const_fn(&mut self, name: &str, f: FN) -> &mut Self;
 }
 
 /// Trait to register fallible custom functions returning [`Result`]`<`[`Dynamic`]`, `[`Box`]`<`[`EvalAltResult`]`>>` with the [`Engine`].
@@ -70,6 +105,73 @@ pub trait RegisterResultFn<FN, ARGS> {
     ///         .expect_err("expecting division by zero error!");
     /// ```
     fn register_result_fn(&mut self, name: &str, f: FN) -> &mut Self;
+    /// Register a custom fallible function with the [`Engine`], marking it as `const`.
+    ///
+    /// A `const` function must be deterministic and free of side effects: its result must depend only
+    /// on its arguments and it must not read the time, random numbers or any other host state.
+    /// Under [`OptimizationLevel::Full`][crate::OptimizationLevel::Full], calls to it with constant
+    /// arguments are evaluated at compile time, unless they return an error.
+    fn register_const_result_fn(&mut self, name: &str, f: FN) -> &mut Self;
+}
+
+/// Trait to register asynchronous custom functions with the [`Engine`].
+///
+/// Asynchronous functions can only be called from scripts evaluated via
+/// [`Engine::eval_ast_async`][crate::Engine::eval_ast_async].
+/// Evaluation is suspended while the returned future is pending.
+///
+/// Parameters are passed by value.  A `&str` parameter borrows from a copy of the argument string
+/// that lives as long as the future.
+///
+/// Available under the `async` feature only.
+#[cfg(feature = "async")]
+pub trait RegisterAsyncFn<FN, ARGS, RET> {
+    /// Register an asynchronous custom function with the [`Engine`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, RegisterAsyncFn};
+    ///
+    /// async fn lookup(id: i64) -> String {
+    ///     format!("user #{}", id)
+    /// }
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // You must use the trait rhai::RegisterAsyncFn to get this method.
+    /// engine.register_async_fn("lookup", lookup);
+    /// ```
+    fn register_async_fn(&mut self, name: &str, f: FN) -> &mut Self;
+}
+
+/// Trait to register fallible asynchronous custom functions, whose futures resolve to
+/// [`Result`]`<`[`Dynamic`]`, `[`Box`]`<`[`EvalAltResult`]`>>`, with the [`Engine`].
+///
+/// Available under the `async` feature only.
+#[cfg(feature = "async")]
+pub trait RegisterAsyncResultFn<FN, ARGS> {
+    /// Register a fallible asynchronous custom function with the [`Engine`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, Dynamic, RegisterAsyncResultFn, EvalAltResult};
+    ///
+    /// async fn div(x: i64, y: i64) -> Result<Dynamic, Box<EvalAltResult>> {
+    ///     if y == 0 {
+    ///         Err("division by zero!".into())
+    ///     } else {
+    ///         Ok((x / y).into())
+    ///     }
+    /// }
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // You must use the trait rhai::RegisterAsyncResultFn to get this method.
+    /// engine.register_async_result_fn("div", div);
+    /// ```
+    fn register_async_result_fn(&mut self, name: &str, f: FN) -> &mut Self;
 }
 
 // These types are used to build a unique _marker_ tuple type for each combination
@@ -140,6 +242,66 @@ macro_rules! make_func {
 	};
 }
 
+/// Dereference into value for an asynchronous function.
+///
+/// A `&str` parameter borrows from a clone of the argument string, which is kept in `strings`
+/// so that it lives as long as the future of the function call.
+#[cfg(feature = "async")]
+#[inline(always)]
+fn by_value_owned<T: Variant + Clone>(
+    data: &mut Dynamic,
+    strings: &mut StaticVec<ImmutableString>,
+) -> T {
+    if TypeId::of::<T>() == TypeId::of::<&str>() {
+        let s = data.clone().take_immutable_string().unwrap();
+        // The string data is not moved when the `ImmutableString` is moved into `strings`
+        let ref_str = unsafe { &*(s.as_str() as *const str) };
+        strings.push(s);
+        let ref_T = unsafe { mem::transmute::<&&str, &T>(&ref_str) };
+        ref_T.clone()
+    } else {
+        by_value(data)
+    }
+}
+
+/// Future of an asynchronous function call, owning the strings borrowed by its `&str` parameters.
+///
+/// The future is dropped before the strings.
+#[cfg(feature = "async")]
+struct AsyncFnCall {
+    future: AsyncFnFuture,
+    _strings: StaticVec<ImmutableString>,
+}
+
+#[cfg(feature = "async")]
+impl Future for AsyncFnCall {
+    type Output = Result<Dynamic, Box<EvalAltResult>>;
+
+    #[inline(always)]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// This macro creates a closure wrapping a registered asynchronous function.
+#[cfg(feature = "async")]
+macro_rules! make_async_func {
+	($fn:ident : $map:expr ; $($par:ident),*) => {
+		Box::new(move |ctx: NativeCallContext, args: &mut FnCallArgs| {
+            // The arguments are assumed to be of the correct number and types!
+
+			let mut _drain = args.iter_mut();
+			let mut _strings = StaticVec::new();
+			$(let $par = by_value_owned::<$par>(_drain.next().unwrap(), &mut _strings); )*
+
+            // Start the future and suspend evaluation until it completes
+			let fut = $fn($($par),*);
+			let future: AsyncFnFuture = Box::pin(async move { $map(fut.await) });
+			await_future(ctx, Box::pin(AsyncFnCall { future, _strings }))
+		}) as Box<FnAny>
+	};
+}
+
 /// To Dynamic mapping function.
 #[inline(always)]
 pub fn map_dynamic(data: impl Variant + Clone) -> Result<Dynamic, Box<EvalAltResult>> {
@@ -171,6 +333,7 @@ fn map_type_id<T: 'static>() -> TypeId {
 macro_rules! def_register {
     () => {
         def_register!(imp from_pure :);
+        def_register!(imp_async :);
     };
     (imp $abi:ident : $($par:ident => $arg:expr => $mark:ty => $param:ty => $let:stmt => $clone:expr),*) => {
     //   ^ function ABI type
@@ -193,6 +356,15 @@ macro_rules! def_register {
                 );
                 self
             }
+            #[inline]
+            fn register_const_fn(&mut self, name: &str, f: FN) -> &mut Self {
+                let hash = self.global_namespace.set_fn(name, FnNamespace::Global, FnAccess::Public, None,
+                    &[$(map_type_id::<$par>()),*],
+                    CallableFunction::$abi(make_func!(f : map_dynamic ; $($par => $let => $clone => $arg),*))
+                );
+                self.global_namespace.update_fn_const(hash, true);
+                self
+            }
         }
 
         impl<
@@ -208,10 +380,55 @@ macro_rules! def_register {
                 );
                 self
             }
+            #[inline]
+            fn register_const_result_fn(&mut self, name: &str, f: FN) -> &mut Self {
+                let hash = self.global_namespace.set_fn(name, FnNamespace::Global, FnAccess::Public, None,
+                    &[$(map_type_id::<$par>()),*],
+                    CallableFunction::$abi(make_func!(f : map_result ; $($par => $let => $clone => $arg),*))
+                );
+                self.global_namespace.update_fn_const(hash, true);
+                self
+            }
         }
 
         //def_register!(imp_pop $($par => $mark => $param),*);
     };
+    (imp_async : $($par:ident),*) => {
+        #[cfg(feature = "async")]
+        impl<
+            $($par: Variant + Clone,)*
+            FN: Fn($($par),*) -> FUT + SendSync + 'static,
+            FUT: Future<Output = RET> + Send + 'static,
+            RET: Variant + Clone
+        > RegisterAsyncFn<FN, ($($par,)*), RET> for Engine
+        {
+            #[inline]
+            fn register_async_fn(&mut self, name: &str, f: FN) -> &mut Self {
+                self.global_namespace.set_fn(name, FnNamespace::Global, FnAccess::Public, None,
+                    &[$(map_type_id::<$par>()),*],
+                    CallableFunction::from_pure(make_async_func!(f : map_dynamic ; $($par),*))
+                );
+                self
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl<
+            $($par: Variant + Clone,)*
+            FN: Fn($($par),*) -> FUT + SendSync + 'static,
+            FUT: Future<Output = Result<Dynamic, Box<EvalAltResult>>> + Send + 'static,
+        > RegisterAsyncResultFn<FN, ($($par,)*)> for Engine
+        {
+            #[inline]
+            fn register_async_result_fn(&mut self, name: &str, f: FN) -> &mut Self {
+                self.global_namespace.set_fn(name, FnNamespace::Global, FnAccess::Public, None,
+                    &[$(map_type_id::<$par>()),*],
+                    CallableFunction::from_pure(make_async_func!(f : map_result ; $($par),*))
+                );
+                self
+            }
+        }
+    };
     ($p0:ident $(, $p:ident)*) => {
         def_register!(imp from_pure   : $p0 => $p0      => $p0      => $p0      => let $p0     => by_value $(, $p => $p => $p => $p => let $p => by_value)*);
         def_register!(imp from_method : $p0 => &mut $p0  => Mut<$p0> => &mut $p0 => let mut $p0 => by_ref   $(, $p => $p => $p => $p => let $p => by_value)*);
@@ -223,6 +440,8 @@ macro_rules! def_register {
         // conflicting implementations since &T: Any and T: Any cannot be distinguished
         //def_register!(imp $p0 => Ref<$p0> => &$p0     => by_ref   $(, $p => $p => $p => by_value)*);
 
+        def_register!(imp_async : $p0 $(, $p)*);
+
         def_register!($($p),*);
     };
 }
@@ -0,0 +1,456 @@
+//! Module implementing string formatting for the `format` and `fmt` functions.
+
+use crate::engine::{Imports, State, FN_TO_DEBUG, FN_TO_STRING};
+use crate::fn_native::FnCallArgs;
+use crate::stdlib::{
+    boxed::Box,
+    format,
+    iter::empty,
+    string::{String, ToString},
+};
+use crate::{
+    calc_script_fn_hash, Dynamic, Engine, EvalAltResult, ImmutableString, Module, Position, INT,
+};
+
+#[cfg(not(feature = "no_float"))]
+use crate::FLOAT;
+
+#[cfg(not(feature = "no_object"))]
+use crate::Map;
+
+/// Alignment of a formatted value within its width.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A format specification, i.e. the part of a placeholder following the `:`.
+///
+/// The syntax is `[[fill]align][+][#][0][width][.precision][type]`, where `align` is one of
+/// `<`, `^` and `>`, and `type` is one of `x`, `X`, `o`, `b` (integers only),
+/// `e`, `E` (floating-point numbers only) and `?` (debug format).
+#[derive(Debug, Clone)]
+struct FormatSpec {
+    fill: char,
+    align: Option<Align>,
+    sign: bool,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            sign: false,
+            alternate: false,
+            zero: false,
+            width: 0,
+            precision: None,
+            kind: None,
+        }
+    }
+}
+
+/// Parse an alignment character.
+fn parse_align(ch: char) -> Option<Align> {
+    match ch {
+        '<' => Some(Align::Left),
+        '^' => Some(Align::Center),
+        '>' => Some(Align::Right),
+        _ => None,
+    }
+}
+
+/// Parse a number of a format specification.
+fn parse_number(spec: &str, chars: &[char], index: &mut usize) -> Result<Option<usize>, String> {
+    let start = *index;
+
+    while *index < chars.len() && chars[*index].is_ascii_digit() {
+        *index += 1;
+    }
+
+    if *index == start {
+        return Ok(None);
+    }
+
+    chars[start..*index]
+        .iter()
+        .collect::<String>()
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("Invalid format specification '{}'", spec))
+}
+
+impl FormatSpec {
+    /// Parse a format specification.
+    fn parse(spec: &str) -> Result<Self, String> {
+        let chars: crate::StaticVec<char> = spec.chars().collect();
+        let mut result = Self::default();
+        let mut index = 0;
+
+        // Fill and alignment
+        if let Some(align) = chars.get(1).cloned().and_then(parse_align) {
+            result.fill = chars[0];
+            result.align = Some(align);
+            index = 2;
+        } else if let Some(align) = chars.first().cloned().and_then(parse_align) {
+            result.align = Some(align);
+            index = 1;
+        }
+
+        // Flags
+        if chars.get(index) == Some(&'+') {
+            result.sign = true;
+            index += 1;
+        }
+        if chars.get(index) == Some(&'#') {
+            result.alternate = true;
+            index += 1;
+        }
+        if chars.get(index) == Some(&'0') {
+            result.zero = true;
+            index += 1;
+        }
+
+        // Width and precision
+        result.width = parse_number(spec, &chars, &mut index)?.unwrap_or(0);
+
+        if chars.get(index) == Some(&'.') {
+            index += 1;
+            match parse_number(spec, &chars, &mut index)? {
+                Some(precision) => result.precision = Some(precision),
+                None => return Err(format!("Invalid format specification '{}'", spec)),
+            }
+        }
+
+        // Type
+        match chars.get(index) {
+            None => (),
+            Some(&ch) if index + 1 == chars.len() => match ch {
+                'x' | 'X' | 'o' | 'b' | 'e' | 'E' | '?' => result.kind = Some(ch),
+                _ => return Err(format!("Unknown format type '{}'", ch)),
+            },
+            Some(_) => return Err(format!("Invalid format specification '{}'", spec)),
+        }
+
+        Ok(result)
+    }
+
+    /// Pad a formatted value to the width of this specification.
+    ///
+    /// Numbers are right-aligned and all other values are left-aligned by default.
+    /// With the `0` flag, numbers are padded with zeros after the sign and radix prefix.
+    fn pad(&self, prefix: &str, body: &str, is_number: bool) -> String {
+        let len = prefix.chars().count() + body.chars().count();
+
+        if len >= self.width {
+            return format!("{}{}", prefix, body);
+        }
+
+        let padding = self.width - len;
+
+        if is_number && self.zero {
+            return format!("{}{}{}", prefix, "0".repeat(padding), body);
+        }
+
+        let (before, after) = match self.align {
+            Some(Align::Left) => (0, padding),
+            Some(Align::Center) => (padding / 2, padding - padding / 2),
+            Some(Align::Right) => (padding, 0),
+            None if is_number => (padding, 0),
+            None => (0, padding),
+        };
+
+        let fill = self.fill.to_string();
+        format!(
+            "{}{}{}{}",
+            fill.repeat(before),
+            prefix,
+            body,
+            fill.repeat(after)
+        )
+    }
+}
+
+/// Get the sign of a number, as displayed with the `+` flag.
+fn sign_prefix(negative: bool, spec: &FormatSpec) -> &'static str {
+    if negative {
+        "-"
+    } else if spec.sign {
+        "+"
+    } else {
+        ""
+    }
+}
+
+impl Engine {
+    /// Format a template string (the first argument) with the rest of the arguments.
+    ///
+    /// Placeholders are `{}` (the next positional argument), `{index}` (the positional argument
+    /// at `index`, starting from zero) or `{name}` (the property `name` of an object map passed
+    /// as the last argument), optionally followed by `:` and a format specification.
+    /// `{{` and `}}` stand for literal braces.
+    pub(crate) fn format_string(
+        &self,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        args: &mut FnCallArgs,
+        pos: Position,
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let template = args[0].clone().take_immutable_string().unwrap();
+        let values = &args[1..];
+
+        #[cfg(not(feature = "no_object"))]
+        let named = values
+            .last()
+            .and_then(|v| v.read_lock::<Map>().map(|map| map.clone()));
+
+        let make_err = |msg: String| -> Box<EvalAltResult> {
+            EvalAltResult::ErrorRuntime(msg.into(), pos).into()
+        };
+
+        let mut result = String::with_capacity(template.len());
+        let mut chars = template.chars();
+        let mut next_index = 0;
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    result.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    result.push('}');
+                }
+                '}' => return Err(make_err("Unmatched '}' in format string".to_string())),
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest.find('}').ok_or_else(|| {
+                        make_err("Unterminated placeholder in format string".to_string())
+                    })?;
+                    let placeholder = &rest[..end];
+                    chars = rest[end + 1..].chars();
+
+                    let (arg, spec) = match placeholder.find(':') {
+                        Some(index) => (&placeholder[..index], &placeholder[index + 1..]),
+                        None => (placeholder, ""),
+                    };
+                    let spec = FormatSpec::parse(spec).map_err(make_err)?;
+
+                    let mut value = if arg.is_empty() {
+                        next_index += 1;
+                        values.get(next_index - 1).map(|v| Dynamic::clone(v))
+                    } else if let Ok(index) = arg.parse::<usize>() {
+                        values.get(index).map(|v| Dynamic::clone(v))
+                    } else {
+                        #[cfg(not(feature = "no_object"))]
+                        let value = named.as_ref().and_then(|map| map.get(arg)).cloned();
+                        #[cfg(feature = "no_object")]
+                        let value = None;
+
+                        match value {
+                            Some(value) => Some(value),
+                            None => {
+                                return Err(make_err(format!(
+                                    "Missing named argument '{}' in format string",
+                                    arg
+                                )))
+                            }
+                        }
+                    }
+                    .ok_or_else(|| {
+                        make_err(format!(
+                            "Argument index out of range in format string: {} argument(s) given",
+                            values.len()
+                        ))
+                    })?
+                    .flatten();
+
+                    #[cfg(not(feature = "unchecked"))]
+                    if self.max_string_size() > 0 && spec.width > self.max_string_size() {
+                        return EvalAltResult::ErrorDataTooLarge(
+                            "Length of string".to_string(),
+                            pos,
+                        )
+                        .into();
+                    }
+
+                    let text =
+                        self.format_value(mods, state, lib, &mut value, &spec, pos, level)?;
+                    result.push_str(&text);
+
+                    #[cfg(not(feature = "unchecked"))]
+                    if self.max_string_size() > 0 && result.len() > self.max_string_size() {
+                        return EvalAltResult::ErrorDataTooLarge(
+                            "Length of string".to_string(),
+                            pos,
+                        )
+                        .into();
+                    }
+                }
+                ch => result.push(ch),
+            }
+        }
+
+        #[cfg(not(feature = "unchecked"))]
+        if self.max_string_size() > 0 && result.len() > self.max_string_size() {
+            return EvalAltResult::ErrorDataTooLarge("Length of string".to_string(), pos).into();
+        }
+
+        Ok(result.into())
+    }
+
+    /// Format a value according to a format specification.
+    fn format_value(
+        &self,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        value: &mut Dynamic,
+        spec: &FormatSpec,
+        pos: Position,
+        level: usize,
+    ) -> Result<String, Box<EvalAltResult>> {
+        let make_err = |msg: String| -> Box<EvalAltResult> {
+            EvalAltResult::ErrorRuntime(msg.into(), pos).into()
+        };
+
+        if let Ok(n) = value.as_int() {
+            if spec.precision.is_some() {
+                return Err(make_err(
+                    "Precision is not allowed for integers in format string".to_string(),
+                ));
+            }
+
+            let negative = n < 0;
+            // Format the magnitude so that negative numbers in other radixes keep their sign
+            let n = n.checked_abs().map_or(INT::MAX as u64 + 1, |n| n as u64);
+
+            let (radix, body) = match spec.kind {
+                Some('x') => ("0x", format!("{:x}", n)),
+                Some('X') => ("0x", format!("{:X}", n)),
+                Some('o') => ("0o", format!("{:o}", n)),
+                Some('b') => ("0b", format!("{:b}", n)),
+                Some('e') | Some('E') => {
+                    return Err(make_err(
+                        "Exponent format is not allowed for integers".to_string(),
+                    ))
+                }
+                _ => ("", n.to_string()),
+            };
+
+            let prefix = format!(
+                "{}{}",
+                sign_prefix(negative, spec),
+                if spec.alternate { radix } else { "" }
+            );
+            return Ok(spec.pad(&prefix, &body, true));
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        if let Ok(f) = value.as_float() {
+            let negative = f.is_sign_negative() && !f.is_nan();
+            let f: FLOAT = f.abs();
+
+            let body = match (spec.kind, spec.precision) {
+                (Some('e'), Some(precision)) => format!("{:.*e}", precision, f),
+                (Some('e'), None) => format!("{:e}", f),
+                (Some('E'), Some(precision)) => format!("{:.*E}", precision, f),
+                (Some('E'), None) => format!("{:E}", f),
+                (Some('x'), _) | (Some('X'), _) | (Some('o'), _) | (Some('b'), _) => {
+                    return Err(make_err(
+                        "Radix format is only allowed for integers".to_string(),
+                    ))
+                }
+                (_, Some(precision)) => format!("{:.*}", precision, f),
+                (_, None) => f.to_string(),
+            };
+
+            return Ok(spec.pad(sign_prefix(negative, spec), &body, true));
+        }
+
+        if spec.sign || spec.alternate || spec.zero {
+            return Err(make_err(format!(
+                "Numeric format flags are not allowed for {}",
+                self.map_value_type_name(value)
+            )));
+        }
+
+        let text = match spec.kind {
+            None => match value.clone().take_immutable_string() {
+                Ok(s) if value.is::<ImmutableString>() => s,
+                _ => self.stringify_value(mods, state, lib, FN_TO_STRING, value, pos, level)?,
+            },
+            Some('?') => self.stringify_value(mods, state, lib, FN_TO_DEBUG, value, pos, level)?,
+            Some(ch) => {
+                return Err(make_err(format!(
+                    "Format type '{}' is not allowed for {}",
+                    ch,
+                    self.map_value_type_name(value)
+                )))
+            }
+        };
+
+        // The precision truncates other values
+        Ok(match spec.precision {
+            Some(precision) => {
+                spec.pad("", &text.chars().take(precision).collect::<String>(), false)
+            }
+            None => spec.pad("", &text, false),
+        })
+    }
+
+    /// Convert a value into a string by calling `to_string` (or `to_debug`), so that functions
+    /// registered for custom types (or defined in script) are used.
+    ///
+    /// Falls back to the type name of the value if there is no such function.
+    fn stringify_value(
+        &self,
+        mods: &mut Imports,
+        state: &mut State,
+        lib: &[&Module],
+        fn_name: &str,
+        value: &mut Dynamic,
+        pos: Position,
+        level: usize,
+    ) -> Result<ImmutableString, Box<EvalAltResult>> {
+        let hash_script = calc_script_fn_hash(empty(), fn_name, 1);
+
+        match self.exec_fn_call(
+            mods,
+            state,
+            lib,
+            fn_name,
+            hash_script,
+            &mut [&mut *value],
+            false,
+            true,
+            false,
+            pos,
+            None,
+            None,
+            level,
+        ) {
+            Ok((result, _)) if result.is::<ImmutableString>() => {
+                Ok(result.take_immutable_string().unwrap())
+            }
+            Ok((result, _)) => Ok(self.map_type_name(result.type_name()).into()),
+            Err(err) => match *err {
+                EvalAltResult::ErrorFunctionNotFound(_, _) => {
+                    Ok(self.map_type_name(value.type_name()).into())
+                }
+                _ => Err(err),
+            },
+        }
+    }
+}
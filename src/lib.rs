@@ -62,15 +62,25 @@
 extern crate alloc;
 
 mod ast;
+mod ast_cache;
+mod build_type;
+#[cfg(feature = "bytecode")]
+mod bytecode;
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+mod class;
 mod dynamic;
 mod engine;
 mod engine_api;
 mod engine_settings;
 mod fn_args;
+#[cfg(feature = "async")]
+mod fn_async;
 mod fn_call;
 mod fn_func;
 mod fn_native;
 mod fn_register;
+mod format;
 mod module;
 mod optimize;
 pub mod packages;
@@ -120,10 +130,23 @@ pub type FLOAT = f64;
 pub type FLOAT = f32;
 
 pub use ast::{FnAccess, ScriptFnMetadata, AST};
+pub use ast_cache::CacheError;
+pub use build_type::{CustomType, TypeBuilder};
+#[cfg(feature = "bytecode")]
+pub use bytecode::ExecutionBackend;
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+pub use class::ClassInstance;
 pub use dynamic::Dynamic;
 pub use engine::{Engine, EvalContext};
 pub use fn_native::{FnPtr, NativeCallContext, Shared};
 pub use fn_register::{RegisterFn, RegisterResultFn};
+
+#[cfg(feature = "async")]
+pub use fn_async::EvalAsync;
+
+#[cfg(feature = "async")]
+pub use fn_register::{RegisterAsyncFn, RegisterAsyncResultFn};
 pub use module::{FnNamespace, Module};
 pub use parse_error::{LexError, ParseError, ParseErrorType};
 pub use result::EvalAltResult;
@@ -191,10 +214,20 @@ pub use ast::{
     ScriptFnDef, Stmt,
 };
 
+#[cfg(feature = "internals")]
+#[cfg(not(feature = "no_object"))]
+#[deprecated = "this type is volatile and may change"]
+pub use ast::ClassDef;
+
 #[cfg(feature = "internals")]
 #[deprecated = "this type is volatile and may change"]
 pub use engine::{Imports, State as EvalState};
 
+#[cfg(feature = "internals")]
+#[cfg(not(feature = "no_module"))]
+#[deprecated = "this type is volatile and may change"]
+pub use engine::ImportChain;
+
 #[cfg(feature = "internals")]
 #[cfg(not(feature = "unchecked"))]
 pub use engine::Limits;
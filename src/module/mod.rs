@@ -2,6 +2,7 @@
 
 use crate::ast::{FnAccess, Ident};
 use crate::dynamic::Variant;
+use crate::engine::{FN_HASH, FN_TO_DEBUG, FN_TO_STRING, KEYWORD_DEBUG, KEYWORD_PRINT};
 use crate::fn_native::{shared_take_or_clone, CallableFunction, FnCallArgs, IteratorFn, SendSync};
 use crate::fn_register::by_value as cast_arg;
 use crate::stdlib::{
@@ -9,17 +10,18 @@ use crate::stdlib::{
     boxed::Box,
     collections::HashMap,
     fmt, format,
+    hash::{Hash, Hasher},
     iter::empty,
     num::NonZeroU64,
     num::NonZeroUsize,
-    ops::{Add, AddAssign, Deref, DerefMut},
+    ops::{Add, AddAssign, Deref, DerefMut, Div, Mul, Neg, Sub},
     string::{String, ToString},
     vec::Vec,
 };
 use crate::token::Token;
-use crate::utils::{combine_hashes, StraightHasherBuilder};
+use crate::utils::{combine_hashes, get_hasher, StraightHasherBuilder};
 use crate::{
-    Dynamic, EvalAltResult, ImmutableString, NativeCallContext, Position, Shared, StaticVec,
+    Dynamic, EvalAltResult, ImmutableString, NativeCallContext, Position, Shared, StaticVec, INT,
 };
 
 #[cfg(not(feature = "no_function"))]
@@ -83,6 +85,8 @@ pub struct FuncInfo {
     pub param_types: StaticVec<TypeId>,
     /// Parameter names (if available).
     pub param_names: StaticVec<ImmutableString>,
+    /// Is the function deterministic and free of side effects, so that it can be evaluated at compile time?
+    pub is_const: bool,
 }
 
 impl FuncInfo {
@@ -478,7 +482,15 @@ impl Module {
         // None + function name + number of arguments.
         let num_params = fn_def.params.len();
         let hash_script = crate::calc_script_fn_hash(empty(), &fn_def.name, num_params).unwrap();
-        let mut param_names: StaticVec<_> = fn_def.params.iter().cloned().collect();
+        let mut param_names: StaticVec<_> = fn_def
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| match fn_def.param_type(i) {
+                Some(typ) => format!("{}: {}", p, typ).into(),
+                None => p.clone(),
+            })
+            .collect();
         param_names.push("Dynamic".into());
         self.functions.insert(
             hash_script,
@@ -489,6 +501,7 @@ impl Module {
                 params: num_params,
                 param_types: Default::default(),
                 param_names,
+                is_const: false,
                 func: fn_def.into(),
             },
         );
@@ -575,6 +588,13 @@ impl Module {
         self.modules.get(name).map(|m| m.as_ref())
     }
 
+    /// Get a shared reference to a sub-module.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub(crate) fn get_sub_module_shared(&self, name: &str) -> Option<Shared<Module>> {
+        self.modules.get(name).cloned()
+    }
+
     /// Set a sub-module into the [`Module`].
     ///
     /// If there is an existing sub-module of the same name, it is replaced.
@@ -670,6 +690,80 @@ impl Module {
         self
     }
 
+    /// Mark a registered function as `const`, i.e. deterministic and free of side effects.
+    ///
+    /// A `const` function may be evaluated at compile time by the optimizer under
+    /// [`OptimizationLevel::Full`][crate::OptimizationLevel::Full] when all its arguments are constants.
+    /// It must not read the time, random numbers or any other host state.
+    ///
+    /// The [`NonZeroU64`] hash is calculated either by the function
+    /// [`calc_native_fn_hash`][crate::calc_native_fn_hash] or the function
+    /// [`calc_script_fn_hash`][crate::calc_script_fn_hash].
+    #[inline(always)]
+    pub fn update_fn_const(&mut self, hash_fn: NonZeroU64, is_const: bool) -> &mut Self {
+        if let Some(f) = self.functions.get_mut(&hash_fn) {
+            f.is_const = is_const;
+        }
+        self
+    }
+
+    /// Copy all public functions of a particular name from another [`Module`] into this
+    /// [`Module`], under a new name and namespace.
+    ///
+    /// Returns `true` if any function is copied.
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) fn copy_fn_from(
+        &mut self,
+        module: &Module,
+        name: &str,
+        new_name: &str,
+        namespace: FnNamespace,
+    ) -> bool {
+        let mut copied = false;
+
+        module
+            .functions
+            .values()
+            .filter(|f| f.access.is_public() && f.name == name)
+            .for_each(|f| {
+                self.insert_fn_info(f, new_name, namespace);
+                copied = true;
+            });
+
+        copied
+    }
+
+    /// Insert a copy of a registered function under a new name and namespace.
+    #[cfg(not(feature = "no_module"))]
+    fn insert_fn_info(&mut self, info: &FuncInfo, name: &str, namespace: FnNamespace) {
+        let (hash_fn, func) = match info.func {
+            #[cfg(not(feature = "no_function"))]
+            CallableFunction::Script(ref fn_def) => {
+                let mut fn_def = fn_def.as_ref().clone();
+                fn_def.name = name.into();
+                let hash_script = crate::calc_script_fn_hash(empty(), name, info.params).unwrap();
+                (hash_script, fn_def.into())
+            }
+            ref func => {
+                let hash_fn =
+                    crate::calc_native_fn_hash(empty(), name, info.param_types.iter().cloned())
+                        .unwrap();
+                (hash_fn, func.clone())
+            }
+        };
+
+        self.functions.insert(
+            hash_fn,
+            FuncInfo {
+                func,
+                namespace,
+                name: name.into(),
+                ..info.clone()
+            },
+        );
+        self.indexed = false;
+    }
+
     /// Set a Rust function into the [`Module`], returning a hash key.
     ///
     /// If there is an existing Rust function of the same hash, it is replaced.
@@ -717,6 +811,7 @@ impl Module {
                 } else {
                     Default::default()
                 },
+                is_const: false,
                 func: func.into(),
             },
         );
@@ -1459,6 +1554,157 @@ impl Module {
         )
     }
 
+    /// Set `to_string` and `print` functions for a custom type into the [`Module`], formatting
+    /// it via its [`Display`][fmt::Display] implementation.
+    ///
+    /// The functions are exposed to the global namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Module, ImmutableString};
+    ///
+    /// let mut module = Module::new();
+    /// module.set_display_fns::<ImmutableString>();
+    /// assert_eq!(module.count().1, 2);
+    /// ```
+    #[inline]
+    pub fn set_display_fns<T: Variant + Clone + fmt::Display>(&mut self) -> &mut Self {
+        for &name in &[FN_TO_STRING, KEYWORD_PRINT] {
+            self.set_fn_1_mut(name, FnNamespace::Global, |x: &mut T| {
+                Ok(ImmutableString::from(x.to_string()))
+            });
+        }
+        self
+    }
+
+    /// Set `to_debug` and `debug` functions for a custom type into the [`Module`], formatting
+    /// it via its [`Debug`][fmt::Debug] implementation.
+    ///
+    /// The functions are exposed to the global namespace.
+    #[inline]
+    pub fn set_debug_fns<T: Variant + Clone + fmt::Debug>(&mut self) -> &mut Self {
+        for &name in &[FN_TO_DEBUG, KEYWORD_DEBUG] {
+            self.set_fn_1_mut(name, FnNamespace::Global, |x: &mut T| {
+                Ok(ImmutableString::from(format!("{:?}", x)))
+            });
+        }
+        self
+    }
+
+    /// Set the equality operators `==` and `!=` for a custom type into the [`Module`], via its
+    /// [`PartialEq`] implementation.
+    ///
+    /// The functions are exposed to the global namespace.
+    #[inline]
+    pub fn set_equality_fns<T: Variant + Clone + PartialEq>(&mut self) -> &mut Self {
+        self.set_fn_2_mut("==", FnNamespace::Global, |x: &mut T, y: T| Ok(*x == y));
+        self.set_fn_2_mut("!=", FnNamespace::Global, |x: &mut T, y: T| Ok(*x != y));
+        self
+    }
+
+    /// Set the comparison operators `==`, `!=`, `<`, `<=`, `>` and `>=` for a custom type into
+    /// the [`Module`], via its [`PartialOrd`] implementation.
+    ///
+    /// The functions are exposed to the global namespace.
+    #[inline]
+    pub fn set_comparison_fns<T: Variant + Clone + PartialOrd>(&mut self) -> &mut Self {
+        self.set_equality_fns::<T>();
+        self.set_fn_2_mut("<", FnNamespace::Global, |x: &mut T, y: T| Ok(*x < y));
+        self.set_fn_2_mut("<=", FnNamespace::Global, |x: &mut T, y: T| Ok(*x <= y));
+        self.set_fn_2_mut(">", FnNamespace::Global, |x: &mut T, y: T| Ok(*x > y));
+        self.set_fn_2_mut(">=", FnNamespace::Global, |x: &mut T, y: T| Ok(*x >= y));
+        self
+    }
+
+    /// Set the `+` operator for a custom type into the [`Module`], via its [`Add`] implementation.
+    ///
+    /// The function is exposed to the global namespace.
+    #[inline]
+    pub fn set_add_fn<T: Variant + Clone + Add<Output = T>>(&mut self) -> &mut Self {
+        let hash = self.set_fn_2("+", |x: T, y: T| Ok(x + y));
+        self.update_fn_namespace(hash, FnNamespace::Global)
+    }
+
+    /// Set the `-` operator for a custom type into the [`Module`], via its [`Sub`] implementation.
+    ///
+    /// The function is exposed to the global namespace.
+    #[inline]
+    pub fn set_sub_fn<T: Variant + Clone + Sub<Output = T>>(&mut self) -> &mut Self {
+        let hash = self.set_fn_2("-", |x: T, y: T| Ok(x - y));
+        self.update_fn_namespace(hash, FnNamespace::Global)
+    }
+
+    /// Set the `*` operator for a custom type into the [`Module`], via its [`Mul`] implementation.
+    ///
+    /// The function is exposed to the global namespace.
+    #[inline]
+    pub fn set_mul_fn<T: Variant + Clone + Mul<Output = T>>(&mut self) -> &mut Self {
+        let hash = self.set_fn_2("*", |x: T, y: T| Ok(x * y));
+        self.update_fn_namespace(hash, FnNamespace::Global)
+    }
+
+    /// Set the `/` operator for a custom type into the [`Module`], via its [`Div`] implementation.
+    ///
+    /// The function is exposed to the global namespace.
+    #[inline]
+    pub fn set_div_fn<T: Variant + Clone + Div<Output = T>>(&mut self) -> &mut Self {
+        let hash = self.set_fn_2("/", |x: T, y: T| Ok(x / y));
+        self.update_fn_namespace(hash, FnNamespace::Global)
+    }
+
+    /// Set the unary `-` operator for a custom type into the [`Module`], via its [`Neg`]
+    /// implementation.
+    ///
+    /// The function is exposed to the global namespace.
+    #[inline]
+    pub fn set_neg_fn<T: Variant + Clone + Neg<Output = T>>(&mut self) -> &mut Self {
+        let hash = self.set_fn_1("-", |x: T| Ok(-x));
+        self.update_fn_namespace(hash, FnNamespace::Global)
+    }
+
+    /// Set the arithmetic operators `+`, `-`, `*`, `/` and unary `-` for a custom type into the
+    /// [`Module`], via its [`Add`], [`Sub`], [`Mul`], [`Div`] and [`Neg`] implementations.
+    ///
+    /// For a type implementing only some of these traits, use [`set_add_fn`][Module::set_add_fn],
+    /// [`set_sub_fn`][Module::set_sub_fn], [`set_mul_fn`][Module::set_mul_fn],
+    /// [`set_div_fn`][Module::set_div_fn] and [`set_neg_fn`][Module::set_neg_fn] instead.
+    ///
+    /// The functions are exposed to the global namespace.
+    #[inline]
+    pub fn set_arithmetic_fns<T>(&mut self) -> &mut Self
+    where
+        T: Variant
+            + Clone
+            + Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Neg<Output = T>,
+    {
+        self.set_add_fn::<T>()
+            .set_sub_fn::<T>()
+            .set_mul_fn::<T>()
+            .set_div_fn::<T>()
+            .set_neg_fn::<T>()
+    }
+
+    /// Set a `hash` function for a custom type into the [`Module`], returning the hash of a
+    /// value (as an integer) via its [`Hash`] implementation.
+    ///
+    /// The hash value is only stable within the same process.
+    ///
+    /// The function is exposed to the global namespace.
+    #[inline]
+    pub fn set_hash_fn<T: Variant + Clone + Hash>(&mut self) -> &mut Self {
+        self.set_fn_1_mut(FN_HASH, FnNamespace::Global, |x: &mut T| {
+            let hasher = &mut get_hasher();
+            x.hash(hasher);
+            Ok(hasher.finish() as INT)
+        });
+        self
+    }
+
     /// Get a Rust function.
     ///
     /// The [`NonZeroU64`] hash is calculated by the function [`calc_native_fn_hash`][crate::calc_native_fn_hash].
@@ -1478,6 +1724,14 @@ impl Module {
             })
     }
 
+    /// Is a Rust function marked as `const`?
+    ///
+    /// Returns [`None`] if the function does not exist.
+    #[inline(always)]
+    pub(crate) fn is_fn_const(&self, hash_fn: NonZeroU64) -> Option<bool> {
+        self.functions.get(&hash_fn).map(|f| f.is_const)
+    }
+
     /// Does the particular namespace-qualified function exist in the [`Module`]?
     ///
     /// The [`NonZeroU64`] hash is calculated by the function
@@ -1715,6 +1969,17 @@ impl Module {
         )
     }
 
+    /// Does any script-defined function in the [`Module`] need the global constants at runtime?
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) fn needs_global_constants(&self) -> bool {
+        #[cfg(not(feature = "no_function"))]
+        return self
+            .iter_script_fn()
+            .any(|(_, _, _, _, fn_def)| fn_def.needs_global_constants());
+        #[cfg(feature = "no_function")]
+        return false;
+    }
+
     /// Get an iterator over all script-defined functions in the [`Module`].
     ///
     /// Function metadata includes:
@@ -1764,6 +2029,18 @@ impl Module {
     /// defined in the [`Module`], are _merged_ into a _unified_ namespace before each call.
     /// Therefore, all functions will be found.
     ///
+    /// Functions defined in the [`Module`] can also read the exported variables of the
+    /// [`Module`] (plus non-exported ones, if enabled via
+    /// [`Engine::enable_private_module_vars`][crate::Engine::enable_private_module_vars])
+    /// by name.  Parameters and local variables of the same name shadow them.
+    ///
+    /// * Constants are read-only.
+    ///
+    /// * Other variables hold module state that is shared among all functions of the [`Module`],
+    ///   so changes made by one call are seen by subsequent calls, as well as by code reading the
+    ///   exported variable.  Under `no_closure`, each call starts with its own copy instead, and
+    ///   changes are discarded when the call returns.
+    ///
     /// # Example
     ///
     /// ```
@@ -1793,7 +2070,30 @@ impl Module {
         // Create new module
         let mut module = Module::new();
 
-        scope.into_iter().for_each(|(_, value, mut aliases)| {
+        // Variables visible to module functions
+        #[cfg(not(feature = "no_function"))]
+        let has_functions = ast.lib().functions.values().any(|f| f.func.is_script());
+        #[cfg(not(feature = "no_function"))]
+        let mut fn_vars: StaticVec<(ImmutableString, Dynamic)> = Default::default();
+
+        scope.into_iter().for_each(|(_name, value, mut aliases)| {
+            #[cfg(not(feature = "no_function"))]
+            let value = if has_functions && (!aliases.is_empty() || engine.private_module_vars) {
+                // Mutable variables are shared among the module, and all module functions
+                #[cfg(not(feature = "no_closure"))]
+                let value = if value.is_read_only() {
+                    value
+                } else {
+                    value.into_shared()
+                };
+                let mut var_value = value.clone();
+                var_value.set_access_mode(value.access_mode());
+                fn_vars.push((_name.into_owned().into(), var_value));
+                value
+            } else {
+                value
+            };
+
             // Variables with an alias left in the scope become module variables
             if aliases.len() > 1 {
                 aliases.into_iter().for_each(|alias| {
@@ -1805,25 +2105,65 @@ impl Module {
         });
 
         // Extra modules left in the scope become sub-modules
+        // (except for imported members, which are merged into the module when re-exported)
         let mut func_mods: crate::engine::Imports = Default::default();
 
         mods.into_iter().skip(orig_mods_len).for_each(|(alias, m)| {
             func_mods.push(alias.clone(), m.clone());
-            module.set_sub_module(alias, m);
+
+            match alias.as_str() {
+                // Imported members are not exported
+                crate::engine::IMPORTED_MEMBERS => (),
+                // Re-exported members become module functions
+                crate::engine::EXPORTED_MEMBERS => m
+                    .functions
+                    .values()
+                    .for_each(|f| module.insert_fn_info(f, &f.name, FnNamespace::Internal)),
+                _ => {
+                    module.set_sub_module(alias, m);
+                }
+            }
         });
 
         // Non-private functions defined become module functions
         #[cfg(not(feature = "no_function"))]
         {
+            let fn_vars: Option<Shared<_>> = if fn_vars.is_empty() {
+                None
+            } else {
+                Some(fn_vars.into())
+            };
+
             ast.lib()
                 .functions
                 .values()
                 .filter(|FuncInfo { access, func, .. }| !access.is_private() && func.is_script())
                 .for_each(|FuncInfo { func, .. }| {
                     // Encapsulate AST environment
+                    let encapsulate = |func: &mut ScriptFnDef| {
+                        func.lib = Some(ast.shared_lib());
+                        func.mods = func_mods.clone();
+                        func.vars = fn_vars.clone();
+                    };
                     let mut func = func.get_fn_def().clone();
-                    func.lib = Some(ast.shared_lib());
-                    func.mods = func_mods.clone();
+                    encapsulate(&mut func);
+                    func.overloads.iter_mut().for_each(encapsulate);
+
+                    // Encapsulate the methods of the class constructed
+                    #[cfg(not(feature = "no_object"))]
+                    if let Some(class) = func.class.take() {
+                        let mut class = shared_take_or_clone(class);
+                        class.methods.values_mut().for_each(|method| {
+                            encapsulate(method);
+                            method.overloads.iter_mut().for_each(encapsulate);
+                        });
+                        let class: Shared<_> = class.into();
+                        func.class = Some(class.clone());
+                        func.overloads
+                            .iter_mut()
+                            .for_each(|f| f.class = Some(class.clone()));
+                    }
+
                     module.set_script_fn(func);
                 });
         }
@@ -2063,6 +2403,12 @@ impl NamespaceRef {
     pub(crate) fn set_index(&mut self, index: Option<NonZeroUsize>) {
         self.index = index
     }
+    /// Is this the `global` namespace?
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub(crate) fn is_global(&self) -> bool {
+        self.path.len() == 1 && self.path[0].name == crate::engine::KEYWORD_GLOBAL
+    }
 }
 
 #[cfg(not(feature = "no_module"))]
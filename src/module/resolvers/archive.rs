@@ -0,0 +1,379 @@
+use crate::stdlib::{
+    boxed::Box,
+    collections::HashMap,
+    fs::File,
+    io::{Error as IoError, ErrorKind, Read},
+    path::Path,
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::{
+    module::resolvers::SourceModuleResolver, Engine, EvalAltResult, Module, ModuleResolver,
+    Position, Shared, AST,
+};
+use serde_json::Value;
+
+/// Name of the manifest file at the root of a script package archive.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Make an [`ErrorSystem`][EvalAltResult::ErrorSystem] for a script package archive.
+fn make_archive_err(desc: &str, err: IoError) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorSystem(desc.into(), err.into()).into()
+}
+
+/// Make an [`ErrorSystem`][EvalAltResult::ErrorSystem] for an invalid manifest.
+fn make_manifest_err(message: impl Into<String>) -> Box<EvalAltResult> {
+    make_archive_err(
+        "Invalid package manifest",
+        IoError::new(ErrorKind::InvalidData, message.into()),
+    )
+}
+
+/// Decode a file in a script package archive as UTF-8 text.
+fn decode_file<'a>(file: &str, contents: &'a [u8]) -> Result<&'a str, Box<EvalAltResult>> {
+    crate::stdlib::str::from_utf8(contents).map_err(|err| {
+        make_archive_err(
+            &format!("Cannot decode file '{}' in package archive", file),
+            IoError::new(ErrorKind::InvalidData, err),
+        )
+    })
+}
+
+/// Is a package version of the form `MAJOR.MINOR.PATCH`, optionally followed by a pre-release
+/// (`-...`) and/or build metadata (`+...`) suffix made up of ASCII alphanumerics, `.` and `-`?
+fn is_valid_version(version: &str) -> bool {
+    let (core, suffix) = match version.find(&['-', '+'][..]) {
+        Some(index) => version.split_at(index),
+        None => (version, ""),
+    };
+
+    let numbers: Vec<&str> = core.split('.').collect();
+
+    numbers.len() == 3
+        && numbers
+            .iter()
+            .all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        && suffix.len() != 1
+        && suffix
+            .chars()
+            .skip(1)
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '+')
+}
+
+/// A [module][Module] resolution service that serves [modules][Module] out of a script package
+/// bundled into a single `tar` archive.
+///
+/// Exported under the `archive` feature only.
+///
+/// The archive is read in full when the resolver is created; files are never extracted to disk.
+/// Each [module][Module] is compiled the first time it is resolved and cached internally for
+/// subsequent requests.
+///
+/// ## Manifest
+///
+/// The root of the archive must contain a `manifest.json` file describing the package:
+///
+/// ```json
+/// {
+///     "name": "pkg",
+///     "version": "1.0.0",
+///     "entry": "main",
+///     "modules": {
+///         "main": "src/main.rhai",
+///         "sub": "src/sub.rhai"
+///     }
+/// }
+/// ```
+///
+/// * `name` (required) is the package name, which is the first part of all `import` paths.
+///   For example, `import "pkg/sub"` resolves the `sub` module.  It cannot be empty or contain `/`.
+///
+/// * `version` (required) is the package version, in the form `MAJOR.MINOR.PATCH` optionally
+///   followed by a `-pre-release` and/or `+build` suffix (e.g. `1.0.0`, `2.1.0-beta.1`).
+///
+/// * `entry` (optional) is the module resolved by importing the package name itself,
+///   i.e. `import "pkg"`.
+///
+/// * `modules` (optional) maps module paths to script files in the archive.
+///   If omitted, each `.rhai` file in the archive is a module with the file path (without extension)
+///   as its module path.
+///
+/// Other fields are ignored.
+///
+/// ## Relative Paths
+///
+/// Within the package, an `import` path starting with `./` or `../` is resolved relative to the
+/// importing module. For example, `import "./util"` within `pkg/net/http` resolves `pkg/net/util`.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::Engine;
+/// use rhai::module_resolvers::ArchiveModuleResolver;
+///
+/// let resolver = ArchiveModuleResolver::from_path("./packages/pkg.tar")?;
+///
+/// let mut engine = Engine::new();
+/// engine.set_module_resolver(resolver);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ArchiveModuleResolver {
+    name: String,
+    version: String,
+    entry: Option<String>,
+    modules: SourceModuleResolver,
+}
+
+impl ArchiveModuleResolver {
+    /// Create a new [`ArchiveModuleResolver`] from a `tar` archive file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Box<EvalAltResult>> {
+        let path = path.as_ref();
+
+        let file = File::open(path).map_err(|err| {
+            make_archive_err(
+                &format!("Cannot open package archive '{}'", path.to_string_lossy()),
+                err,
+            )
+        })?;
+
+        Self::from_reader(file)
+    }
+    /// Create a new [`ArchiveModuleResolver`] from a `tar` archive held in memory.
+    #[inline(always)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<EvalAltResult>> {
+        Self::from_reader(bytes)
+    }
+    /// Create a new [`ArchiveModuleResolver`] from a `tar` archive read from a reader.
+    pub fn from_reader(reader: impl Read) -> Result<Self, Box<EvalAltResult>> {
+        let read_err = |err| make_archive_err("Cannot read package archive", err);
+
+        // Read all files in the archive
+        let mut files = HashMap::new();
+        let mut archive = tar::Archive::new(reader);
+
+        for entry in archive.entries().map_err(read_err)? {
+            let mut entry = entry.map_err(read_err)?;
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let path = entry
+                .path()
+                .map_err(read_err)?
+                .to_string_lossy()
+                .trim_start_matches("./")
+                .to_string();
+
+            // Files other than the manifest and the scripts may not be text
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents).map_err(read_err)?;
+
+            files.insert(path, contents);
+        }
+
+        // Parse the manifest
+        let manifest = files
+            .remove(MANIFEST_FILE)
+            .ok_or_else(|| make_manifest_err(format!("'{}' not found", MANIFEST_FILE)))?;
+
+        let mut manifest = match serde_json::from_str(decode_file(MANIFEST_FILE, &manifest)?) {
+            Ok(Value::Object(manifest)) => manifest,
+            Ok(_) => return Err(make_manifest_err("not a JSON object")),
+            Err(err) => return Err(make_manifest_err(err.to_string())),
+        };
+
+        let mut get_string = |key: &str| match manifest.remove(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(_) => Err(make_manifest_err(format!("'{}' is not a string", key))),
+        };
+
+        let name = get_string("name")?.ok_or_else(|| make_manifest_err("'name' not found"))?;
+        let version =
+            get_string("version")?.ok_or_else(|| make_manifest_err("'version' not found"))?;
+        let entry = get_string("entry")?;
+
+        if name.is_empty() || name.contains('/') {
+            return Err(make_manifest_err(format!(
+                "invalid package name '{}'",
+                name
+            )));
+        }
+        if !is_valid_version(&version) {
+            return Err(make_manifest_err(format!(
+                "invalid package version '{}'",
+                version
+            )));
+        }
+
+        let module_files: Vec<(String, String)> = match manifest.remove("modules") {
+            Some(Value::Object(modules)) => modules
+                .into_iter()
+                .map(|(path, file)| match file {
+                    Value::String(file) => Ok((path, file)),
+                    _ => Err(make_manifest_err(format!(
+                        "file of module '{}' is not a string",
+                        path
+                    ))),
+                })
+                .collect::<Result<_, _>>()?,
+            None | Some(Value::Null) => files
+                .keys()
+                .filter(|file| file.ends_with(".rhai"))
+                .map(|file| (file.trim_end_matches(".rhai").into(), file.clone()))
+                .collect(),
+            Some(_) => return Err(make_manifest_err("'modules' is not an object")),
+        };
+
+        // Build the modules
+        let mut modules = SourceModuleResolver::new();
+
+        for (path, file) in module_files {
+            let script = files.get(&file).ok_or_else(|| {
+                make_manifest_err(format!(
+                    "file '{}' of module '{}' not found in archive",
+                    file, path
+                ))
+            })?;
+
+            modules.insert(format!("{}/{}", name, path), decode_file(&file, script)?);
+        }
+
+        if let Some(ref entry) = entry {
+            if !modules.contains_path(&format!("{}/{}", name, entry)) {
+                return Err(make_manifest_err(format!(
+                    "entry module '{}' not found",
+                    entry
+                )));
+            }
+        }
+
+        Ok(Self {
+            name,
+            version,
+            entry,
+            modules,
+        })
+    }
+
+    /// Get the name of the package.
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the version of the package.
+    #[inline(always)]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+    /// Get the path of the entry module of the package (if any), relative to the package name.
+    #[inline(always)]
+    pub fn entry(&self) -> Option<&str> {
+        self.entry.as_deref()
+    }
+    /// Does the package contain a module with a particular `import` path?
+    #[inline(always)]
+    pub fn contains_path(&self, path: &str) -> bool {
+        match self.get_module_path(path) {
+            Some(path) => self.modules.contains_path(&path),
+            None => false,
+        }
+    }
+    /// Empty the internal cache.
+    #[inline(always)]
+    pub fn clear_cache(&mut self) {
+        self.modules.clear_cache();
+    }
+    /// Map an `import` path to the full path of a module in the package.
+    fn get_module_path(&self, path: &str) -> Option<String> {
+        if path == self.name {
+            self.entry
+                .as_ref()
+                .map(|entry| format!("{}/{}", self.name, entry))
+        } else if path.starts_with(&self.name) && path[self.name.len()..].starts_with('/') {
+            Some(path.to_string())
+        } else {
+            None
+        }
+    }
+    /// Resolve a relative `import` path against the path of the importing module.
+    fn get_relative_path(source: &str, path: &str) -> String {
+        let mut segments: Vec<&str> = source.split('/').collect();
+        segments.pop();
+
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => (),
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+
+        segments.join("/")
+    }
+}
+
+impl ModuleResolver for ArchiveModuleResolver {
+    #[inline(always)]
+    fn resolve(
+        &self,
+        engine: &Engine,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        self.resolve_from_source(engine, None, path, pos)
+    }
+
+    fn resolve_from_source(
+        &self,
+        engine: &Engine,
+        source: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let full_path = match source {
+            // Relative paths are resolved against the importing module within the package
+            Some(source)
+                if (path.starts_with("./") || path.starts_with("../"))
+                    && self.get_module_path(source).is_some() =>
+            {
+                Some(Self::get_relative_path(source, path))
+            }
+            _ => self.get_module_path(path),
+        };
+
+        match full_path {
+            Some(full_path) => self
+                .modules
+                .resolve(engine, &full_path, pos)
+                .map_err(|err| match *err {
+                    EvalAltResult::ErrorModuleNotFound(_, paths, pos) => {
+                        EvalAltResult::ErrorModuleNotFound(path.into(), paths, pos).into()
+                    }
+                    _ => err,
+                }),
+            None => EvalAltResult::ErrorModuleNotFound(path.into(), Default::default(), pos).into(),
+        }
+    }
+
+    /// Resolve an `AST` based on a path string.
+    ///
+    /// The script is compiled during each call; the internal cache is by-passed.
+    fn resolve_ast(
+        &self,
+        engine: &Engine,
+        path: &str,
+        pos: Position,
+    ) -> Option<Result<AST, Box<EvalAltResult>>> {
+        Some(match self.get_module_path(path) {
+            Some(full_path) => self.modules.resolve_ast(engine, &full_path, pos)?,
+            None => EvalAltResult::ErrorModuleNotFound(path.into(), Default::default(), pos).into(),
+        })
+    }
+}
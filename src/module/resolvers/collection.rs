@@ -108,24 +108,37 @@ impl ModuleResolversCollection {
 }
 
 impl ModuleResolver for ModuleResolversCollection {
+    #[inline(always)]
     fn resolve(
         &self,
         engine: &Engine,
         path: &str,
         pos: Position,
     ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        self.resolve_from_source(engine, None, path, pos)
+    }
+
+    fn resolve_from_source(
+        &self,
+        engine: &Engine,
+        source: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let mut attempted = Vec::new();
+
         for resolver in self.0.iter() {
-            match resolver.resolve(engine, path, pos) {
+            match resolver.resolve_from_source(engine, source, path, pos) {
                 Ok(module) => return Ok(module),
                 Err(err) => match *err {
-                    EvalAltResult::ErrorModuleNotFound(_, _) => continue,
+                    EvalAltResult::ErrorModuleNotFound(_, paths, _) => attempted.extend(paths),
                     EvalAltResult::ErrorInModule(_, err, _) => return Err(err),
                     _ => panic!("ModuleResolver::resolve returns error that is not ErrorModuleNotFound or ErrorInModule"),
                 },
             }
         }
 
-        EvalAltResult::ErrorModuleNotFound(path.into(), pos).into()
+        EvalAltResult::ErrorModuleNotFound(path.into(), attempted, pos).into()
     }
 }
 
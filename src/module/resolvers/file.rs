@@ -1,12 +1,60 @@
+use crate::fn_native::SendSync;
 use crate::stdlib::{
     boxed::Box,
     collections::HashMap,
+    fmt, fs,
+    hash::{Hash, Hasher},
     io::Error as IoError,
-    path::{Path, PathBuf},
-    string::String,
+    iter::once,
+    path::{Component, Path, PathBuf},
+    string::{String, ToString},
+    time::SystemTime,
+    vec::Vec,
 };
+use crate::utils::get_hasher;
 use crate::{Engine, EvalAltResult, Module, ModuleResolver, Position, Shared};
 
+/// How a [`FileModuleResolver`] detects changes to script files of cached [modules][Module].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ReloadMode {
+    /// Cached [modules][Module] are never reloaded. This is the default.
+    Never,
+    /// Reload a cached [module][Module] when the modification time of its script file changes.
+    ModifiedTime,
+    /// Reload a cached [module][Module] when the contents of its script file change.
+    ///
+    /// The script file is read in full each time the [module][Module] is resolved.
+    ContentHash,
+}
+
+impl Default for ReloadMode {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Never
+    }
+}
+
+/// A callback function notified when a cached [module][Module] is reloaded.
+#[cfg(not(feature = "sync"))]
+type OnReloadCallback = Box<dyn Fn(&Path) + 'static>;
+/// A callback function notified when a cached [module][Module] is reloaded.
+#[cfg(feature = "sync")]
+type OnReloadCallback = Box<dyn Fn(&Path) + Send + Sync + 'static>;
+
+/// Snapshot of a script file used to detect changes.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum FileStamp {
+    Modified(SystemTime),
+    Hash(u64),
+}
+
+/// A cached [module][Module] together with a snapshot of its script file.
+#[derive(Debug, Clone)]
+struct CachedModule {
+    module: Shared<Module>,
+    stamp: Option<FileStamp>,
+}
+
 /// A [module][Module] resolution service that loads [module][Module] script files from the file system.
 ///
 /// ## Caching
@@ -17,6 +65,28 @@ use crate::{Engine, EvalAltResult, Module, ModuleResolver, Position, Shared};
 /// Use [`clear_cache`][FileModuleResolver::clear_cache] or
 /// [`clear_cache_for_path`][FileModuleResolver::clear_cache_for_path] to clear the internal cache.
 ///
+/// ## Hot Reloading
+///
+/// Use [`set_reload_mode`][FileModuleResolver::set_reload_mode] to check script files for changes
+/// each time a cached [module][Module] is resolved.  A [module][Module] is reloaded when its own
+/// script file, or the script file of any [module][Module] it imports (directly or indirectly), has
+/// changed.  All cached [modules][Module] importing a changed [module][Module] are reloaded as well.
+///
+/// Use [`on_reload`][FileModuleResolver::on_reload] to be notified of [modules][Module] removed from
+/// the internal cache because of changes.
+///
+/// ## Search Paths
+///
+/// An `import` path is searched under the base path first, followed by each additional search path
+/// (see [`add_search_path`][FileModuleResolver::add_search_path]) in order.
+///
+/// ## Relative Paths
+///
+/// An `import` path starting with `./` or `../` is resolved relative to the directory of the
+/// importing script, based on the [source][crate::AST::source] of its [`AST`][crate::AST].
+/// Script files loaded by this resolver have their file paths as sources.
+/// If the importing script has no source, the path is resolved relative to the base path.
+///
 /// ## Namespace
 ///
 /// When a function within a script file module is called, all functions defined within the same
@@ -37,15 +107,37 @@ use crate::{Engine, EvalAltResult, Module, ModuleResolver, Position, Shared};
 ///
 /// engine.set_module_resolver(resolver);
 /// ```
-#[derive(Debug)]
 pub struct FileModuleResolver {
     base_path: PathBuf,
+    search_paths: Vec<PathBuf>,
     extension: String,
+    reload_mode: ReloadMode,
+    on_reload: Option<OnReloadCallback>,
+
+    #[cfg(not(feature = "sync"))]
+    cache: crate::stdlib::cell::RefCell<HashMap<PathBuf, CachedModule>>,
+    #[cfg(feature = "sync")]
+    cache: crate::stdlib::sync::RwLock<HashMap<PathBuf, CachedModule>>,
 
+    /// Script files imported by each script file.
     #[cfg(not(feature = "sync"))]
-    cache: crate::stdlib::cell::RefCell<HashMap<PathBuf, Shared<Module>>>,
+    imports: crate::stdlib::cell::RefCell<HashMap<PathBuf, Vec<PathBuf>>>,
+    /// Script files imported by each script file.
     #[cfg(feature = "sync")]
-    cache: crate::stdlib::sync::RwLock<HashMap<PathBuf, Shared<Module>>>,
+    imports: crate::stdlib::sync::RwLock<HashMap<PathBuf, Vec<PathBuf>>>,
+}
+
+impl fmt::Debug for FileModuleResolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FileModuleResolver")
+            .field("base_path", &self.base_path)
+            .field("search_paths", &self.search_paths)
+            .field("extension", &self.extension)
+            .field("reload_mode", &self.reload_mode)
+            .field("cache", &self.cache)
+            .field("imports", &self.imports)
+            .finish()
+    }
 }
 
 impl Default for FileModuleResolver {
@@ -100,8 +192,12 @@ impl FileModuleResolver {
     ) -> Self {
         Self {
             base_path: path.into(),
+            search_paths: Default::default(),
             extension: extension.into(),
+            reload_mode: Default::default(),
+            on_reload: None,
             cache: Default::default(),
+            imports: Default::default(),
         }
     }
 
@@ -139,6 +235,32 @@ impl FileModuleResolver {
         self
     }
 
+    /// Get the additional paths to search for script files, in order, after the base path.
+    #[inline(always)]
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+    /// Add a path to search for script files, after the base path and all previously-added paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    /// use rhai::module_resolvers::FileModuleResolver;
+    ///
+    /// // Search the 'scripts' subdirectory first, then 'vendor/scripts'.
+    /// let mut resolver = FileModuleResolver::new_with_path("./scripts");
+    /// resolver.add_search_path("./vendor/scripts");
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_module_resolver(resolver);
+    /// ```
+    #[inline(always)]
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.search_paths.push(path.into());
+        self
+    }
+
     /// Get the script file extension.
     #[inline(always)]
     pub fn extension(&self) -> &str {
@@ -152,6 +274,44 @@ impl FileModuleResolver {
         self
     }
 
+    /// Get how changes to script files of cached [modules][Module] are detected.
+    #[inline(always)]
+    pub fn reload_mode(&self) -> ReloadMode {
+        self.reload_mode
+    }
+    /// Set how changes to script files of cached [modules][Module] are detected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    /// use rhai::module_resolvers::{FileModuleResolver, ReloadMode};
+    ///
+    /// let mut resolver = FileModuleResolver::new_with_path("./scripts");
+    ///
+    /// // Reload modules when their script files are modified
+    /// resolver.set_reload_mode(ReloadMode::ModifiedTime)
+    ///         .on_reload(|path| println!("Reloading {}", path.display()));
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_module_resolver(resolver);
+    /// ```
+    #[inline(always)]
+    pub fn set_reload_mode(&mut self, mode: ReloadMode) -> &mut Self {
+        self.reload_mode = mode;
+        self
+    }
+    /// Register a callback that is notified with the file path of each cached [module][Module]
+    /// removed from the internal cache because its script file, or the script file of a
+    /// [module][Module] it imports, has changed.
+    ///
+    /// The [module][Module] is reloaded the next time it is resolved.
+    #[inline(always)]
+    pub fn on_reload(&mut self, callback: impl Fn(&Path) + SendSync + 'static) -> &mut Self {
+        self.on_reload = Some(Box::new(callback));
+        self
+    }
+
     /// Is a particular path cached?
     #[inline(always)]
     pub fn is_cached(&self, path: &str) -> bool {
@@ -169,6 +329,11 @@ impl FileModuleResolver {
         self.cache.borrow_mut().clear();
         #[cfg(feature = "sync")]
         self.cache.write().unwrap().clear();
+
+        #[cfg(not(feature = "sync"))]
+        self.imports.borrow_mut().clear();
+        #[cfg(feature = "sync")]
+        self.imports.write().unwrap().clear();
     }
     /// Remove the specified path from internal cache.
     ///
@@ -177,77 +342,338 @@ impl FileModuleResolver {
     pub fn clear_cache_for_path(&mut self, path: &str) -> Option<Shared<Module>> {
         let file_path = self.get_file_path(path);
 
+        #[cfg(not(feature = "sync"))]
+        self.imports.borrow_mut().remove(&file_path);
+        #[cfg(feature = "sync")]
+        self.imports.write().unwrap().remove(&file_path);
+
         #[cfg(not(feature = "sync"))]
         return self
             .cache
             .borrow_mut()
             .remove_entry(&file_path)
-            .map(|(_, v)| v);
+            .map(|(_, v)| v.module);
         #[cfg(feature = "sync")]
         return self
             .cache
             .write()
             .unwrap()
             .remove_entry(&file_path)
-            .map(|(_, v)| v);
+            .map(|(_, v)| v.module);
+    }
+    /// Remove the specified path from internal cache, together with all cached [modules][Module]
+    /// that import it, directly or indirectly.
+    ///
+    /// Returns the file paths of all [modules][Module] removed.
+    ///
+    /// The next time these paths are resolved, the script files will be loaded once again.
+    #[inline(always)]
+    pub fn clear_cache_for_path_and_dependants(&mut self, path: &str) -> Vec<PathBuf> {
+        let file_path = self.get_file_path(path);
+        self.remove_with_dependants(&file_path)
+    }
+    /// Remove a script file from the internal cache, together with all cached [modules][Module]
+    /// that import it, directly or indirectly.
+    ///
+    /// Returns the file paths of all [modules][Module] removed.
+    fn remove_with_dependants(&self, file_path: &Path) -> Vec<PathBuf> {
+        let mut visited: Vec<PathBuf> = Default::default();
+        let mut removed: Vec<PathBuf> = Default::default();
+        let mut stack = vec![file_path.to_path_buf()];
+
+        while let Some(file_path) = stack.pop() {
+            if visited.contains(&file_path) {
+                continue;
+            }
+
+            #[cfg(not(feature = "sync"))]
+            let mut imports = self.imports.borrow_mut();
+            #[cfg(feature = "sync")]
+            let mut imports = self.imports.write().unwrap();
+
+            // Import records are re-built when the script file is loaded again
+            imports.remove(&file_path);
+
+            stack.extend(
+                imports
+                    .iter()
+                    .filter(|(_, paths)| paths.contains(&file_path))
+                    .map(|(importer, _)| importer.clone()),
+            );
+
+            #[cfg(not(feature = "sync"))]
+            let entry = self.cache.borrow_mut().remove(&file_path);
+            #[cfg(feature = "sync")]
+            let entry = self.cache.write().unwrap().remove(&file_path);
+
+            if entry.is_some() {
+                removed.push(file_path.clone());
+            }
+
+            visited.push(file_path);
+        }
+
+        removed
+    }
+    /// Remove a cached [module][Module] from the internal cache if its script file, or the script
+    /// file of any [module][Module] it imports (directly or indirectly), has changed.
+    ///
+    /// All cached [modules][Module] importing a changed script file are also removed.
+    fn remove_changed(&self, file_path: &Path) {
+        let mut visited: Vec<PathBuf> = Default::default();
+        let mut changed: Vec<PathBuf> = Default::default();
+        let mut stack = vec![file_path.to_path_buf()];
+
+        while let Some(file_path) = stack.pop() {
+            if visited.contains(&file_path) {
+                continue;
+            }
+
+            #[cfg(not(feature = "sync"))]
+            let stamp = self.cache.borrow().get(&file_path).map(|c| c.stamp.clone());
+            #[cfg(feature = "sync")]
+            let stamp = self
+                .cache
+                .read()
+                .unwrap()
+                .get(&file_path)
+                .map(|c| c.stamp.clone());
+
+            match stamp {
+                Some(stamp) if stamp != self.get_file_stamp(&file_path) => {
+                    changed.push(file_path.clone())
+                }
+                Some(_) => (),
+                None => {
+                    visited.push(file_path);
+                    continue;
+                }
+            }
+
+            #[cfg(not(feature = "sync"))]
+            let imports = self.imports.borrow();
+            #[cfg(feature = "sync")]
+            let imports = self.imports.read().unwrap();
+
+            if let Some(paths) = imports.get(&file_path) {
+                stack.extend(paths.iter().cloned());
+            }
+
+            visited.push(file_path);
+        }
+
+        for file_path in changed {
+            for removed in self.remove_with_dependants(&file_path) {
+                if let Some(ref callback) = self.on_reload {
+                    callback(&removed);
+                }
+            }
+        }
+    }
+    /// Take a snapshot of a script file according to the reload mode.
+    fn get_file_stamp(&self, file_path: &Path) -> Option<FileStamp> {
+        match self.reload_mode {
+            ReloadMode::Never => None,
+            ReloadMode::ModifiedTime => file_path
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .map(FileStamp::Modified),
+            ReloadMode::ContentHash => fs::read(file_path).ok().map(|contents| {
+                let s = &mut get_hasher();
+                contents.hash(s);
+                FileStamp::Hash(s.finish())
+            }),
+        }
+    }
+    /// Record that a script file is imported by the script with a particular source.
+    fn add_import(&self, source: Option<&str>, file_path: &Path) {
+        let importer = match source {
+            Some(source) if Path::new(source) != file_path => PathBuf::from(source),
+            _ => return,
+        };
+
+        #[cfg(not(feature = "sync"))]
+        let mut imports = self.imports.borrow_mut();
+        #[cfg(feature = "sync")]
+        let mut imports = self.imports.write().unwrap();
+
+        let paths = imports.entry(importer).or_default();
+
+        if !paths.iter().any(|p| p == file_path) {
+            paths.push(file_path.to_path_buf());
+        }
     }
     /// Construct a full file path.
+    #[inline(always)]
     fn get_file_path(&self, path: &str) -> PathBuf {
-        let mut file_path = self.base_path.clone();
-        file_path.push(path);
+        self.get_file_path_in(&self.base_path, path)
+    }
+    /// Construct a full file path under a particular directory.
+    fn get_file_path_in(&self, dir: &Path, path: &str) -> PathBuf {
+        let mut file_path = PathBuf::new();
+
+        // Remove `.` and `..` so the same file is always cached under the same path
+        for component in dir.join(path).components() {
+            match component {
+                Component::CurDir => (),
+                Component::ParentDir
+                    if matches!(
+                        file_path.components().next_back(),
+                        Some(Component::Normal(_))
+                    ) =>
+                {
+                    file_path.pop();
+                }
+                component => file_path.push(component),
+            }
+        }
+
         file_path.set_extension(&self.extension); // Force extension
         file_path
     }
+    /// Construct all the file paths to try, in order, for a path imported by a script with a
+    /// particular source.
+    fn get_search_file_paths(&self, source: Option<&str>, path: &str) -> Vec<PathBuf> {
+        // Relative paths are resolved against the directory of the importing script
+        if path.starts_with("./") || path.starts_with("../") {
+            let dir = source
+                .and_then(|s| Path::new(s).parent())
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or(&self.base_path);
+
+            return vec![self.get_file_path_in(dir, path)];
+        }
+
+        let mut file_paths = Vec::with_capacity(1 + self.search_paths.len());
+
+        once(&self.base_path)
+            .chain(self.search_paths.iter())
+            .map(|dir| self.get_file_path_in(dir, path))
+            .for_each(|file_path| {
+                if !file_paths.contains(&file_path) {
+                    file_paths.push(file_path);
+                }
+            });
+
+        file_paths
+    }
+    /// Load a script file and compile it into an [`AST`][crate::AST].
+    fn compile_file(
+        engine: &Engine,
+        file_path: &Path,
+        path: &str,
+        pos: Position,
+    ) -> Result<crate::AST, Box<EvalAltResult>> {
+        let mut ast = engine
+            .compile_file(file_path.to_path_buf())
+            .map_err(|err| match *err {
+                EvalAltResult::ErrorSystem(_, err) if err.is::<IoError>() => {
+                    Box::new(EvalAltResult::ErrorModuleNotFound(
+                        path.to_string(),
+                        vec![file_path.to_string_lossy().to_string()],
+                        pos,
+                    ))
+                }
+                _ => Box::new(EvalAltResult::ErrorInModule(path.to_string(), err, pos)),
+            })?;
+
+        ast.set_source(file_path.to_string_lossy().to_string());
+
+        Ok(ast)
+    }
+}
+
+/// Make an [`ErrorModuleNotFound`][EvalAltResult::ErrorModuleNotFound] listing all attempted paths.
+fn make_not_found_err(path: &str, file_paths: &[PathBuf], pos: Position) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorModuleNotFound(
+        path.to_string(),
+        file_paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        pos,
+    )
+    .into()
 }
 
 impl ModuleResolver for FileModuleResolver {
+    #[inline(always)]
     fn resolve(
         &self,
         engine: &Engine,
         path: &str,
         pos: Position,
     ) -> Result<Shared<Module>, Box<EvalAltResult>> {
-        // Construct the script file path
-        let file_path = self.get_file_path(path);
+        self.resolve_from_source(engine, None, path, pos)
+    }
 
-        // See if it is cached
-        {
-            #[cfg(not(feature = "sync"))]
-            let c = self.cache.borrow();
-            #[cfg(feature = "sync")]
-            let c = self.cache.read().unwrap();
+    fn resolve_from_source(
+        &self,
+        engine: &Engine,
+        source: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let file_paths = self.get_search_file_paths(source, path);
 
-            if let Some(module) = c.get(&file_path) {
-                return Ok(module.clone());
+        for file_path in file_paths.iter() {
+            // Remove it from the cache if changed
+            if self.reload_mode != ReloadMode::Never {
+                self.remove_changed(file_path);
             }
-        }
 
-        // Load the script file and compile it
-        let scope = Default::default();
+            // See if it is cached
+            {
+                #[cfg(not(feature = "sync"))]
+                let c = self.cache.borrow();
+                #[cfg(feature = "sync")]
+                let c = self.cache.read().unwrap();
 
-        let mut ast = engine
-            .compile_file(file_path.clone())
-            .map_err(|err| match *err {
-                EvalAltResult::ErrorSystem(_, err) if err.is::<IoError>() => {
-                    Box::new(EvalAltResult::ErrorModuleNotFound(path.to_string(), pos))
+                if let Some(cached) = c.get(file_path) {
+                    let module = cached.module.clone();
+                    drop(c);
+                    self.add_import(source, file_path);
+                    return Ok(module);
                 }
-                _ => Box::new(EvalAltResult::ErrorInModule(path.to_string(), err, pos)),
-            })?;
+            }
 
-        ast.set_source(path);
+            if !file_path.is_file() {
+                continue;
+            }
 
-        // Make a module from the AST
-        let m: Shared<Module> = Module::eval_ast_as_new(scope, &ast, engine)
-            .map_err(|err| Box::new(EvalAltResult::ErrorInModule(path.to_string(), err, pos)))?
-            .into();
+            // Take a snapshot before loading so changes made during loading are detected
+            let stamp = self.get_file_stamp(file_path);
 
-        // Put it into the cache
-        #[cfg(not(feature = "sync"))]
-        self.cache.borrow_mut().insert(file_path, m.clone());
-        #[cfg(feature = "sync")]
-        self.cache.write().unwrap().insert(file_path, m.clone());
+            // Load the script file and compile it
+            let scope = Default::default();
+            let ast = Self::compile_file(engine, file_path, path, pos)?;
+
+            // Make a module from the AST
+            let m: Shared<Module> = Module::eval_ast_as_new(scope, &ast, engine)
+                .map_err(|err| Box::new(EvalAltResult::ErrorInModule(path.to_string(), err, pos)))?
+                .into();
 
-        Ok(m)
+            let cached = CachedModule {
+                module: m.clone(),
+                stamp,
+            };
+
+            // Put it into the cache
+            #[cfg(not(feature = "sync"))]
+            self.cache.borrow_mut().insert(file_path.clone(), cached);
+            #[cfg(feature = "sync")]
+            self.cache
+                .write()
+                .unwrap()
+                .insert(file_path.clone(), cached);
+
+            self.add_import(source, file_path);
+
+            return Ok(m);
+        }
+
+        Err(make_not_found_err(path, &file_paths, pos))
     }
 
     /// Resolve an `AST` based on a path string.
@@ -259,21 +685,13 @@ impl ModuleResolver for FileModuleResolver {
         path: &str,
         pos: Position,
     ) -> Option<Result<crate::AST, Box<EvalAltResult>>> {
-        // Construct the script file path
-        let file_path = self.get_file_path(path);
+        let file_paths = self.get_search_file_paths(None, path);
 
-        // Load the script file and compile it
-        match engine.compile_file(file_path).map_err(|err| match *err {
-            EvalAltResult::ErrorSystem(_, err) if err.is::<IoError>() => {
-                Box::new(EvalAltResult::ErrorModuleNotFound(path.to_string(), pos))
-            }
-            _ => Box::new(EvalAltResult::ErrorInModule(path.to_string(), err, pos)),
-        }) {
-            Ok(mut ast) => {
-                ast.set_source(path);
-                Some(Ok(ast))
-            }
-            err @ Err(_) => Some(err),
-        }
+        Some(
+            match file_paths.iter().find(|file_path| file_path.is_file()) {
+                Some(file_path) => Self::compile_file(engine, file_path, path, pos),
+                None => Err(make_not_found_err(path, &file_paths, pos)),
+            },
+        )
     }
 }
@@ -14,7 +14,33 @@ mod file;
 
 #[cfg(not(feature = "no_std"))]
 #[cfg(not(target_arch = "wasm32"))]
-pub use file::FileModuleResolver;
+pub use file::{FileModuleResolver, ReloadMode};
+
+#[cfg(feature = "archive")]
+#[cfg(not(feature = "no_std"))]
+#[cfg(not(target_arch = "wasm32"))]
+mod archive;
+
+#[cfg(feature = "archive")]
+#[cfg(not(feature = "no_std"))]
+#[cfg(not(target_arch = "wasm32"))]
+pub use archive::ArchiveModuleResolver;
+
+#[cfg(feature = "dylib")]
+#[cfg(not(feature = "no_std"))]
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(feature = "dylib")]
+#[cfg(not(feature = "no_std"))]
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::{
+    NativeModuleDeclaration, NativeModuleResolver, NATIVE_MODULE_BUILD_FLAGS, NATIVE_MODULE_PREFIX,
+    NATIVE_MODULE_RHAI_VERSION, NATIVE_MODULE_SYMBOL,
+};
+
+mod source;
+pub use source::SourceModuleResolver;
 
 mod stat;
 pub use stat::StaticModuleResolver;
@@ -29,6 +55,22 @@ pub trait ModuleResolver: SendSync {
         pos: Position,
     ) -> Result<Shared<Module>, Box<EvalAltResult>>;
 
+    /// Resolve a module based on a path string, given the source of the importing script (if any).
+    ///
+    /// The default implementation ignores the source and calls [`resolve`][ModuleResolver::resolve].
+    ///
+    /// Override this method if the module resolver supports paths relative to the importing script.
+    #[allow(unused_variables)]
+    fn resolve_from_source(
+        &self,
+        engine: &Engine,
+        source: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        self.resolve(engine, path, pos)
+    }
+
     /// Resolve an `AST` based on a path string.
     ///
     /// Returns [`None`] (default) if such resolution is not supported
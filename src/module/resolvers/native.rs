@@ -0,0 +1,352 @@
+use crate::stdlib::{
+    boxed::Box,
+    collections::HashMap,
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    io::{Error as IoError, ErrorKind},
+    mem,
+    path::{Path, PathBuf},
+    string::ToString,
+    vec::Vec,
+};
+use crate::{Engine, EvalAltResult, Module, ModuleResolver, Position, Shared};
+
+/// Prefix of `import` paths resolved by a [`NativeModuleResolver`].
+pub const NATIVE_MODULE_PREFIX: &str = "native:";
+
+/// Name of the symbol holding the [`NativeModuleDeclaration`] of a native module library.
+pub const NATIVE_MODULE_SYMBOL: &str = "RHAI_NATIVE_MODULE";
+
+/// Version of Rhai that native module libraries must be built against.
+pub const NATIVE_MODULE_RHAI_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Feature flags that native module libraries must be built with.
+///
+/// Each bit encodes a feature that changes the types or the layout of data shared between the
+/// host and the library.
+pub const NATIVE_MODULE_BUILD_FLAGS: u32 = (cfg!(feature = "sync") as u32)
+    | (cfg!(feature = "only_i32") as u32) << 1
+    | (cfg!(feature = "only_i64") as u32) << 2
+    | (cfg!(feature = "no_float") as u32) << 3
+    | (cfg!(feature = "f32_float") as u32) << 4
+    | (cfg!(feature = "no_index") as u32) << 5
+    | (cfg!(feature = "no_object") as u32) << 6
+    | (cfg!(feature = "no_function") as u32) << 7
+    | (cfg!(feature = "no_closure") as u32) << 8
+    | (cfg!(feature = "no_module") as u32) << 9
+    | (cfg!(feature = "no_optimize") as u32) << 10
+    | (cfg!(feature = "unchecked") as u32) << 11
+    | (cfg!(feature = "internals") as u32) << 12
+    | (cfg!(feature = "metadata") as u32) << 13
+    | (cfg!(feature = "bytecode") as u32) << 14;
+
+/// Declaration of a native module library, exported under the symbol [`NATIVE_MODULE_SYMBOL`].
+///
+/// Exported under the `dylib` feature only.
+///
+/// Use the [`export_native_module!`][crate::export_native_module] macro to declare it.
+///
+/// A library is only loaded when it is built against the same version of Rhai, with the same
+/// [feature flags][NATIVE_MODULE_BUILD_FLAGS], as the host.  As Rust has no stable ABI, the
+/// library must also be built by the same version of the Rust compiler, and both must use the
+/// same global allocator.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct NativeModuleDeclaration {
+    /// Version of Rhai that the library is built against.
+    pub rhai_version: &'static str,
+    /// Feature flags that the library is built with.
+    pub build_flags: u32,
+    /// Function creating the [module][Module].
+    pub create: fn() -> Module,
+}
+
+impl NativeModuleDeclaration {
+    /// Is the library built against the same version of Rhai, with the same feature flags,
+    /// as the host?
+    #[inline(always)]
+    pub fn is_compatible(&self) -> bool {
+        self.rhai_version == NATIVE_MODULE_RHAI_VERSION
+            && self.build_flags == NATIVE_MODULE_BUILD_FLAGS
+    }
+}
+
+/// Declare a native module library, exporting a function creating its [module][crate::Module].
+///
+/// Exported under the `dylib` feature only.
+///
+/// The library crate must be built with `crate-type = ["cdylib"]`.
+///
+/// # Example
+///
+/// ```
+/// use rhai::{Module, INT};
+///
+/// fn create_module() -> Module {
+///     let mut module = Module::new();
+///     module.set_fn_1("double", |x: INT| Ok(x * 2));
+///     module
+/// }
+///
+/// rhai::export_native_module!(create_module);
+/// ```
+#[macro_export]
+macro_rules! export_native_module {
+    ($create:path) => {
+        #[no_mangle]
+        pub static RHAI_NATIVE_MODULE: $crate::module_resolvers::NativeModuleDeclaration =
+            $crate::module_resolvers::NativeModuleDeclaration {
+                rhai_version: $crate::module_resolvers::NATIVE_MODULE_RHAI_VERSION,
+                build_flags: $crate::module_resolvers::NATIVE_MODULE_BUILD_FLAGS,
+                create: $create,
+            };
+    };
+}
+
+/// Make an [`ErrorSystem`][EvalAltResult::ErrorSystem] for an incompatible native module library.
+fn make_incompatible_err(file_path: &Path, decl: &NativeModuleDeclaration) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorSystem(
+        format!(
+            "Incompatible native module library '{}'",
+            file_path.to_string_lossy()
+        ),
+        IoError::new(
+            ErrorKind::InvalidData,
+            format!(
+                "built against Rhai {} with feature flags {:#x}, expecting Rhai {} with feature flags {:#x}",
+                decl.rhai_version,
+                decl.build_flags,
+                NATIVE_MODULE_RHAI_VERSION,
+                NATIVE_MODULE_BUILD_FLAGS
+            ),
+        )
+        .into(),
+    )
+    .into()
+}
+
+/// A [module][Module] resolution service that loads native Rust [modules][Module] from dynamic
+/// libraries.
+///
+/// Exported under the `dylib` feature only.
+///
+/// An `import` path of the form `native:name` resolves to the dynamic library `name` with the
+/// platform's naming conventions (e.g. `libname.so` on Linux, `name.dll` on Windows), searched
+/// in the base path and then in each additional search path in order.  Paths without the
+/// `native:` prefix are never resolved, so this resolver is usually combined with others in a
+/// [`ModuleResolversCollection`][super::ModuleResolversCollection].
+///
+/// A library must declare its [module][Module] via the
+/// [`export_native_module!`][crate::export_native_module] macro.  See
+/// [`NativeModuleDeclaration`] for the compatibility requirements.
+///
+/// Resolved [modules][Module] are cached internally.  Loaded libraries are never unloaded because
+/// functions in resolved [modules][Module] may still be in use.
+///
+/// # Example
+///
+/// ```
+/// use rhai::Engine;
+/// use rhai::module_resolvers::NativeModuleResolver;
+///
+/// // 'import "native:foo"' loads 'plugins/libfoo.so' on Linux
+/// let resolver = NativeModuleResolver::new_with_path("./plugins");
+///
+/// let mut engine = Engine::new();
+/// engine.set_module_resolver(resolver);
+/// ```
+#[derive(Debug, Default)]
+pub struct NativeModuleResolver {
+    base_path: PathBuf,
+    search_paths: Vec<PathBuf>,
+
+    #[cfg(not(feature = "sync"))]
+    cache: crate::stdlib::cell::RefCell<HashMap<PathBuf, Shared<Module>>>,
+    #[cfg(feature = "sync")]
+    cache: crate::stdlib::sync::RwLock<HashMap<PathBuf, Shared<Module>>>,
+}
+
+impl NativeModuleResolver {
+    /// Create a new [`NativeModuleResolver`] with the current directory as base path.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Create a new [`NativeModuleResolver`] with a specific base path.
+    #[inline(always)]
+    pub fn new_with_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            base_path: path.into(),
+            ..Default::default()
+        }
+    }
+    /// Get the base path for dynamic libraries.
+    #[inline(always)]
+    pub fn base_path(&self) -> &Path {
+        self.base_path.as_ref()
+    }
+    /// Set the base path for dynamic libraries.
+    #[inline(always)]
+    pub fn set_base_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.base_path = path.into();
+        self
+    }
+    /// Get the additional paths to search for dynamic libraries, in order, after the base path.
+    #[inline(always)]
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+    /// Add a path to search for dynamic libraries, after the base path and all previously-added
+    /// paths.
+    #[inline(always)]
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.search_paths.push(path.into());
+        self
+    }
+    /// Is a particular `import` path cached?
+    pub fn is_cached(&self, path: &str) -> bool {
+        let file_paths = self.get_file_paths(path);
+
+        #[cfg(not(feature = "sync"))]
+        let c = self.cache.borrow();
+        #[cfg(feature = "sync")]
+        let c = self.cache.read().unwrap();
+
+        file_paths.iter().any(|file_path| c.contains_key(file_path))
+    }
+    /// Empty the internal cache.
+    ///
+    /// Loaded libraries are not unloaded.
+    #[inline(always)]
+    pub fn clear_cache(&mut self) {
+        #[cfg(not(feature = "sync"))]
+        self.cache.borrow_mut().clear();
+        #[cfg(feature = "sync")]
+        self.cache.write().unwrap().clear();
+    }
+    /// Load a native [module][Module] from a dynamic library file.
+    ///
+    /// The library is never unloaded because functions in the [module][Module] may still be in
+    /// use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorSystem`][EvalAltResult::ErrorSystem] if the library cannot be loaded, does
+    /// not declare a native module, or is incompatible with the host.
+    pub fn load_module(file_path: impl AsRef<Path>) -> Result<Module, Box<EvalAltResult>> {
+        let file_path = file_path.as_ref();
+
+        let load_err = |desc: &str, err: libloading::Error| -> Box<EvalAltResult> {
+            EvalAltResult::ErrorSystem(
+                format!("{} '{}'", desc, file_path.to_string_lossy()),
+                err.into(),
+            )
+            .into()
+        };
+
+        // SAFETY: loading a library runs its initialization routines, which is the purpose
+        //         of loading native modules.
+        let library = unsafe { libloading::Library::new(file_path) }
+            .map_err(|err| load_err("Cannot load native module library", err))?;
+
+        // SAFETY: the symbol is declared as a `NativeModuleDeclaration` by
+        //         `export_native_module!`, and the library is never unloaded.
+        let decl = unsafe {
+            let symbol = library
+                .get::<*const NativeModuleDeclaration>(NATIVE_MODULE_SYMBOL.as_bytes())
+                .map_err(|err| load_err("Not a native module library", err))?;
+
+            **symbol
+        };
+
+        if !decl.is_compatible() {
+            return Err(make_incompatible_err(file_path, &decl));
+        }
+
+        let mut module = (decl.create)();
+        module.build_index();
+
+        // Functions in the module point into the library, so it must never be unloaded
+        mem::forget(library);
+
+        Ok(module)
+    }
+    /// Get the file paths of the dynamic library for an `import` path, in search order.
+    fn get_file_paths(&self, path: &str) -> Vec<PathBuf> {
+        if !path.starts_with(NATIVE_MODULE_PREFIX) {
+            return Vec::new();
+        }
+
+        let name = Path::new(&path[NATIVE_MODULE_PREFIX.len()..]);
+
+        let file_name = match name.file_name() {
+            Some(file_name) => format!(
+                "{}{}{}",
+                DLL_PREFIX,
+                file_name.to_string_lossy(),
+                DLL_SUFFIX
+            ),
+            None => return Vec::new(),
+        };
+
+        let name = name.with_file_name(file_name);
+
+        crate::stdlib::iter::once(&self.base_path)
+            .chain(self.search_paths.iter())
+            .map(|dir| dir.join(&name))
+            .collect()
+    }
+}
+
+impl ModuleResolver for NativeModuleResolver {
+    fn resolve(
+        &self,
+        _: &Engine,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let file_paths = self.get_file_paths(path);
+
+        for file_path in file_paths.iter() {
+            // See if it is cached
+            {
+                #[cfg(not(feature = "sync"))]
+                let c = self.cache.borrow();
+                #[cfg(feature = "sync")]
+                let c = self.cache.read().unwrap();
+
+                if let Some(module) = c.get(file_path) {
+                    return Ok(module.clone());
+                }
+            }
+
+            if !file_path.is_file() {
+                continue;
+            }
+
+            let m: Shared<Module> = Self::load_module(file_path)
+                .map_err(|err| Box::new(EvalAltResult::ErrorInModule(path.to_string(), err, pos)))?
+                .into();
+
+            // Put it into the cache
+            #[cfg(not(feature = "sync"))]
+            self.cache.borrow_mut().insert(file_path.clone(), m.clone());
+            #[cfg(feature = "sync")]
+            self.cache
+                .write()
+                .unwrap()
+                .insert(file_path.clone(), m.clone());
+
+            return Ok(m);
+        }
+
+        EvalAltResult::ErrorModuleNotFound(
+            path.into(),
+            file_paths
+                .iter()
+                .map(|file_path| file_path.to_string_lossy().into_owned())
+                .collect(),
+            pos,
+        )
+        .into()
+    }
+}
@@ -0,0 +1,273 @@
+use crate::fn_native::SendSync;
+use crate::stdlib::{
+    boxed::Box,
+    collections::HashMap,
+    fmt,
+    string::{String, ToString},
+};
+use crate::{Engine, EvalAltResult, Module, ModuleResolver, Position, Shared, AST};
+
+/// A loader function returning the script text of a [module][Module] given its path.
+#[cfg(not(feature = "sync"))]
+type SourceLoader = Box<dyn Fn(&str) -> Option<String> + 'static>;
+/// A loader function returning the script text of a [module][Module] given its path.
+#[cfg(feature = "sync")]
+type SourceLoader = Box<dyn Fn(&str) -> Option<String> + Send + Sync + 'static>;
+
+/// A [module][Module] resolution service that compiles [modules][Module] from script text held in
+/// memory or returned by a loader function.
+///
+/// Script text is looked up first among the scripts added via
+/// [`insert`][SourceModuleResolver::insert], then via the loader function (if any).
+///
+/// ## Caching
+///
+/// Each [module][Module] is compiled with the importing [`Engine`] the first time it is resolved,
+/// and cached internally for subsequent requests.
+///
+/// Use [`clear_cache`][SourceModuleResolver::clear_cache] or
+/// [`clear_cache_for_path`][SourceModuleResolver::clear_cache_for_path] to clear the internal cache.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::Engine;
+/// use rhai::module_resolvers::SourceModuleResolver;
+///
+/// let mut resolver = SourceModuleResolver::new();
+/// resolver.insert("hello", "export const x = 42;");
+///
+/// let mut engine = Engine::new();
+/// engine.set_module_resolver(resolver);
+///
+/// assert_eq!(engine.eval::<rhai::INT>(r#"import "hello" as h; h::x"#)?, 42);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SourceModuleResolver {
+    sources: HashMap<String, String>,
+    loader: Option<SourceLoader>,
+
+    #[cfg(not(feature = "sync"))]
+    cache: crate::stdlib::cell::RefCell<HashMap<String, Shared<Module>>>,
+    #[cfg(feature = "sync")]
+    cache: crate::stdlib::sync::RwLock<HashMap<String, Shared<Module>>>,
+}
+
+impl Default for SourceModuleResolver {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            sources: Default::default(),
+            loader: None,
+            cache: Default::default(),
+        }
+    }
+}
+
+impl fmt::Debug for SourceModuleResolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SourceModuleResolver")
+            .field("sources", &self.sources)
+            .field("loader", &self.loader.is_some())
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> From<HashMap<K, V>> for SourceModuleResolver {
+    #[inline(always)]
+    fn from(sources: HashMap<K, V>) -> Self {
+        Self {
+            sources: sources
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl SourceModuleResolver {
+    /// Create a new [`SourceModuleResolver`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    /// use rhai::module_resolvers::SourceModuleResolver;
+    ///
+    /// let mut resolver = SourceModuleResolver::new();
+    /// resolver.insert("hello", "export const x = 42;");
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_module_resolver(resolver);
+    /// ```
+    #[inline(always)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Create a new [`SourceModuleResolver`] with a loader function that returns the script text
+    /// of a [module][Module] given its path, or [`None`] if there is no such [module][Module].
+    ///
+    /// The loader function is called at most once for each path until the cache is cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    /// use rhai::module_resolvers::SourceModuleResolver;
+    ///
+    /// let resolver = SourceModuleResolver::new_with_loader(|path| match path {
+    ///     "hello" => Some("export const x = 42;".into()),
+    ///     _ => None,
+    /// });
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_module_resolver(resolver);
+    /// ```
+    #[inline(always)]
+    pub fn new_with_loader(loader: impl Fn(&str) -> Option<String> + SendSync + 'static) -> Self {
+        Self {
+            loader: Some(Box::new(loader)),
+            ..Default::default()
+        }
+    }
+    /// Add the script text of a [module][Module] keyed by its path.
+    ///
+    /// Any cached [module][Module] of the same path is removed.
+    #[inline(always)]
+    pub fn insert(&mut self, path: impl Into<String>, script: impl Into<String>) -> Option<String> {
+        let path = path.into();
+        self.clear_cache_for_path(&path);
+        self.sources.insert(path, script.into())
+    }
+    /// Remove the script text of a [module][Module] given its path.
+    ///
+    /// Any cached [module][Module] of the same path is removed.
+    #[inline(always)]
+    pub fn remove(&mut self, path: &str) -> Option<String> {
+        self.clear_cache_for_path(path);
+        self.sources.remove(path)
+    }
+    /// Does the path exist among the scripts added via [`insert`][SourceModuleResolver::insert]?
+    #[inline(always)]
+    pub fn contains_path(&self, path: &str) -> bool {
+        self.sources.contains_key(path)
+    }
+    /// Is a particular path cached?
+    #[inline(always)]
+    pub fn is_cached(&self, path: &str) -> bool {
+        #[cfg(not(feature = "sync"))]
+        return self.cache.borrow().contains_key(path);
+        #[cfg(feature = "sync")]
+        return self.cache.read().unwrap().contains_key(path);
+    }
+    /// Empty the internal cache.
+    #[inline(always)]
+    pub fn clear_cache(&mut self) {
+        #[cfg(not(feature = "sync"))]
+        self.cache.borrow_mut().clear();
+        #[cfg(feature = "sync")]
+        self.cache.write().unwrap().clear();
+    }
+    /// Remove the specified path from internal cache.
+    ///
+    /// The next time this path is resolved, the script text will be compiled once again.
+    #[inline(always)]
+    pub fn clear_cache_for_path(&mut self, path: &str) -> Option<Shared<Module>> {
+        #[cfg(not(feature = "sync"))]
+        return self.cache.borrow_mut().remove(path);
+        #[cfg(feature = "sync")]
+        return self.cache.write().unwrap().remove(path);
+    }
+    /// Compile the script text of a [module][Module] into an [`AST`].
+    fn compile(
+        &self,
+        engine: &Engine,
+        path: &str,
+        pos: Position,
+    ) -> Result<AST, Box<EvalAltResult>> {
+        let script = match self.sources.get(path) {
+            Some(script) => engine.compile(script),
+            None => match self.loader.as_ref().and_then(|loader| loader(path)) {
+                Some(script) => engine.compile(&script),
+                None => {
+                    return EvalAltResult::ErrorModuleNotFound(
+                        path.to_string(),
+                        Default::default(),
+                        pos,
+                    )
+                    .into()
+                }
+            },
+        };
+
+        let mut ast = script.map_err(|err| {
+            Box::new(EvalAltResult::ErrorInModule(
+                path.to_string(),
+                err.into(),
+                pos,
+            ))
+        })?;
+
+        ast.set_source(path);
+
+        Ok(ast)
+    }
+}
+
+impl ModuleResolver for SourceModuleResolver {
+    fn resolve(
+        &self,
+        engine: &Engine,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        // See if it is cached
+        {
+            #[cfg(not(feature = "sync"))]
+            let c = self.cache.borrow();
+            #[cfg(feature = "sync")]
+            let c = self.cache.read().unwrap();
+
+            if let Some(module) = c.get(path) {
+                return Ok(module.clone());
+            }
+        }
+
+        // Compile the script text
+        let scope = Default::default();
+        let ast = self.compile(engine, path, pos)?;
+
+        // Make a module from the AST
+        let m: Shared<Module> = Module::eval_ast_as_new(scope, &ast, engine)
+            .map_err(|err| Box::new(EvalAltResult::ErrorInModule(path.to_string(), err, pos)))?
+            .into();
+
+        // Put it into the cache
+        #[cfg(not(feature = "sync"))]
+        self.cache.borrow_mut().insert(path.to_string(), m.clone());
+        #[cfg(feature = "sync")]
+        self.cache
+            .write()
+            .unwrap()
+            .insert(path.to_string(), m.clone());
+
+        Ok(m)
+    }
+
+    /// Resolve an `AST` based on a path string.
+    ///
+    /// The script text is compiled during each call; the internal cache is by-passed.
+    #[inline(always)]
+    fn resolve_ast(
+        &self,
+        engine: &Engine,
+        path: &str,
+        pos: Position,
+    ) -> Option<Result<AST, Box<EvalAltResult>>> {
+        Some(self.compile(engine, path, pos))
+    }
+}
@@ -118,10 +118,9 @@ impl ModuleResolver for StaticModuleResolver {
         path: &str,
         pos: Position,
     ) -> Result<Shared<Module>, Box<EvalAltResult>> {
-        self.0
-            .get(path)
-            .cloned()
-            .ok_or_else(|| EvalAltResult::ErrorModuleNotFound(path.into(), pos).into())
+        self.0.get(path).cloned().ok_or_else(|| {
+            EvalAltResult::ErrorModuleNotFound(path.into(), Default::default(), pos).into()
+        })
     }
 }
 
@@ -10,6 +10,7 @@ use crate::stdlib::{
     hash::{Hash, Hasher},
     iter::empty,
     mem,
+    num::NonZeroU64,
     string::{String, ToString},
     vec,
     vec::Vec,
@@ -18,6 +19,15 @@ use crate::token::is_valid_identifier;
 use crate::utils::get_hasher;
 use crate::{calc_native_fn_hash, Dynamic, Engine, Module, Position, Scope, StaticVec, AST};
 
+#[cfg(not(feature = "no_function"))]
+use crate::ast::{FnCallExpr, Ident};
+#[cfg(not(feature = "no_function"))]
+use crate::engine::{
+    KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL, KEYWORD_FN_PTR_CURRY, KEYWORD_IS_DEF_VAR, KEYWORD_THIS,
+};
+#[cfg(not(feature = "no_function"))]
+use crate::stdlib::num::NonZeroUsize;
+
 /// Level of optimization performed.
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum OptimizationLevel {
@@ -25,8 +35,8 @@ pub enum OptimizationLevel {
     None,
     /// Only perform simple optimizations without evaluating functions.
     Simple,
-    /// Full optimizations performed, including evaluating functions.
-    /// Take care that this may cause side effects as it essentially assumes that all functions are pure.
+    /// Full optimizations performed, including evaluating functions marked `const` and
+    /// inlining small script-defined functions.
     Full,
 }
 
@@ -68,6 +78,9 @@ struct State<'a> {
     lib: &'a [&'a Module],
     /// Optimization level.
     optimization_level: OptimizationLevel,
+    /// Global constants with constant values, accessible via the `global` namespace.
+    #[cfg(not(feature = "no_module"))]
+    global_constants: &'a [(String, Expr)],
 }
 
 impl<'a> State<'a> {
@@ -82,6 +95,8 @@ impl<'a> State<'a> {
             mods: (&engine.global_sub_modules).into(),
             lib,
             optimization_level: level,
+            #[cfg(not(feature = "no_module"))]
+            global_constants: &[],
         }
     }
     /// Reset the state from dirty to clean.
@@ -128,17 +143,46 @@ impl<'a> State<'a> {
 
         None
     }
+    /// Look up a global constant, accessed via the `global` namespace.
+    #[cfg(not(feature = "no_module"))]
+    #[inline]
+    pub fn find_global_constant(&self, name: &str) -> Option<&Expr> {
+        self.global_constants
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, expr)| expr)
+    }
+}
+
+/// Is a native function safe to call at compile time?
+///
+/// Registered functions must be marked `const` because they may otherwise read the time,
+/// random numbers or other host state.
+///
+/// Returns [`None`] if the function is not registered.  It may then only resolve to a built-in
+/// operator or, at runtime, to another registered function with its arguments converted.
+fn is_const_native_fn(state: &State, hash_fn: NonZeroU64) -> Option<bool> {
+    state
+        .engine
+        .global_namespace
+        .is_fn_const(hash_fn)
+        .or_else(|| {
+            state
+                .engine
+                .global_modules
+                .iter()
+                .find_map(|m| m.is_fn_const(hash_fn))
+        })
 }
 
 /// Call a registered function
 fn call_fn_with_constant_arguments(
     state: &State,
     fn_name: &str,
+    hash_fn: NonZeroU64,
     arg_values: &mut [Dynamic],
 ) -> Option<Dynamic> {
     // Search built-in's and external functions
-    let hash_fn = calc_native_fn_hash(empty(), fn_name, arg_values.iter().map(|a| a.type_id()));
-
     state
         .engine
         .call_native_fn(
@@ -146,7 +190,7 @@ fn call_fn_with_constant_arguments(
             &mut Default::default(),
             state.lib,
             fn_name,
-            hash_fn.unwrap(),
+            hash_fn,
             arg_values.iter_mut().collect::<StaticVec<_>>().as_mut(),
             false,
             true,
@@ -157,6 +201,265 @@ fn call_fn_with_constant_arguments(
         .map(|(v, _)| v)
 }
 
+/// How a script-defined function uses one of its parameters.
+#[cfg(not(feature = "no_function"))]
+#[derive(Debug, Clone, Copy, Default)]
+struct ParamUsage {
+    /// Number of times the parameter is referenced.
+    count: usize,
+    /// Is the parameter ever passed by reference (first argument of a call or target of an index)?
+    by_ref: bool,
+}
+
+/// Get the single expression making up the body of a script-defined function, if any.
+#[cfg(not(feature = "no_function"))]
+fn fn_body_expr(fn_def: &ScriptFnDef) -> Option<&Expr> {
+    match &fn_def.body {
+        Stmt::Expr(expr) => Some(expr),
+        Stmt::Block(statements, _) if statements.len() == 1 => match &statements[0] {
+            Stmt::Expr(expr) => Some(expr),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Check whether an expression in the body of a script-defined function can be inlined,
+/// counting its nodes and recording how parameters are used.
+///
+/// Only pure expressions that refer to nothing but the function's own parameters, and that do not
+/// call other script-defined functions (so the function cannot be recursive), are allowed.
+#[cfg(not(feature = "no_function"))]
+fn is_inlinable_expr(
+    expr: &Expr,
+    fn_def: &ScriptFnDef,
+    lib: &[&Module],
+    size: &mut usize,
+    usage: &mut [ParamUsage],
+) -> bool {
+    // Functions that depend on the calling scope or on the sharing status of values
+    const DONT_INLINE_KEYWORDS: &[&str] = &[
+        KEYWORD_EVAL,
+        KEYWORD_IS_DEF_VAR,
+        KEYWORD_FN_PTR,
+        KEYWORD_FN_PTR_CALL,
+        KEYWORD_FN_PTR_CURRY,
+        "is_shared",
+    ];
+
+    // Mark a parameter passed by reference
+    fn mark_by_ref(expr: &Expr, fn_def: &ScriptFnDef, usage: &mut [ParamUsage]) {
+        if let Expr::Variable(x) = expr {
+            if let Some(index) = fn_def.params.iter().position(|p| *p == x.2.name) {
+                usage[index].by_ref = true;
+            }
+        }
+    }
+
+    *size += 1;
+
+    match expr {
+        Expr::Variable(x) if x.1.is_none() => {
+            match fn_def.params.iter().position(|p| *p == x.2.name) {
+                Some(index) => {
+                    usage[index].count += 1;
+                    true
+                }
+                None => false,
+            }
+        }
+        Expr::FnCall(x, _) => {
+            if x.namespace.is_some()
+                || x.capture
+                || DONT_INLINE_KEYWORDS.contains(&x.name.as_ref())
+                || lib.iter().any(|&m| {
+                    m.get_script_fn(x.name.as_ref(), x.args.len(), false)
+                        .is_some()
+                })
+            {
+                return false;
+            }
+            // Operators do not modify their operands
+            if is_valid_identifier(x.name.chars()) {
+                if let Some(first) = x.args.first() {
+                    mark_by_ref(first, fn_def, usage);
+                }
+            }
+            x.args
+                .iter()
+                .all(|a| is_inlinable_expr(a, fn_def, lib, size, usage))
+        }
+        Expr::Index(x, _) => {
+            mark_by_ref(&x.lhs, fn_def, usage);
+            is_inlinable_expr(&x.lhs, fn_def, lib, size, usage)
+                && is_inlinable_expr(&x.rhs, fn_def, lib, size, usage)
+        }
+        Expr::In(x, _) | Expr::And(x, _) | Expr::Or(x, _) => {
+            is_inlinable_expr(&x.lhs, fn_def, lib, size, usage)
+                && is_inlinable_expr(&x.rhs, fn_def, lib, size, usage)
+        }
+        Expr::Array(x, _) => x
+            .iter()
+            .all(|e| is_inlinable_expr(e, fn_def, lib, size, usage)),
+        Expr::Map(x, _) => x
+            .iter()
+            .all(|(_, e)| is_inlinable_expr(e, fn_def, lib, size, usage)),
+        expr => expr.is_constant(),
+    }
+}
+
+/// Find a small, non-recursive, pure script-defined function to inline at a call site.
+#[cfg(not(feature = "no_function"))]
+fn find_inline_fn<'a>(
+    state: &State<'a>,
+    x: &FnCallExpr,
+) -> Option<(&'a ScriptFnDef, StaticVec<ParamUsage>)> {
+    let max_size = state.engine.max_inline_fn_size;
+
+    if max_size == 0 || x.namespace.is_some() || x.capture {
+        return None;
+    }
+
+    // Whether a script-defined operator is called depends on the types of the arguments
+    if !is_valid_identifier(x.name.chars()) {
+        return None;
+    }
+
+    let fn_def = state
+        .lib
+        .iter()
+        .find_map(|&m| m.get_script_fn(x.name.as_ref(), x.args.len(), false))?;
+
+    // The overload to call depends on the types of the arguments
+    if fn_def.is_overloaded() {
+        return None;
+    }
+
+    // Class constructors create new objects
+    #[cfg(not(feature = "no_object"))]
+    if fn_def.class.is_some() {
+        return None;
+    }
+
+    // Closures capture variables
+    #[cfg(not(feature = "no_closure"))]
+    if !fn_def.externals.is_empty() {
+        return None;
+    }
+
+    let body = fn_body_expr(fn_def)?;
+    let mut size = 0;
+    let mut usage: StaticVec<_> = fn_def.params.iter().map(|_| Default::default()).collect();
+
+    if is_inlinable_expr(body, fn_def, state.lib, &mut size, usage.as_mut()) && size <= max_size {
+        Some((fn_def, usage))
+    } else {
+        None
+    }
+}
+
+/// Replace references to parameters in an inlined function body.
+///
+/// Parameters with a replacement expression are substituted, the others are bound in an enclosing
+/// block with a single variable.
+#[cfg(not(feature = "no_function"))]
+fn substitute_params(expr: &mut Expr, fn_def: &ScriptFnDef, args: &[Option<Expr>]) {
+    match expr {
+        Expr::Variable(x) => {
+            let index = fn_def.params.iter().position(|p| *p == x.2.name).unwrap();
+
+            match &args[index] {
+                Some(arg) => {
+                    let pos = x.2.pos;
+                    *expr = arg.clone();
+                    if expr.is_constant() {
+                        expr.set_position(pos);
+                    }
+                }
+                None => x.0 = NonZeroUsize::new(1),
+            }
+        }
+        Expr::FnCall(x, _) => x
+            .args
+            .iter_mut()
+            .for_each(|a| substitute_params(a, fn_def, args)),
+        Expr::Index(x, _) | Expr::In(x, _) | Expr::And(x, _) | Expr::Or(x, _) => {
+            substitute_params(&mut x.lhs, fn_def, args);
+            substitute_params(&mut x.rhs, fn_def, args);
+        }
+        Expr::Array(x, _) => x
+            .iter_mut()
+            .for_each(|e| substitute_params(e, fn_def, args)),
+        Expr::Map(x, _) => x
+            .iter_mut()
+            .for_each(|(_, e)| substitute_params(e, fn_def, args)),
+        _ => (),
+    }
+}
+
+/// Inline a call to a script-defined function found by [`find_inline_fn`].
+///
+/// Constant arguments, and variables that are only read, are substituted directly.
+/// At most one other argument is allowed, bound to its parameter before the function body is
+/// evaluated, and only when all other arguments are constants so the order of evaluation is kept.
+#[cfg(not(feature = "no_function"))]
+fn inline_fn_call(
+    fn_def: &ScriptFnDef,
+    usage: &[ParamUsage],
+    call_args: &[Expr],
+    pos: Position,
+) -> Option<Expr> {
+    let mut bound = None;
+    let mut has_vars = false;
+
+    let args: StaticVec<_> = call_args
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| match arg {
+            arg if arg.is_constant() => Some(arg.clone()),
+            Expr::Variable(x)
+                if x.1.is_none()
+                    && x.2.name != KEYWORD_THIS
+                    && usage[index].count > 0
+                    && !usage[index].by_ref =>
+            {
+                has_vars = true;
+                Some(arg.clone())
+            }
+            _ => {
+                bound = Some(match bound {
+                    None => index,
+                    Some(_) => usize::MAX,
+                });
+                None
+            }
+        })
+        .collect();
+
+    let mut body = fn_body_expr(fn_def)?.clone();
+
+    match bound {
+        None => {
+            substitute_params(&mut body, fn_def, &args);
+            Some(body)
+        }
+        Some(index) if index != usize::MAX && !has_vars => {
+            substitute_params(&mut body, fn_def, &args);
+
+            let var = Ident {
+                name: fn_def.params[index].clone(),
+                pos,
+            };
+            let statements = vec![
+                Stmt::Let(Box::new(var), Some(call_args[index].clone()), false, pos),
+                Stmt::Expr(body),
+            ];
+            Some(Expr::Stmt(Box::new(statements.into_iter().collect()), pos))
+        }
+        _ => None,
+    }
+}
+
 /// Optimize a block of [statements][Stmt].
 fn optimize_stmt_block(
     mut statements: Vec<Stmt>,
@@ -195,6 +498,16 @@ fn optimize_stmt_block(
                     Expr::Unit(var_def.pos),
                 );
             }
+            // Add imported members into the state
+            #[cfg(not(feature = "no_module"))]
+            Stmt::ImportMembers(x, _, _) => {
+                optimize_expr(&mut x.0, state);
+
+                x.1.iter().for_each(|(name, alias)| {
+                    let alias = alias.as_ref().unwrap_or(name);
+                    state.push_var(&alias.name, AccessMode::ReadWrite, Expr::Unit(alias.pos));
+                });
+            }
             // Optimize the statement
             _ => optimize_stmt(stmt, state, preserve_result),
         }
@@ -225,6 +538,8 @@ fn optimize_stmt_block(
             }
             #[cfg(not(feature = "no_module"))]
             Stmt::Import(expr, _, _) => removed = expr.is_pure(),
+            #[cfg(not(feature = "no_module"))]
+            Stmt::ImportMembers(x, _, _) => removed = x.0.is_pure(),
             _ => {
                 statements.push(expr);
                 break;
@@ -283,7 +598,9 @@ fn optimize_stmt_block(
         [x] if matches!(x, Stmt::Const(_, _, _, _)) => Stmt::Block(statements, pos),
         // Only one import statement - leave it alone
         #[cfg(not(feature = "no_module"))]
-        [x] if matches!(x, Stmt::Import(_, _, _)) => Stmt::Block(statements, pos),
+        [x] if matches!(x, Stmt::Import(_, _, _) | Stmt::ImportMembers(_, _, _)) => {
+            Stmt::Block(statements, pos)
+        }
         // Only one statement - promote
         [_] => {
             if count_promote_as_dirty {
@@ -445,6 +762,9 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut State, preserve_result: bool) {
         // import expr as var;
         #[cfg(not(feature = "no_module"))]
         Stmt::Import(expr, _, _) => optimize_expr(expr, state),
+        // import { var, ... } from expr;
+        #[cfg(not(feature = "no_module"))]
+        Stmt::ImportMembers(x, _, _) => optimize_expr(&mut x.0, state),
         // { block }
         Stmt::Block(statements, pos) => {
             *stmt = optimize_stmt_block(mem::take(statements), *pos, state, preserve_result, true);
@@ -496,6 +816,25 @@ fn optimize_expr(expr: &mut Expr, state: &mut State) {
         KEYWORD_EVAL,  // arbitrary scripts
     ];
 
+    // Inline small script-defined functions
+    #[cfg(not(feature = "no_function"))]
+    if let Expr::FnCall(x, pos) = expr {
+        if state.optimization_level == OptimizationLevel::Full // full optimizations
+            && !DONT_EVAL_KEYWORDS.contains(&x.name.as_ref())
+        {
+            if let Some((fn_def, usage)) = find_inline_fn(state, x) {
+                x.args.iter_mut().for_each(|a| optimize_expr(a, state));
+
+                if let Some(result) = inline_fn_call(fn_def, usage.as_ref(), x.args.as_ref(), *pos)
+                {
+                    state.set_dirty();
+                    *expr = result;
+                }
+                return;
+            }
+        }
+    }
+
     match expr {
         // {}
         Expr::Stmt(x, pos) if x.is_empty() => { state.set_dirty(); *expr = Expr::Unit(*pos) }
@@ -702,6 +1041,21 @@ fn optimize_expr(expr: &mut Expr, state: &mut State) {
 
             if !has_script_fn {
                 let mut arg_values: StaticVec<_> = x.args.iter().map(|e| e.get_constant_value().unwrap()).collect();
+                let hash_fn = calc_native_fn_hash(empty(), x.name.as_ref(), arg_values.iter().map(Dynamic::type_id)).unwrap();
+
+                let result = match is_const_native_fn(state, hash_fn) {
+                    // Call registered functions only if they are `const`
+                    Some(true) => call_fn_with_constant_arguments(&state, x.name.as_ref(), hash_fn, arg_values.as_mut()),
+                    // Never call functions that are not `const`
+                    Some(false) => {
+                        x.args.iter_mut().for_each(|a| optimize_expr(a, state));
+                        return;
+                    }
+                    // Functions that are not registered may be built-in operators, but never
+                    // search for registered functions with the arguments converted
+                    None if arg_values.len() == 2 => run_builtin_binary_op(x.name.as_ref(), &arg_values[0], &arg_values[1]).ok().flatten(),
+                    None => None,
+                };
 
                 // Save the typename of the first argument if it is `type_of()`
                 // This is to avoid `call_args` being passed into the closure
@@ -711,14 +1065,22 @@ fn optimize_expr(expr: &mut Expr, state: &mut State) {
                     ""
                 };
 
-                if let Some(result) = call_fn_with_constant_arguments(&state, x.name.as_ref(), arg_values.as_mut())
+                // The default value is not used if the function may resolve to another via
+                // argument conversions at runtime
+                let def_value = if state.engine.type_conversions.is_empty() {
+                    x.def_value.clone()
+                } else {
+                    None
+                };
+
+                if let Some(result) = result
                                         .or_else(|| {
                                             if !arg_for_type_of.is_empty() {
                                                 // Handle `type_of()`
                                                 Some(arg_for_type_of.to_string().into())
                                             } else {
                                                 // Otherwise use the default value, if any
-                                                x.def_value.clone()
+                                                def_value
                                             }
                                         })
                                         .and_then(|result| map_dynamic_to_expr(result, *pos))
@@ -745,6 +1107,20 @@ fn optimize_expr(expr: &mut Expr, state: &mut State) {
             *expr = result;
         }
 
+        // global::constant-name
+        #[cfg(not(feature = "no_module"))]
+        Expr::Variable(x)
+            if matches!(&x.1, Some((_, ns)) if ns.is_global())
+                && state.find_global_constant(&x.2.name).is_some() =>
+        {
+            state.set_dirty();
+
+            // Replace global constant with value
+            let mut result = state.find_global_constant(&x.2.name).unwrap().clone();
+            result.set_position(x.2.pos);
+            *expr = result;
+        }
+
         // Custom syntax
         Expr::Custom(x, _) => {
             if x.scope_delta != 0 {
@@ -764,6 +1140,7 @@ fn optimize_top_level(
     engine: &Engine,
     scope: &Scope,
     lib: &[&Module],
+    _global_constants: &[(String, Expr)],
     level: OptimizationLevel,
 ) -> Vec<Stmt> {
     // If optimization level is None then skip optimizing
@@ -775,6 +1152,11 @@ fn optimize_top_level(
     // Set up the state
     let mut state = State::new(engine, lib, level);
 
+    #[cfg(not(feature = "no_module"))]
+    {
+        state.global_constants = _global_constants;
+    }
+
     // Add constants and variables from the scope
     scope.iter().for_each(|(name, constant, value)| {
         if !constant {
@@ -826,13 +1208,22 @@ fn optimize_top_level(
                         Expr::Unit(var_def.pos),
                     );
                 }
+                #[cfg(not(feature = "no_module"))]
+                Stmt::ImportMembers(x, _, _) => {
+                    optimize_expr(&mut x.0, &mut state);
+
+                    x.1.iter().for_each(|(name, alias)| {
+                        let alias = alias.as_ref().unwrap_or(name);
+                        state.push_var(&alias.name, AccessMode::ReadWrite, Expr::Unit(alias.pos));
+                    });
+                }
                 _ => {
                     // Keep all variable declarations at this level
                     // and always keep the last return value
                     let keep = match stmt {
                         Stmt::Let(_, _, _, _) | Stmt::Const(_, _, _, _) => true,
                         #[cfg(not(feature = "no_module"))]
-                        Stmt::Import(_, _, _) => true,
+                        Stmt::Import(_, _, _) | Stmt::ImportMembers(_, _, _) => true,
                         _ => i == num_statements - 1,
                     };
                     optimize_stmt(stmt, &mut state, keep);
@@ -862,6 +1253,101 @@ fn optimize_top_level(
     statements
 }
 
+/// Optimize the body of a script-defined function.
+#[cfg(not(feature = "no_function"))]
+fn optimize_fn_def(
+    mut fn_def: ScriptFnDef,
+    engine: &Engine,
+    lib: &[&Module],
+    global_constants: &[(String, Expr)],
+    level: OptimizationLevel,
+) -> ScriptFnDef {
+    let pos = fn_def.body.position();
+
+    // Optimize the other overloads
+    fn_def.overloads = crate::stdlib::mem::take(&mut fn_def.overloads)
+        .into_iter()
+        .map(|f| optimize_fn_def(f, engine, lib, global_constants, level))
+        .collect();
+
+    // Optimize the methods of the class constructed
+    #[cfg(not(feature = "no_object"))]
+    if let Some(class) = fn_def.class.take() {
+        let mut class = crate::fn_native::shared_take_or_clone(class);
+        class.methods = crate::stdlib::mem::take(&mut class.methods)
+            .into_iter()
+            .map(|(hash, f)| {
+                (
+                    hash,
+                    optimize_fn_def(f, engine, lib, global_constants, level),
+                )
+            })
+            .collect();
+        fn_def.class = Some(class.into());
+    }
+
+    // Optimize the function body
+    let mut body = optimize_top_level(
+        vec![fn_def.body],
+        engine,
+        &Scope::new(),
+        lib,
+        global_constants,
+        level,
+    );
+
+    // {} -> Noop
+    fn_def.body = match body.pop().unwrap_or_else(|| Stmt::Noop(pos)) {
+        // { return val; } -> val
+        Stmt::Return((crate::ast::ReturnType::Return, _), Some(expr), _) => Stmt::Expr(expr),
+        // { return; } -> ()
+        Stmt::Return((crate::ast::ReturnType::Return, pos), None, _) => Stmt::Expr(Expr::Unit(pos)),
+        // All others
+        stmt => stmt,
+    };
+    fn_def
+}
+
+/// Collect the global constants that have constant values, from the [`Scope`] and from `const`
+/// statements at global level.
+///
+/// Only `const` statements at the start of the script are collected, before any other code runs.
+/// A constant declared later may not exist yet when a function referring to it is called.
+///
+/// Constants defined more than once are skipped because their values depend on when they are
+/// accessed.
+#[cfg(not(feature = "no_module"))]
+fn collect_global_constants(scope: &Scope, statements: &[Stmt]) -> Vec<(String, Expr)> {
+    let mut constants: Vec<(String, Option<Expr>)> = Vec::new();
+
+    let mut add =
+        |name: &str, value: Option<Expr>| match constants.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = None,
+            None => constants.push((name.into(), value)),
+        };
+
+    scope
+        .iter()
+        .filter(|(_, constant, _)| *constant)
+        .for_each(|(name, _, value)| add(name, map_dynamic_to_expr(value, Position::NONE)));
+
+    let mut leading = true;
+
+    statements.iter().for_each(|stmt| match stmt {
+        Stmt::Const(var_def, expr, _, _) => {
+            let value = expr.as_ref().filter(|expr| expr.is_constant()).cloned();
+            leading = leading && value.is_some();
+            add(&var_def.name, value.filter(|_| leading));
+        }
+        _ => leading = false,
+    });
+
+    constants
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+}
+
 /// Optimize an [`AST`].
 pub fn optimize_into_ast(
     engine: &Engine,
@@ -876,6 +1362,15 @@ pub fn optimize_into_ast(
         level
     };
 
+    #[cfg(not(feature = "no_module"))]
+    let global_constants = if level.is_none() {
+        Vec::new()
+    } else {
+        collect_global_constants(scope, &statements)
+    };
+    #[cfg(feature = "no_module")]
+    let global_constants = Vec::new();
+
     #[cfg(not(feature = "no_function"))]
     let lib = {
         let mut module = Module::new();
@@ -891,12 +1386,23 @@ pub fn optimize_into_ast(
                     access: fn_def.access,
                     body: Default::default(),
                     params: fn_def.params.clone(),
+                    param_types: fn_def.param_types.clone(),
+                    overloads: Default::default(),
                     #[cfg(not(feature = "no_closure"))]
                     externals: fn_def.externals.clone(),
                     lib: None,
                     #[cfg(not(feature = "no_module"))]
                     mods: Default::default(),
+                    #[cfg(not(feature = "no_module"))]
+                    vars: None,
                     comments: Default::default(),
+                    #[cfg(not(feature = "no_object"))]
+                    class: fn_def.class.clone(),
+                    #[cfg(feature = "bytecode")]
+                    #[cfg(not(feature = "no_function"))]
+                    bytecode: Default::default(),
+                    #[cfg(not(feature = "no_module"))]
+                    needs_globals: Default::default(),
                 })
                 .for_each(|fn_def| {
                     lib2.set_script_fn(fn_def);
@@ -904,36 +1410,27 @@ pub fn optimize_into_ast(
 
             _functions
                 .into_iter()
-                .map(|mut fn_def| {
-                    let pos = fn_def.body.position();
-
-                    // Optimize the function body
-                    let mut body = optimize_top_level(
-                        vec![fn_def.body],
-                        engine,
-                        &Scope::new(),
-                        &[&lib2],
-                        level,
-                    );
-
-                    // {} -> Noop
-                    fn_def.body = match body.pop().unwrap_or_else(|| Stmt::Noop(pos)) {
-                        // { return val; } -> val
-                        Stmt::Return((crate::ast::ReturnType::Return, _), Some(expr), _) => {
-                            Stmt::Expr(expr)
-                        }
-                        // { return; } -> ()
-                        Stmt::Return((crate::ast::ReturnType::Return, pos), None, _) => {
-                            Stmt::Expr(Expr::Unit(pos))
-                        }
-                        // All others
-                        stmt => stmt,
-                    };
-                    fn_def
-                })
+                .map(|fn_def| optimize_fn_def(fn_def, engine, &[&lib2], &global_constants, level))
                 .for_each(|fn_def| {
                     module.set_script_fn(fn_def);
                 });
+
+            // Now that function bodies are available, inline small functions into other functions
+            if level.is_full() && engine.max_inline_fn_size > 0 {
+                let mut inlined = Module::new();
+
+                module
+                    .iter_script_fn()
+                    .map(|(_, _, _, _, fn_def)| fn_def.clone())
+                    .map(|fn_def| {
+                        optimize_fn_def(fn_def, engine, &[&module], &global_constants, level)
+                    })
+                    .for_each(|fn_def| {
+                        inlined.set_script_fn(fn_def);
+                    });
+
+                module = inlined;
+            }
         } else {
             _functions.into_iter().for_each(|fn_def| {
                 module.set_script_fn(fn_def);
@@ -951,9 +1448,14 @@ pub fn optimize_into_ast(
     AST::new(
         match level {
             OptimizationLevel::None => statements,
-            OptimizationLevel::Simple | OptimizationLevel::Full => {
-                optimize_top_level(statements, engine, &scope, &[&lib], level)
-            }
+            OptimizationLevel::Simple | OptimizationLevel::Full => optimize_top_level(
+                statements,
+                engine,
+                &scope,
+                &[&lib],
+                &global_constants,
+                level,
+            ),
         },
         lib,
     )
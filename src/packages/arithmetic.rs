@@ -23,7 +23,7 @@ macro_rules! gen_arithmetic_functions {
 
             #[export_module]
             pub mod functions {
-                #[rhai_fn(name = "+", return_raw)]
+                #[rhai_fn(name = "+", return_raw, pure, const)]
                 pub fn add(x: $arg_type, y: $arg_type) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         x.checked_add(y).ok_or_else(|| make_err(format!("Addition overflow: {} + {}", x, y))).map(Dynamic::from)
@@ -31,7 +31,7 @@ macro_rules! gen_arithmetic_functions {
                         Ok(Dynamic::from(x + y))
                     }
                 }
-                #[rhai_fn(name = "-", return_raw)]
+                #[rhai_fn(name = "-", return_raw, pure, const)]
                 pub fn subtract(x: $arg_type, y: $arg_type) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         x.checked_sub(y).ok_or_else(|| make_err(format!("Subtraction overflow: {} - {}", x, y))).map(Dynamic::from)
@@ -39,7 +39,7 @@ macro_rules! gen_arithmetic_functions {
                         Ok(Dynamic::from(x - y))
                     }
                 }
-                #[rhai_fn(name = "*", return_raw)]
+                #[rhai_fn(name = "*", return_raw, pure, const)]
                 pub fn multiply(x: $arg_type, y: $arg_type) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         x.checked_mul(y).ok_or_else(|| make_err(format!("Multiplication overflow: {} * {}", x, y))).map(Dynamic::from)
@@ -47,7 +47,7 @@ macro_rules! gen_arithmetic_functions {
                         Ok(Dynamic::from(x * y))
                     }
                 }
-                #[rhai_fn(name = "/", return_raw)]
+                #[rhai_fn(name = "/", return_raw, pure, const)]
                 pub fn divide(x: $arg_type, y: $arg_type) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         // Detect division by zero
@@ -60,7 +60,7 @@ macro_rules! gen_arithmetic_functions {
                         Ok(Dynamic::from(x / y))
                     }
                 }
-                #[rhai_fn(name = "%", return_raw)]
+                #[rhai_fn(name = "%", return_raw, pure, const)]
                 pub fn modulo(x: $arg_type, y: $arg_type) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         x.checked_rem(y).ok_or_else(|| make_err(format!("Modulo division by zero or overflow: {} % {}", x, y))).map(Dynamic::from)
@@ -68,7 +68,7 @@ macro_rules! gen_arithmetic_functions {
                         Ok(Dynamic::from(x % y))
                     }
                 }
-                #[rhai_fn(name = "~", return_raw)]
+                #[rhai_fn(name = "~", return_raw, pure, const)]
                 pub fn power(x: INT, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
@@ -83,7 +83,7 @@ macro_rules! gen_arithmetic_functions {
                     }
                 }
 
-                #[rhai_fn(name = "<<", return_raw)]
+                #[rhai_fn(name = "<<", return_raw, pure, const)]
                 pub fn shift_left(x: $arg_type, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
@@ -97,7 +97,7 @@ macro_rules! gen_arithmetic_functions {
                         Ok(Dynamic::from(x << y))
                     }
                 }
-                #[rhai_fn(name = ">>", return_raw)]
+                #[rhai_fn(name = ">>", return_raw, pure, const)]
                 pub fn shift_right(x: $arg_type, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
@@ -111,15 +111,15 @@ macro_rules! gen_arithmetic_functions {
                         Ok(Dynamic::from(x >> y))
                     }
                 }
-                #[rhai_fn(name = "&")]
+                #[rhai_fn(name = "&", pure, const)]
                 pub fn binary_and(x: $arg_type, y: $arg_type) -> $arg_type {
                     x & y
                 }
-                #[rhai_fn(name = "|")]
+                #[rhai_fn(name = "|", pure, const)]
                 pub fn binary_or(x: $arg_type, y: $arg_type) -> $arg_type {
                     x | y
                 }
-                #[rhai_fn(name = "^")]
+                #[rhai_fn(name = "^", pure, const)]
                 pub fn binary_xor(x: $arg_type, y: $arg_type) -> $arg_type {
                     x ^ y
                 }
@@ -135,7 +135,7 @@ macro_rules! gen_signed_functions {
 
             #[export_module]
             pub mod functions {
-                #[rhai_fn(name = "-", return_raw)]
+                #[rhai_fn(name = "-", return_raw, pure, const)]
                 pub fn neg(x: $arg_type) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         x.checked_neg().ok_or_else(|| make_err(format!("Negation overflow: -{}", x))).map(Dynamic::from)
@@ -143,11 +143,11 @@ macro_rules! gen_signed_functions {
                         Ok(Dynamic::from(-x))
                     }
                 }
-                #[rhai_fn(name = "+")]
+                #[rhai_fn(name = "+", pure, const)]
                 pub fn plus(x: $arg_type) -> $arg_type {
                     x
                 }
-                #[rhai_fn(return_raw)]
+                #[rhai_fn(return_raw, pure, const)]
                 pub fn abs(x: $arg_type) -> Result<Dynamic, Box<EvalAltResult>> {
                     if cfg!(not(feature = "unchecked")) {
                         x.checked_abs().ok_or_else(|| make_err(format!("Negation overflow: -{}", x))).map(Dynamic::from)
@@ -155,6 +155,7 @@ macro_rules! gen_signed_functions {
                         Ok(Dynamic::from(x.abs()))
                     }
                 }
+                #[rhai_fn(pure, const)]
                 pub fn sign(x: $arg_type) -> INT {
                     if x == 0 {
                         0
@@ -226,42 +227,44 @@ gen_signed_functions!(signed_num_128 => i128);
 mod f32_functions {
     #[cfg(not(feature = "f32_float"))]
     pub mod basic_arithmetic {
-        #[rhai_fn(name = "+")]
+        #[rhai_fn(name = "+", pure, const)]
         pub fn add(x: f32, y: f32) -> f32 {
             x + y
         }
-        #[rhai_fn(name = "-")]
+        #[rhai_fn(name = "-", pure, const)]
         pub fn subtract(x: f32, y: f32) -> f32 {
             x - y
         }
-        #[rhai_fn(name = "*")]
+        #[rhai_fn(name = "*", pure, const)]
         pub fn multiply(x: f32, y: f32) -> f32 {
             x * y
         }
-        #[rhai_fn(name = "/")]
+        #[rhai_fn(name = "/", pure, const)]
         pub fn divide(x: f32, y: f32) -> f32 {
             x / y
         }
-        #[rhai_fn(name = "%")]
+        #[rhai_fn(name = "%", pure, const)]
         pub fn modulo(x: f32, y: f32) -> f32 {
             x % y
         }
-        #[rhai_fn(name = "~", return_raw)]
+        #[rhai_fn(name = "~", return_raw, pure, const)]
         pub fn pow_f_f(x: f32, y: f32) -> Result<Dynamic, Box<EvalAltResult>> {
             Ok(Dynamic::from(x.powf(y)))
         }
     }
-    #[rhai_fn(name = "-")]
+    #[rhai_fn(name = "-", pure, const)]
     pub fn neg(x: f32) -> f32 {
         -x
     }
-    #[rhai_fn(name = "+")]
+    #[rhai_fn(name = "+", pure, const)]
     pub fn plus(x: f32) -> f32 {
         -x
     }
+    #[rhai_fn(pure, const)]
     pub fn abs(x: f32) -> f32 {
         x.abs()
     }
+    #[rhai_fn(pure, const)]
     pub fn sign(x: f32) -> INT {
         if x == 0.0 {
             0
@@ -271,7 +274,7 @@ mod f32_functions {
             1
         }
     }
-    #[rhai_fn(name = "~", return_raw)]
+    #[rhai_fn(name = "~", return_raw, pure, const)]
     pub fn pow_f_i(x: f32, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
         if cfg!(not(feature = "unchecked")) && y > (i32::MAX as INT) {
             Err(make_err(format!(
@@ -289,42 +292,44 @@ mod f32_functions {
 mod f64_functions {
     #[cfg(feature = "f32_float")]
     pub mod basic_arithmetic {
-        #[rhai_fn(name = "+")]
+        #[rhai_fn(name = "+", pure, const)]
         pub fn add(x: f64, y: f64) -> f64 {
             x + y
         }
-        #[rhai_fn(name = "-")]
+        #[rhai_fn(name = "-", pure, const)]
         pub fn subtract(x: f64, y: f64) -> f64 {
             x - y
         }
-        #[rhai_fn(name = "*")]
+        #[rhai_fn(name = "*", pure, const)]
         pub fn multiply(x: f64, y: f64) -> f64 {
             x * y
         }
-        #[rhai_fn(name = "/")]
+        #[rhai_fn(name = "/", pure, const)]
         pub fn divide(x: f64, y: f64) -> f64 {
             x / y
         }
-        #[rhai_fn(name = "%")]
+        #[rhai_fn(name = "%", pure, const)]
         pub fn modulo(x: f64, y: f64) -> f64 {
             x % y
         }
-        #[rhai_fn(name = "~", return_raw)]
+        #[rhai_fn(name = "~", return_raw, pure, const)]
         pub fn pow_f_f(x: f64, y: f64) -> Result<Dynamic, Box<EvalAltResult>> {
             Ok(Dynamic::from(x.powf(y)))
         }
     }
-    #[rhai_fn(name = "-")]
+    #[rhai_fn(name = "-", pure, const)]
     pub fn neg(x: f64) -> f64 {
         -x
     }
-    #[rhai_fn(name = "+")]
+    #[rhai_fn(name = "+", pure, const)]
     pub fn plus(x: f64) -> f64 {
         -x
     }
+    #[rhai_fn(pure, const)]
     pub fn abs(x: f64) -> f64 {
         x.abs()
     }
+    #[rhai_fn(pure, const)]
     pub fn sign(x: f64) -> INT {
         if x == 0.0 {
             0
@@ -334,7 +339,7 @@ mod f64_functions {
             1
         }
     }
-    #[rhai_fn(name = "~", return_raw)]
+    #[rhai_fn(name = "~", return_raw, pure, const)]
     pub fn pow_f_i(x: FLOAT, y: INT) -> Result<Dynamic, Box<EvalAltResult>> {
         if cfg!(not(feature = "unchecked")) && y > (i32::MAX as INT) {
             Err(make_err(format!(
@@ -57,7 +57,7 @@ fn collect_fn_metadata(ctx: NativeCallContext) -> Array {
         namespace: Option<ImmutableString>,
         f: &ScriptFnDef,
     ) -> Map {
-        let mut map = Map::with_capacity(6);
+        let mut map = Map::with_capacity(7);
 
         if let Some(ns) = namespace {
             map.insert(dict["namespace"].clone(), ns.into());
@@ -84,6 +84,16 @@ fn collect_fn_metadata(ctx: NativeCallContext) -> Array {
                 .collect::<Array>()
                 .into(),
         );
+        if !f.param_types.is_empty() {
+            map.insert(
+                dict["param_types"].clone(),
+                f.param_types
+                    .iter()
+                    .map(|t| t.clone().map(Into::<Dynamic>::into).unwrap_or_default())
+                    .collect::<Array>()
+                    .into(),
+            );
+        }
 
         map.into()
     }
@@ -95,9 +105,10 @@ fn collect_fn_metadata(ctx: NativeCallContext) -> Array {
         namespace: ImmutableString,
         module: &Module,
     ) {
-        module.iter_script_fn().for_each(|(_, _, _, _, f)| {
-            list.push(make_metadata(dict, Some(namespace.clone()), f).into())
-        });
+        module
+            .iter_script_fn()
+            .flat_map(|(_, _, _, _, f)| f.iter_overloads())
+            .for_each(|f| list.push(make_metadata(dict, Some(namespace.clone()), f).into()));
         module.iter_sub_modules().for_each(|(ns, m)| {
             let ns: ImmutableString = format!("{}::{}", namespace, ns).into();
             scan_module(list, dict, ns, m.as_ref())
@@ -105,7 +116,7 @@ fn collect_fn_metadata(ctx: NativeCallContext) -> Array {
     }
 
     // Intern strings
-    let mut dict = HashMap::<&str, ImmutableString>::with_capacity(8);
+    let mut dict = HashMap::<&str, ImmutableString>::with_capacity(9);
     [
         "namespace",
         "name",
@@ -114,6 +125,7 @@ fn collect_fn_metadata(ctx: NativeCallContext) -> Array {
         "private",
         "is_anonymous",
         "params",
+        "param_types",
     ]
     .iter()
     .for_each(|&s| {
@@ -125,7 +137,8 @@ fn collect_fn_metadata(ctx: NativeCallContext) -> Array {
     ctx.lib
         .iter()
         .flat_map(|m| m.iter_script_fn())
-        .for_each(|(_, _, _, _, f)| list.push(make_metadata(&dict, None, f).into()));
+        .flat_map(|(_, _, _, _, f)| f.iter_overloads())
+        .for_each(|f| list.push(make_metadata(&dict, None, f).into()));
 
     if let Some(mods) = ctx.mods {
         mods.iter_raw()
@@ -12,27 +12,27 @@ macro_rules! gen_cmp_functions {
 
             #[export_module]
             pub mod functions {
-                #[rhai_fn(name = "<")]
+                #[rhai_fn(name = "<", pure, const)]
                 pub fn lt(x: $arg_type, y: $arg_type) -> bool {
                     x < y
                 }
-                #[rhai_fn(name = "<=")]
+                #[rhai_fn(name = "<=", pure, const)]
                 pub fn lte(x: $arg_type, y: $arg_type) -> bool {
                     x <= y
                 }
-                #[rhai_fn(name = ">")]
+                #[rhai_fn(name = ">", pure, const)]
                 pub fn gt(x: $arg_type, y: $arg_type) -> bool {
                     x > y
                 }
-                #[rhai_fn(name = ">=")]
+                #[rhai_fn(name = ">=", pure, const)]
                 pub fn gte(x: $arg_type, y: $arg_type) -> bool {
                     x >= y
                 }
-                #[rhai_fn(name = "==")]
+                #[rhai_fn(name = "==", pure, const)]
                 pub fn eq(x: $arg_type, y: $arg_type) -> bool {
                     x == y
                 }
-                #[rhai_fn(name = "!=")]
+                #[rhai_fn(name = "!=", pure, const)]
                 pub fn ne(x: $arg_type, y: $arg_type) -> bool {
                     x != y
                 }
@@ -70,7 +70,8 @@ def_package!(crate:LogicPackage:"Logical operators.", lib, {
         reg_functions!(lib += float; f64);
     }
 
-    set_exported_fn!(lib, "!", not);
+    let hash = set_exported_fn!(lib, "!", not);
+    lib.update_fn_const(hash, true);
 });
 
 // Logic operators
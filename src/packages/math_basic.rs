@@ -38,7 +38,8 @@ macro_rules! gen_conversion_functions {
 
 macro_rules! reg_functions {
     ($mod_name:ident += $root:ident :: $func_name:ident ( $($arg_type:ident),+ ) ) => { $(
-        set_exported_fn!($mod_name, stringify!($func_name), $root::$arg_type::$func_name);
+        let hash = set_exported_fn!($mod_name, stringify!($func_name), $root::$arg_type::$func_name);
+        $mod_name.update_fn_const(hash, true);
     )* }
 }
 
@@ -80,7 +81,7 @@ def_package!(crate:BasicMathPackage:"Basic mathematic functions.", lib, {
 
 #[export_module]
 mod int_functions {
-    #[rhai_fn(name = "parse_int", return_raw)]
+    #[rhai_fn(name = "parse_int", return_raw, pure, const)]
     pub fn parse_int_radix(s: &str, radix: INT) -> Result<Dynamic, Box<EvalAltResult>> {
         if radix < 2 || radix > 36 {
             return EvalAltResult::ErrorArithmetic(
@@ -100,7 +101,7 @@ mod int_functions {
                 .into()
             })
     }
-    #[rhai_fn(name = "parse_int", return_raw)]
+    #[rhai_fn(name = "parse_int", return_raw, pure, const)]
     pub fn parse_int(s: &str) -> Result<Dynamic, Box<EvalAltResult>> {
         parse_int_radix(s, 10)
     }
@@ -111,39 +112,51 @@ mod int_functions {
 mod trig_functions {
     use crate::FLOAT;
 
+    #[rhai_fn(pure, const)]
     pub fn sin(x: FLOAT) -> FLOAT {
         x.to_radians().sin()
     }
+    #[rhai_fn(pure, const)]
     pub fn cos(x: FLOAT) -> FLOAT {
         x.to_radians().cos()
     }
+    #[rhai_fn(pure, const)]
     pub fn tan(x: FLOAT) -> FLOAT {
         x.to_radians().tan()
     }
+    #[rhai_fn(pure, const)]
     pub fn sinh(x: FLOAT) -> FLOAT {
         x.to_radians().sinh()
     }
+    #[rhai_fn(pure, const)]
     pub fn cosh(x: FLOAT) -> FLOAT {
         x.to_radians().cosh()
     }
+    #[rhai_fn(pure, const)]
     pub fn tanh(x: FLOAT) -> FLOAT {
         x.to_radians().tanh()
     }
+    #[rhai_fn(pure, const)]
     pub fn asin(x: FLOAT) -> FLOAT {
         x.asin().to_degrees()
     }
+    #[rhai_fn(pure, const)]
     pub fn acos(x: FLOAT) -> FLOAT {
         x.acos().to_degrees()
     }
+    #[rhai_fn(pure, const)]
     pub fn atan(x: FLOAT) -> FLOAT {
         x.atan().to_degrees()
     }
+    #[rhai_fn(pure, const)]
     pub fn asinh(x: FLOAT) -> FLOAT {
         x.asinh().to_degrees()
     }
+    #[rhai_fn(pure, const)]
     pub fn acosh(x: FLOAT) -> FLOAT {
         x.acosh().to_degrees()
     }
+    #[rhai_fn(pure, const)]
     pub fn atanh(x: FLOAT) -> FLOAT {
         x.atanh().to_degrees()
     }
@@ -154,54 +167,59 @@ mod trig_functions {
 mod float_functions {
     use crate::FLOAT;
 
+    #[rhai_fn(pure, const)]
     pub fn sqrt(x: FLOAT) -> FLOAT {
         x.sqrt()
     }
+    #[rhai_fn(pure, const)]
     pub fn exp(x: FLOAT) -> FLOAT {
         x.exp()
     }
+    #[rhai_fn(pure, const)]
     pub fn ln(x: FLOAT) -> FLOAT {
         x.ln()
     }
+    #[rhai_fn(pure, const)]
     pub fn log(x: FLOAT, base: FLOAT) -> FLOAT {
         x.log(base)
     }
+    #[rhai_fn(pure, const)]
     pub fn log10(x: FLOAT) -> FLOAT {
         x.log10()
     }
-    #[rhai_fn(name = "floor", get = "floor")]
+    #[rhai_fn(name = "floor", get = "floor", pure, const)]
     pub fn floor(x: FLOAT) -> FLOAT {
         x.floor()
     }
-    #[rhai_fn(name = "ceiling", get = "ceiling")]
+    #[rhai_fn(name = "ceiling", get = "ceiling", pure, const)]
     pub fn ceiling(x: FLOAT) -> FLOAT {
         x.ceil()
     }
-    #[rhai_fn(name = "round", get = "round")]
+    #[rhai_fn(name = "round", get = "round", pure, const)]
     pub fn round(x: FLOAT) -> FLOAT {
         x.ceil()
     }
-    #[rhai_fn(name = "int", get = "int")]
+    #[rhai_fn(name = "int", get = "int", pure, const)]
     pub fn int(x: FLOAT) -> FLOAT {
         x.trunc()
     }
-    #[rhai_fn(name = "fraction", get = "fraction")]
+    #[rhai_fn(name = "fraction", get = "fraction", pure, const)]
     pub fn fraction(x: FLOAT) -> FLOAT {
         x.fract()
     }
-    #[rhai_fn(name = "is_nan", get = "is_nan")]
+    #[rhai_fn(name = "is_nan", get = "is_nan", pure, const)]
     pub fn is_nan(x: FLOAT) -> bool {
         x.is_nan()
     }
-    #[rhai_fn(name = "is_finite", get = "is_finite")]
+    #[rhai_fn(name = "is_finite", get = "is_finite", pure, const)]
     pub fn is_finite(x: FLOAT) -> bool {
         x.is_finite()
     }
-    #[rhai_fn(name = "is_infinite", get = "is_infinite")]
+    #[rhai_fn(name = "is_infinite", get = "is_infinite", pure, const)]
     pub fn is_infinite(x: FLOAT) -> bool {
         x.is_infinite()
     }
-    #[rhai_fn(name = "to_int", return_raw)]
+    #[rhai_fn(name = "to_int", return_raw, pure, const)]
     pub fn f32_to_int(x: f32) -> Result<Dynamic, Box<EvalAltResult>> {
         if cfg!(not(feature = "unchecked")) && x > (MAX_INT as f32) {
             EvalAltResult::ErrorArithmetic(
@@ -213,7 +231,7 @@ mod float_functions {
             Ok((x.trunc() as INT).into())
         }
     }
-    #[rhai_fn(name = "to_int", return_raw)]
+    #[rhai_fn(name = "to_int", return_raw, pure, const)]
     pub fn f64_to_int(x: f64) -> Result<Dynamic, Box<EvalAltResult>> {
         if cfg!(not(feature = "unchecked")) && x > (MAX_INT as f64) {
             EvalAltResult::ErrorArithmetic(
@@ -225,7 +243,7 @@ mod float_functions {
             Ok((x.trunc() as INT).into())
         }
     }
-    #[rhai_fn(return_raw)]
+    #[rhai_fn(return_raw, pure, const)]
     pub fn parse_float(s: &str) -> Result<Dynamic, Box<EvalAltResult>> {
         s.trim()
             .parse::<FLOAT>()
@@ -240,7 +258,7 @@ mod float_functions {
     }
     #[cfg(not(feature = "f32_float"))]
     pub mod f32_f64 {
-        #[rhai_fn(name = "to_float")]
+        #[rhai_fn(name = "to_float", pure, const)]
         pub fn f32_to_f64(x: f32) -> f64 {
             x as f64
         }
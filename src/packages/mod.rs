@@ -11,8 +11,11 @@ mod map_basic;
 mod math_basic;
 mod pkg_core;
 mod pkg_std;
+#[cfg(feature = "regex")]
+mod regex_basic;
 mod string_basic;
 mod string_more;
+mod string_unicode;
 mod time_basic;
 
 pub use arithmetic::ArithmeticPackage;
@@ -26,8 +29,11 @@ pub use map_basic::BasicMapPackage;
 pub use math_basic::BasicMathPackage;
 pub use pkg_core::CorePackage;
 pub use pkg_std::StandardPackage;
+#[cfg(feature = "regex")]
+pub use regex_basic::RegexPackage;
 pub use string_basic::BasicStringPackage;
 pub use string_more::MoreStringPackage;
+pub use string_unicode::UnicodeStringPackage;
 #[cfg(not(feature = "no_std"))]
 pub use time_basic::BasicTimePackage;
 
@@ -4,7 +4,9 @@ use super::array_basic::BasicArrayPackage;
 use super::map_basic::BasicMapPackage;
 use super::math_basic::BasicMathPackage;
 use super::pkg_core::CorePackage;
-use super::string_more::MoreStringPackage;
+#[cfg(feature = "regex")]
+use super::regex_basic::RegexPackage;
+use super::string_unicode::UnicodeStringPackage;
 #[cfg(not(feature = "no_std"))]
 use super::time_basic::BasicTimePackage;
 
@@ -19,5 +21,7 @@ def_package!(crate:StandardPackage:"_Standard_ package containing all built-in f
     BasicMapPackage::init(lib);
     #[cfg(not(feature = "no_std"))]
     BasicTimePackage::init(lib);
-    MoreStringPackage::init(lib);
+    UnicodeStringPackage::init(lib);
+    #[cfg(feature = "regex")]
+    RegexPackage::init(lib);
 });
@@ -0,0 +1,293 @@
+#![allow(non_snake_case)]
+
+use crate::engine::MAX_REGEX_CACHE_SIZE;
+use crate::plugin::*;
+use crate::stdlib::{boxed::Box, format, string::ToString};
+use crate::{def_package, Dynamic, Engine, EvalAltResult, ImmutableString, Position};
+use regex::{Regex, RegexBuilder};
+
+#[cfg(not(feature = "no_index"))]
+use crate::{Array, FnPtr, INT};
+
+#[cfg(not(feature = "no_object"))]
+use crate::Map;
+
+def_package!(crate:RegexPackage:"Regular expressions.", lib, {
+    combine_with_exported_module!(lib, "regex", regex_functions);
+});
+
+/// Compile a regular expression, reusing the compiled expressions cached by the [`Engine`].
+fn compile_regex(engine: &Engine, pattern: &str) -> Result<Regex, Box<EvalAltResult>> {
+    #[cfg(not(feature = "sync"))]
+    let cached = engine.regex_cache.borrow().get(pattern).cloned();
+    #[cfg(feature = "sync")]
+    let cached = engine.regex_cache.read().unwrap().get(pattern).cloned();
+
+    if let Some(re) = cached {
+        return Ok(re);
+    }
+
+    #[allow(unused_mut)]
+    let mut builder = RegexBuilder::new(pattern);
+
+    #[cfg(not(feature = "unchecked"))]
+    if engine.max_regex_size() > 0 {
+        builder.size_limit(engine.max_regex_size());
+    }
+
+    let re = builder.build().map_err(|err| match err {
+        regex::Error::CompiledTooBig(_) => Box::new(EvalAltResult::ErrorDataTooLarge(
+            "Size of regular expression".to_string(),
+            Position::NONE,
+        )),
+        err => Box::new(EvalAltResult::ErrorRuntime(
+            format!("Invalid regular expression: {}", err).into(),
+            Position::NONE,
+        )),
+    })?;
+
+    #[cfg(not(feature = "sync"))]
+    let mut cache = engine.regex_cache.borrow_mut();
+    #[cfg(feature = "sync")]
+    let mut cache = engine.regex_cache.write().unwrap();
+
+    if cache.len() >= MAX_REGEX_CACHE_SIZE {
+        cache.clear();
+    }
+    cache.insert(pattern.into(), re.clone());
+
+    Ok(re)
+}
+
+/// Check that a string does not exceed the maximum string size.
+#[cfg(not(feature = "unchecked"))]
+fn check_string_size(ctx: &NativeCallContext, s: &str) -> Result<(), Box<EvalAltResult>> {
+    if ctx.engine().max_string_size() > 0 && s.len() > ctx.engine().max_string_size() {
+        EvalAltResult::ErrorDataTooLarge("Length of string".to_string(), Position::NONE).into()
+    } else {
+        Ok(())
+    }
+}
+
+/// Collect items into an array, stopping as soon as it exceeds the maximum array size.
+#[cfg(not(feature = "no_index"))]
+fn collect_array(
+    _ctx: &NativeCallContext,
+    items: impl Iterator<Item = Dynamic>,
+) -> Result<Array, Box<EvalAltResult>> {
+    #[cfg(not(feature = "unchecked"))]
+    if _ctx.engine().max_array_size() > 0 {
+        let max_size = _ctx.engine().max_array_size();
+        let array: Array = items.take(max_size + 1).collect();
+
+        return if array.len() > max_size {
+            EvalAltResult::ErrorDataTooLarge("Size of array".to_string(), Position::NONE).into()
+        } else {
+            Ok(array)
+        };
+    }
+
+    Ok(items.collect())
+}
+
+/// Convert the capture groups of a match into an array, with `()` for groups that did not match.
+#[cfg(not(feature = "no_index"))]
+fn captures_to_array(captures: &regex::Captures) -> Array {
+    captures
+        .iter()
+        .map(|group| group.map_or(Dynamic::UNIT, |m| m.as_str().into()))
+        .collect()
+}
+
+/// Replace matches of a regular expression (up to `limit`, or all if zero) with the results of
+/// calling a function with the capture groups of each match.
+#[cfg(not(feature = "no_index"))]
+fn replace_with_fn(
+    ctx: NativeCallContext,
+    fn_name: &str,
+    re: &Regex,
+    text: &str,
+    replacer: FnPtr,
+    limit: usize,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let mut result = crate::stdlib::string::String::with_capacity(text.len());
+    let mut last = 0;
+
+    for captures in re
+        .captures_iter(text)
+        .take(if limit == 0 { usize::MAX } else { limit })
+    {
+        let m = captures.get(0).unwrap();
+
+        let replacement = replacer
+            .call_dynamic(ctx, None, [captures_to_array(&captures).into()])
+            .map_err(|err| {
+                Box::new(EvalAltResult::ErrorInFunctionCall(
+                    fn_name.to_string(),
+                    ctx.source().unwrap_or("").to_string(),
+                    err,
+                    Position::NONE,
+                ))
+            })?;
+
+        result.push_str(&text[last..m.start()]);
+        result.push_str(&replacement.to_string());
+        last = m.end();
+
+        #[cfg(not(feature = "unchecked"))]
+        check_string_size(&ctx, &result)?;
+    }
+
+    result.push_str(&text[last..]);
+
+    #[cfg(not(feature = "unchecked"))]
+    check_string_size(&ctx, &result)?;
+
+    Ok(result.into())
+}
+
+#[export_module]
+mod regex_functions {
+    #[rhai_fn(return_raw)]
+    pub fn regex(ctx: NativeCallContext, pattern: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        compile_regex(ctx.engine(), pattern).map(Dynamic::from)
+    }
+
+    #[rhai_fn(get = "pattern", name = "to_string", name = "print", pure)]
+    pub fn pattern(re: &mut Regex) -> ImmutableString {
+        re.as_str().into()
+    }
+    #[rhai_fn(name = "to_debug", name = "debug", pure)]
+    pub fn to_debug(re: &mut Regex) -> ImmutableString {
+        format!("regex({:?})", re.as_str()).into()
+    }
+
+    #[rhai_fn(pure)]
+    pub fn is_match(re: &mut Regex, text: &str) -> bool {
+        re.is_match(text)
+    }
+    #[rhai_fn(pure)]
+    pub fn find(re: &mut Regex, text: &str) -> Dynamic {
+        re.find(text)
+            .map_or(Dynamic::UNIT, |m| m.as_str().to_string().into())
+    }
+
+    #[rhai_fn(name = "replace", return_raw, pure)]
+    pub fn replace(
+        _ctx: NativeCallContext,
+        re: &mut Regex,
+        text: &str,
+        replacement: &str,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let result = re.replace(text, replacement);
+
+        #[cfg(not(feature = "unchecked"))]
+        check_string_size(&_ctx, &result)?;
+
+        Ok(result.into_owned().into())
+    }
+    #[rhai_fn(name = "replace_all", return_raw, pure)]
+    pub fn replace_all(
+        _ctx: NativeCallContext,
+        re: &mut Regex,
+        text: &str,
+        replacement: &str,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let result = re.replace_all(text, replacement);
+
+        #[cfg(not(feature = "unchecked"))]
+        check_string_size(&_ctx, &result)?;
+
+        Ok(result.into_owned().into())
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    pub mod arrays {
+        #[rhai_fn(return_raw, pure)]
+        pub fn find_all(
+            _ctx: NativeCallContext,
+            re: &mut Regex,
+            text: &str,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            let matches = re.find_iter(text).map(|m| m.as_str().to_string().into());
+            Ok(collect_array(&_ctx, matches)?.into())
+        }
+        #[rhai_fn(pure)]
+        pub fn captures(re: &mut Regex, text: &str) -> Dynamic {
+            re.captures(text).map_or(Dynamic::UNIT, |captures| {
+                captures_to_array(&captures).into()
+            })
+        }
+        #[rhai_fn(return_raw, pure)]
+        pub fn captures_all(
+            _ctx: NativeCallContext,
+            re: &mut Regex,
+            text: &str,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            let captures = re
+                .captures_iter(text)
+                .map(|captures| captures_to_array(&captures).into());
+            Ok(collect_array(&_ctx, captures)?.into())
+        }
+
+        #[rhai_fn(name = "split", return_raw, pure)]
+        pub fn split(
+            _ctx: NativeCallContext,
+            re: &mut Regex,
+            text: &str,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            let segments = re.split(text).map(|s| s.to_string().into());
+            Ok(collect_array(&_ctx, segments)?.into())
+        }
+        #[rhai_fn(name = "split", return_raw, pure)]
+        pub fn splitn(
+            _ctx: NativeCallContext,
+            re: &mut Regex,
+            text: &str,
+            segments: INT,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            let segments = if segments < 1 { 1 } else { segments as usize };
+            let segments = re.splitn(text, segments).map(|s| s.to_string().into());
+            Ok(collect_array(&_ctx, segments)?.into())
+        }
+
+        #[rhai_fn(name = "replace", return_raw, pure)]
+        pub fn replace_with_fn_ptr(
+            ctx: NativeCallContext,
+            re: &mut Regex,
+            text: &str,
+            replacer: FnPtr,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            replace_with_fn(ctx, "replace", re, text, replacer, 1)
+        }
+        #[rhai_fn(name = "replace_all", return_raw, pure)]
+        pub fn replace_all_with_fn_ptr(
+            ctx: NativeCallContext,
+            re: &mut Regex,
+            text: &str,
+            replacer: FnPtr,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            replace_with_fn(ctx, "replace_all", re, text, replacer, 0)
+        }
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    pub mod maps {
+        /// Named capture groups that did not match are `()`.
+        #[rhai_fn(pure)]
+        pub fn named_captures(re: &mut Regex, text: &str) -> Dynamic {
+            re.captures(text).map_or(Dynamic::UNIT, |captures| {
+                re.capture_names()
+                    .flatten()
+                    .map(|name| {
+                        let value = captures
+                            .name(name)
+                            .map_or(Dynamic::UNIT, |m| m.as_str().into());
+                        (name.into(), value)
+                    })
+                    .collect::<Map>()
+                    .into()
+            })
+        }
+    }
+}
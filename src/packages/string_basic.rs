@@ -1,6 +1,6 @@
 #![allow(non_snake_case)]
 
-use crate::engine::{KEYWORD_DEBUG, KEYWORD_PRINT};
+use crate::engine::{FN_TO_DEBUG, FN_TO_STRING, KEYWORD_DEBUG, KEYWORD_PRINT};
 use crate::plugin::*;
 use crate::stdlib::{
     fmt::{Debug, Display},
@@ -15,9 +15,6 @@ use crate::Array;
 #[cfg(not(feature = "no_object"))]
 use crate::Map;
 
-const FUNC_TO_STRING: &'static str = "to_string";
-const FUNC_TO_DEBUG: &'static str = "to_debug";
-
 type Unit = ();
 
 macro_rules! gen_functions {
@@ -35,14 +32,14 @@ macro_rules! gen_functions {
 
 macro_rules! reg_print_functions {
     ($mod_name:ident += $root:ident ; $($arg_type:ident),+) => { $(
-        set_exported_fn!($mod_name, FUNC_TO_STRING, $root::$arg_type::to_string_func);
+        set_exported_fn!($mod_name, FN_TO_STRING, $root::$arg_type::to_string_func);
         set_exported_fn!($mod_name, KEYWORD_PRINT, $root::$arg_type::to_string_func);
     )* }
 }
 
 macro_rules! reg_debug_functions {
     ($mod_name:ident += $root:ident ; $($arg_type:ident),+) => { $(
-        set_exported_fn!($mod_name, FUNC_TO_DEBUG, $root::$arg_type::to_string_func);
+        set_exported_fn!($mod_name, FN_TO_DEBUG, $root::$arg_type::to_string_func);
         set_exported_fn!($mod_name, KEYWORD_DEBUG, $root::$arg_type::to_string_func);
     )* }
 }
@@ -150,7 +147,7 @@ mod print_debug_functions {
             let len = arr.len();
 
             arr.iter_mut().enumerate().for_each(|(i, x)| {
-                result.push_str(&print_with_func(FUNC_TO_DEBUG, &ctx, x));
+                result.push_str(&print_with_func(FN_TO_DEBUG, &ctx, x));
                 if i < len - 1 {
                     result.push_str(", ");
                 }
@@ -175,7 +172,38 @@ mod print_debug_functions {
                 result.push_str(&format!(
                     "{:?}: {}{}",
                     k,
-                    &print_with_func(FUNC_TO_DEBUG, &ctx, v),
+                    &print_with_func(FN_TO_DEBUG, &ctx, v),
+                    if i < len - 1 { ", " } else { "" }
+                ));
+            });
+
+            result.push_str("}");
+            result.into()
+        }
+    }
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    pub mod class_functions {
+        use super::*;
+        use crate::ClassInstance;
+
+        #[rhai_fn(name = "print", name = "to_string", name = "to_debug", name = "debug")]
+        pub fn format_object(ctx: NativeCallContext, obj: &mut ClassInstance) -> ImmutableString {
+            let mut result = crate::stdlib::string::String::with_capacity(16);
+            result.push_str(obj.class_name());
+            result.push_str(" #{");
+
+            let mut fields: crate::stdlib::vec::Vec<_> = obj
+                .iter_fields()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect();
+            let len = fields.len();
+
+            fields.iter_mut().enumerate().for_each(|(i, (k, v))| {
+                result.push_str(&format!(
+                    "{:?}: {}{}",
+                    k,
+                    &print_with_func(FN_TO_DEBUG, &ctx, v),
                     if i < len - 1 { ", " } else { "" }
                 ));
             });
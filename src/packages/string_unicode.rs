@@ -0,0 +1,339 @@
+#![allow(non_snake_case)]
+
+use super::string_more::MoreStringPackage;
+use crate::plugin::*;
+use crate::stdlib::{
+    boxed::Box, cmp::Ordering, convert::TryFrom, format, string::String, string::ToString, vec::Vec,
+};
+use crate::{def_package, Dynamic, EvalAltResult, ImmutableString, Position, INT};
+
+def_package!(crate:UnicodeStringPackage:"Unicode string utilities, including case conversion, splitting and character classification.", lib, {
+    MoreStringPackage::init(lib);
+
+    combine_with_exported_module!(lib, "unicode", unicode_functions);
+    combine_with_exported_module!(lib, "char", char_functions);
+
+    // Register characters stream iterator
+    lib.set_iterator::<CharsStream>();
+});
+
+/// Iterator over the characters of a string, returned by `chars`.
+#[derive(Debug, Clone)]
+pub struct CharsStream(Vec<char>, usize);
+
+impl CharsStream {
+    /// Create a stream over `len` characters of a string, starting from character position `start`.
+    fn new(s: &str, start: INT, len: INT) -> Self {
+        let start = if start < 0 { 0 } else { start as usize };
+        let chars = if len <= 0 {
+            Vec::new()
+        } else {
+            s.chars().skip(start).take(len as usize).collect()
+        };
+        Self(chars, 0)
+    }
+}
+
+impl Iterator for CharsStream {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.0.get(self.1).cloned();
+        self.1 += 1;
+        ch
+    }
+}
+
+/// Check that a string of `len` bytes does not exceed the maximum string size.
+#[cfg(not(feature = "unchecked"))]
+fn check_string_size(ctx: &NativeCallContext, len: usize) -> Result<(), Box<EvalAltResult>> {
+    if ctx.engine().max_string_size() > 0 && len > ctx.engine().max_string_size() {
+        EvalAltResult::ErrorDataTooLarge("Length of string".to_string(), Position::NONE).into()
+    } else {
+        Ok(())
+    }
+}
+
+/// Lower-case characters of a string, for case-insensitive comparisons.
+fn lower_case_chars(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+}
+
+#[export_module]
+mod unicode_functions {
+    #[rhai_fn(return_raw)]
+    pub fn to_upper(_ctx: NativeCallContext, s: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        let result = s.to_uppercase();
+
+        #[cfg(not(feature = "unchecked"))]
+        check_string_size(&_ctx, result.len())?;
+
+        Ok(result.into())
+    }
+    #[rhai_fn(return_raw)]
+    pub fn to_lower(_ctx: NativeCallContext, s: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        let result = s.to_lowercase();
+
+        #[cfg(not(feature = "unchecked"))]
+        check_string_size(&_ctx, result.len())?;
+
+        Ok(result.into())
+    }
+
+    pub fn starts_with(s: &str, prefix: ImmutableString) -> bool {
+        s.starts_with(prefix.as_str())
+    }
+    #[rhai_fn(name = "starts_with")]
+    pub fn starts_with_char(s: &str, ch: char) -> bool {
+        s.starts_with(ch)
+    }
+    pub fn ends_with(s: &str, suffix: ImmutableString) -> bool {
+        s.ends_with(suffix.as_str())
+    }
+    #[rhai_fn(name = "ends_with")]
+    pub fn ends_with_char(s: &str, ch: char) -> bool {
+        s.ends_with(ch)
+    }
+
+    pub fn trim_start(s: &mut ImmutableString) {
+        let trimmed = s.trim_start();
+
+        if trimmed.len() < s.len() {
+            *s = trimmed.to_string().into();
+        }
+    }
+    pub fn trim_end(s: &mut ImmutableString) {
+        let trimmed = s.trim_end();
+
+        if trimmed.len() < s.len() {
+            *s = trimmed.to_string().into();
+        }
+    }
+
+    pub fn chars(s: &str) -> CharsStream {
+        CharsStream::new(s, 0, s.len() as INT)
+    }
+    #[rhai_fn(name = "chars")]
+    pub fn chars_starting_from(s: &str, start: INT) -> CharsStream {
+        CharsStream::new(s, start, s.len() as INT)
+    }
+    #[rhai_fn(name = "chars")]
+    pub fn chars_range(s: &str, start: INT, len: INT) -> CharsStream {
+        CharsStream::new(s, start, len)
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn repeat(
+        _ctx: NativeCallContext,
+        s: &str,
+        count: INT,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        if count <= 0 || s.is_empty() {
+            return Ok(ImmutableString::default().into());
+        }
+
+        // Check if string will be over max size limit
+        #[cfg(not(feature = "unchecked"))]
+        check_string_size(&_ctx, s.len().saturating_mul(count as usize))?;
+
+        Ok(s.repeat(count as usize).into())
+    }
+    pub fn reverse(s: &mut ImmutableString) {
+        *s = s.chars().rev().collect::<String>().into();
+    }
+
+    pub fn eq_ignore_case(s: &str, other: &str) -> bool {
+        lower_case_chars(s).eq(lower_case_chars(other))
+    }
+    pub fn compare_ignore_case(s: &str, other: &str) -> INT {
+        match lower_case_chars(s).cmp(lower_case_chars(other)) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        }
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    pub mod arrays {
+        use crate::Array;
+
+        #[rhai_fn(name = "split")]
+        pub fn split_at(s: &str, index: INT) -> Array {
+            let index = if index <= 0 {
+                0
+            } else {
+                s.chars()
+                    .take(index as usize)
+                    .map(char::len_utf8)
+                    .sum::<usize>()
+            };
+            let (prefix, suffix) = s.split_at(index);
+            vec![prefix.into(), suffix.into()]
+        }
+        #[rhai_fn(name = "split")]
+        pub fn splitn(s: &str, delimiter: ImmutableString, segments: INT) -> Array {
+            let segments = if segments < 1 { 1 } else { segments as usize };
+            s.splitn(segments, delimiter.as_str())
+                .map(Into::<Dynamic>::into)
+                .collect()
+        }
+        #[rhai_fn(name = "split")]
+        pub fn splitn_char(s: &str, delimiter: char, segments: INT) -> Array {
+            let segments = if segments < 1 { 1 } else { segments as usize };
+            s.splitn(segments, delimiter)
+                .map(Into::<Dynamic>::into)
+                .collect()
+        }
+        pub fn split_rev(s: &str, delimiter: ImmutableString) -> Array {
+            s.rsplit(delimiter.as_str())
+                .map(Into::<Dynamic>::into)
+                .collect()
+        }
+        #[rhai_fn(name = "split_rev")]
+        pub fn split_rev_char(s: &str, delimiter: char) -> Array {
+            s.rsplit(delimiter).map(Into::<Dynamic>::into).collect()
+        }
+        #[rhai_fn(name = "split_rev")]
+        pub fn rsplitn(s: &str, delimiter: ImmutableString, segments: INT) -> Array {
+            let segments = if segments < 1 { 1 } else { segments as usize };
+            s.rsplitn(segments, delimiter.as_str())
+                .map(Into::<Dynamic>::into)
+                .collect()
+        }
+        #[rhai_fn(name = "split_rev")]
+        pub fn rsplitn_char(s: &str, delimiter: char, segments: INT) -> Array {
+            let segments = if segments < 1 { 1 } else { segments as usize };
+            s.rsplitn(segments, delimiter)
+                .map(Into::<Dynamic>::into)
+                .collect()
+        }
+        pub fn lines(s: &str) -> Array {
+            s.lines().map(Into::<Dynamic>::into).collect()
+        }
+
+        pub fn to_chars(s: &str) -> Array {
+            s.chars().map(Into::<Dynamic>::into).collect()
+        }
+        #[rhai_fn(return_raw)]
+        pub fn from_chars(
+            ctx: NativeCallContext,
+            chars: Array,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            let mut result = String::with_capacity(chars.len());
+
+            for ch in chars {
+                match ch.as_char() {
+                    Ok(ch) => result.push(ch),
+                    Err(typ) => {
+                        return EvalAltResult::ErrorMismatchDataType(
+                            "char".to_string(),
+                            ctx.engine().map_type_name(typ).to_string(),
+                            Position::NONE,
+                        )
+                        .into()
+                    }
+                }
+            }
+
+            #[cfg(not(feature = "unchecked"))]
+            check_string_size(&ctx, result.len())?;
+
+            Ok(result.into())
+        }
+    }
+}
+
+#[export_module]
+mod char_functions {
+    #[rhai_fn(pure, const)]
+    pub fn is_alphabetic(ch: char) -> bool {
+        ch.is_alphabetic()
+    }
+    #[rhai_fn(pure, const)]
+    pub fn is_alphanumeric(ch: char) -> bool {
+        ch.is_alphanumeric()
+    }
+    #[rhai_fn(pure, const)]
+    pub fn is_digit(ch: char) -> bool {
+        ch.is_ascii_digit()
+    }
+    #[rhai_fn(name = "is_digit", return_raw, pure, const)]
+    pub fn is_digit_radix(ch: char, radix: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        if !(2..=36).contains(&radix) {
+            return EvalAltResult::ErrorArithmetic(
+                format!("Invalid radix: {}", radix),
+                Position::NONE,
+            )
+            .into();
+        }
+        Ok(ch.is_digit(radix as u32).into())
+    }
+    #[rhai_fn(pure, const)]
+    pub fn is_whitespace(ch: char) -> bool {
+        ch.is_whitespace()
+    }
+    #[rhai_fn(pure, const)]
+    pub fn is_uppercase(ch: char) -> bool {
+        ch.is_uppercase()
+    }
+    #[rhai_fn(pure, const)]
+    pub fn is_lowercase(ch: char) -> bool {
+        ch.is_lowercase()
+    }
+
+    /// Characters whose upper-case (or lower-case) form is more than one character are unchanged.
+    #[rhai_fn(name = "to_upper", pure, const)]
+    pub fn to_upper_char(ch: char) -> char {
+        let mut upper = ch.to_uppercase();
+        match (upper.next(), upper.next()) {
+            (Some(c), None) => c,
+            _ => ch,
+        }
+    }
+    #[rhai_fn(name = "to_lower", pure, const)]
+    pub fn to_lower_char(ch: char) -> char {
+        let mut lower = ch.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(c), None) => c,
+            _ => ch,
+        }
+    }
+
+    #[rhai_fn(name = "to_int", pure, const)]
+    pub fn to_int(ch: char) -> INT {
+        ch as INT
+    }
+    #[rhai_fn(name = "to_int", return_raw, pure, const)]
+    pub fn to_digit(ch: char, radix: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        if !(2..=36).contains(&radix) {
+            return EvalAltResult::ErrorArithmetic(
+                format!("Invalid radix: {}", radix),
+                Position::NONE,
+            )
+            .into();
+        }
+        ch.to_digit(radix as u32)
+            .map(|digit| (digit as INT).into())
+            .ok_or_else(|| {
+                EvalAltResult::ErrorArithmetic(
+                    format!("Invalid digit in base {}: '{}'", radix, ch),
+                    Position::NONE,
+                )
+                .into()
+            })
+    }
+    #[rhai_fn(return_raw, pure, const)]
+    pub fn to_char(code: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        match u32::try_from(code)
+            .ok()
+            .and_then(crate::stdlib::char::from_u32)
+        {
+            Some(ch) => Ok(ch.into()),
+            None => EvalAltResult::ErrorArithmetic(
+                format!("Invalid character code: {}", code),
+                Position::NONE,
+            )
+            .into(),
+        }
+    }
+}
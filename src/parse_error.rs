@@ -137,6 +137,11 @@ pub enum ParseErrorType {
     ///
     /// Never appears under the `no_object` feature.
     FnDuplicatedDefinition(String, usize),
+    /// Defining an overload of a function with a different access mode (i.e. `private` or not)
+    /// from its other overloads. Wrapped values are the function name and number of parameters.
+    ///
+    /// Never appears under the `no_function` feature.
+    FnMismatchedAccess(String, usize),
     /// Missing a function name after the `fn` keyword.
     ///
     /// Never appears under the `no_function` feature.
@@ -150,14 +155,37 @@ pub enum ParseErrorType {
     ///
     /// Never appears under the `no_function` feature.
     FnDuplicatedParam(String, String),
+    /// A function parameter is missing its type after `:`. Wrapped values are the function name
+    /// and parameter name.
+    ///
+    /// Never appears under the `no_function` feature.
+    FnMissingParamType(String, String),
     /// A function definition is missing the body. Wrapped value is the function name.
     ///
     /// Never appears under the `no_function` feature.
     FnMissingBody(String),
-    /// Export statement not at global level.
+    /// Defining a class in an inappropriate place (e.g. inside a block or a function).
+    ///
+    /// Never appears under the `no_function` or `no_object` features.
+    ClassWrongDefinition,
+    /// Defining a class with a name that conflicts with an existing class.
+    /// Wrapped value is the class name.
+    ///
+    /// Never appears under the `no_function` or `no_object` features.
+    ClassDuplicatedDefinition(String),
+    /// Missing a class name after the `class` or `new` keywords.
+    ///
+    /// Never appears under the `no_function` or `no_object` features.
+    ClassMissingName,
+    /// A class definition has duplicated fields. Wrapped values are the class name and field name.
+    ///
+    /// Never appears under the `no_function` or `no_object` features.
+    ClassDuplicatedField(String, String),
+    /// Export statement not at global level, or an invalid form of export statement.
+    /// Wrapped value is the error message (if any).
     ///
     /// Never appears under the `no_module` feature.
-    WrongExport,
+    WrongExport(String),
     /// Assignment to an a constant variable. Wrapped value is the constant variable name.
     AssignmentToConstant(String),
     /// Assignment to an inappropriate LHS (left-hand-side) expression.
@@ -200,12 +228,19 @@ impl ParseErrorType {
             Self::ExprExpected(_) => "Expecting an expression",
             Self::FnWrongDefinition => "Function definitions must be at global level and cannot be inside a block or another function",
             Self::FnDuplicatedDefinition(_, _) => "Function already exists",
+            Self::FnMismatchedAccess(_, _) => "Function overloads must be either all private or all public",
             Self::FnMissingName => "Expecting function name in function declaration",
             Self::FnMissingParams(_) => "Expecting parameters in function declaration",
             Self::FnDuplicatedParam(_,_) => "Duplicated parameters in function declaration",
+            Self::FnMissingParamType(_,_) => "Expecting parameter type in function declaration",
             Self::FnMissingBody(_) => "Expecting body statement block for function declaration",
+            Self::ClassWrongDefinition => "Class definitions must be at global level and cannot be inside a block or a function",
+            Self::ClassDuplicatedDefinition(_) => "Class already exists",
+            Self::ClassMissingName => "Expecting class name",
+            Self::ClassDuplicatedField(_,_) => "Duplicated fields in class declaration",
             Self::WrongDocComment => "Doc-comment must be followed immediately by a function definition",
-            Self::WrongExport => "Export statement can only appear at global level",
+            Self::WrongExport(s) if s.is_empty() => "Export statement can only appear at global level",
+            Self::WrongExport(_) => "Invalid export statement",
             Self::AssignmentToConstant(_) => "Cannot assign to a constant value",
             Self::AssignmentToInvalidLHS(_) => "Expression cannot be assigned to",
             Self::ExprTooDeep => "Expression exceeds maximum complexity",
@@ -235,6 +270,13 @@ impl fmt::Display for ParseErrorType {
                     _ => write!(f, "{} parameters already exists", n),
                 }
             }
+            Self::FnMismatchedAccess(s, n) => write!(
+                f,
+                "Overloads of function '{}' with {} parameter{} must be either all private or all public",
+                s,
+                n,
+                if *n == 1 { "" } else { "s" }
+            ),
             Self::DuplicatedProperty(s) => {
                 write!(f, "Duplicated property '{}' for object map literal", s)
             }
@@ -255,6 +297,20 @@ impl fmt::Display for ParseErrorType {
                 write!(f, "Duplicated parameter '{}' for function '{}'", arg, s)
             }
 
+            Self::FnMissingParamType(s, arg) => {
+                write!(
+                    f,
+                    "Expecting type of parameter '{}' for function '{}'",
+                    arg, s
+                )
+            }
+
+            Self::ClassDuplicatedDefinition(s) => write!(f, "Class '{}' already exists", s),
+
+            Self::ClassDuplicatedField(s, field) => {
+                write!(f, "Duplicated field '{}' for class '{}'", field, s)
+            }
+
             Self::MissingToken(token, s) => write!(f, "Expecting '{}' {}", token, s),
 
             Self::AssignmentToConstant(s) if s.is_empty() => f.write_str(self.desc()),
@@ -263,6 +319,9 @@ impl fmt::Display for ParseErrorType {
             Self::AssignmentToInvalidLHS(s) if s.is_empty() => f.write_str(self.desc()),
             Self::AssignmentToInvalidLHS(s) => f.write_str(s),
 
+            Self::WrongExport(s) if s.is_empty() => f.write_str(self.desc()),
+            Self::WrongExport(s) => f.write_str(s),
+
             Self::LiteralTooLarge(typ, max) => {
                 write!(f, "{} exceeds the maximum limit ({})", typ, max)
             }
@@ -1,7 +1,12 @@
 //! Main module defining the lexer and parser.
 
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+use crate::ast::ClassDef;
 use crate::ast::{BinaryExpr, CustomExpr, Expr, FnCallExpr, Ident, ReturnType, ScriptFnDef, Stmt};
 use crate::dynamic::{AccessMode, Union};
+#[cfg(not(feature = "no_module"))]
+use crate::engine::KEYWORD_GLOBAL;
 use crate::engine::KEYWORD_THIS;
 use crate::module::NamespaceRef;
 use crate::optimize::optimize_into_ast;
@@ -32,6 +37,10 @@ use crate::FLOAT;
 #[cfg(not(feature = "no_function"))]
 use crate::FnAccess;
 
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+use crate::Shared;
+
 type PERR = ParseErrorType;
 
 type FunctionsLib = HashMap<NonZeroU64, ScriptFnDef, StraightHasherBuilder>;
@@ -1018,6 +1027,11 @@ fn parse_primary(
             expr
         }
 
+        // new ClassName(...)
+        #[cfg(not(feature = "no_function"))]
+        #[cfg(not(feature = "no_object"))]
+        Token::New => parse_new(input, state, lib, settings.level_up())?,
+
         // Array literal
         #[cfg(not(feature = "no_index"))]
         Token::LeftBracket => parse_array_literal(input, state, lib, settings.level_up())?,
@@ -1306,6 +1320,7 @@ fn parse_unary(
                     Ok(Expr::FnCall(
                         Box::new(FnCallExpr {
                             name: op.into(),
+                            hash_script: calc_script_fn_hash(empty(), op, 1),
                             args,
                             ..Default::default()
                         }),
@@ -1332,6 +1347,7 @@ fn parse_unary(
                     Ok(Expr::FnCall(
                         Box::new(FnCallExpr {
                             name: op.into(),
+                            hash_script: calc_script_fn_hash(empty(), op, 1),
                             args,
                             ..Default::default()
                         }),
@@ -1352,6 +1368,7 @@ fn parse_unary(
             Ok(Expr::FnCall(
                 Box::new(FnCallExpr {
                     name: op.into(),
+                    hash_script: calc_script_fn_hash(empty(), op, 1),
                     args,
                     def_value: Some(false.into()), // NOT operator, when operating on invalid operand, defaults to false
                     ..Default::default()
@@ -1803,7 +1820,9 @@ fn parse_binary_op(
         let cmp_def = Some(false.into());
         let op = op_token.syntax();
 
+        // Script-defined functions can overload operators
         let op_base = FnCallExpr {
+            hash_script: calc_script_fn_hash(empty(), &op, 2),
             name: op,
             capture: false,
             ..Default::default()
@@ -1886,21 +1905,7 @@ fn parse_binary_op(
                     .map(Option::is_some)
                     .unwrap_or(false) =>
             {
-                let hash_script = if is_valid_identifier(s.chars()) {
-                    // Accept non-native functions for custom operators
-                    calc_script_fn_hash(empty(), &s, 2)
-                } else {
-                    None
-                };
-
-                Expr::FnCall(
-                    Box::new(FnCallExpr {
-                        hash_script,
-                        args,
-                        ..op_base
-                    }),
-                    pos,
-                )
+                Expr::FnCall(Box::new(FnCallExpr { args, ..op_base }), pos)
             }
 
             op_token => return Err(PERR::UnknownOperator(op_token.into()).into_err(pos)),
@@ -2315,6 +2320,16 @@ fn parse_import(
     // import ...
     settings.pos = eat_token(input, Token::Import);
 
+    // import { ... } from expr
+    if let (Token::LeftBrace, _) = input.peek().unwrap() {
+        let (expr, members) = parse_import_members(input, state, lib, settings.level_up())?;
+        return Ok(Stmt::ImportMembers(
+            Box::new((expr, members)),
+            false,
+            settings.pos,
+        ));
+    }
+
     // import expr ...
     let expr = parse_expr(input, state, lib, settings.level_up())?;
 
@@ -2325,6 +2340,9 @@ fn parse_import(
 
     // import expr as name ...
     let (name, name_pos) = match input.next().unwrap() {
+        (Token::Identifier(s), pos) if s == KEYWORD_GLOBAL => {
+            return Err(PERR::Reserved(s).into_err(pos));
+        }
         (Token::Identifier(s), pos) => (s, pos),
         (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
             return Err(PERR::Reserved(s).into_err(pos));
@@ -2346,6 +2364,84 @@ fn parse_import(
     ))
 }
 
+/// Parse the list of members of an import statement, i.e. `{ var as var, ... } from expr`.
+#[cfg(not(feature = "no_module"))]
+fn parse_import_members(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    settings: ParseSettings,
+) -> Result<(Expr, Vec<(Ident, Option<Ident>)>), ParseError> {
+    eat_token(input, Token::LeftBrace);
+
+    let mut members = Vec::with_capacity(4);
+
+    loop {
+        let (id, id_pos) = match input.next().unwrap() {
+            (Token::Identifier(s), pos) => (s, pos),
+            (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
+                return Err(PERR::Reserved(s).into_err(pos));
+            }
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+        };
+
+        let rename = if match_token(input, Token::As).0 {
+            match input.next().unwrap() {
+                (Token::Identifier(s), pos) => Some(Ident {
+                    name: state.get_interned_string(s),
+                    pos,
+                }),
+                (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
+                    return Err(PERR::Reserved(s).into_err(pos));
+                }
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+            }
+        } else {
+            None
+        };
+
+        members.push((
+            Ident {
+                name: state.get_interned_string(id),
+                pos: id_pos,
+            },
+            rename,
+        ));
+
+        match input.next().unwrap() {
+            (Token::Comma, _) => (),
+            (Token::RightBrace, _) => break,
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::RightBrace.into(),
+                    "to end the list of imported members".into(),
+                )
+                .into_err(pos))
+            }
+        }
+    }
+
+    // { ... } from expr
+    match input.next().unwrap() {
+        (Token::Identifier(s), _) if s == "from" => (),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(PERR::MissingToken(
+                "from".into(),
+                "after the list of imported members".into(),
+            )
+            .into_err(pos))
+        }
+    }
+
+    let expr = parse_expr(input, state, lib, settings.level_up())?;
+
+    Ok((expr, members))
+}
+
 /// Parse an export statement.
 #[cfg(not(feature = "no_module"))]
 fn parse_export(
@@ -2372,6 +2468,30 @@ fn parse_export(
             stmt.set_position(pos);
             return Ok(stmt);
         }
+        // export import ...
+        (Token::Import, pos) => {
+            let pos = *pos;
+            return match parse_import(input, state, lib, settings.level_up())? {
+                // export import expr as name
+                stmt @ Stmt::Import(_, Some(_), _) => Ok(stmt),
+                // export import { ... } from expr
+                Stmt::ImportMembers(x, _, _) => Ok(Stmt::ImportMembers(x, true, settings.pos)),
+                _ => Err(PERR::WrongExport(
+                    "A re-exported module must have an alias, i.e. 'export import ... as name'"
+                        .to_string(),
+                )
+                .into_err(pos)),
+            };
+        }
+        // export { ... } from expr
+        (Token::LeftBrace, _) => {
+            let (expr, members) = parse_import_members(input, state, lib, settings.level_up())?;
+            return Ok(Stmt::ImportMembers(
+                Box::new((expr, members)),
+                true,
+                settings.pos,
+            ));
+        }
         _ => (),
     }
 
@@ -2593,6 +2713,17 @@ fn parse_stmt(
         #[cfg(not(feature = "no_function"))]
         Token::Fn if !settings.is_global => Err(PERR::FnWrongDefinition.into_err(settings.pos)),
 
+        // class ...
+        #[cfg(not(feature = "no_function"))]
+        #[cfg(not(feature = "no_object"))]
+        Token::Class if !settings.is_global => {
+            Err(PERR::ClassWrongDefinition.into_err(settings.pos))
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        #[cfg(not(feature = "no_object"))]
+        Token::Class => parse_class(input, state, lib, settings.level_up()),
+
         #[cfg(not(feature = "no_function"))]
         Token::Fn | Token::Private => {
             let access = if matches!(token, Token::Private) {
@@ -2625,19 +2756,7 @@ fn parse_stmt(
                     };
 
                     let func = parse_fn(input, &mut new_state, lib, access, settings, _comments)?;
-
-                    // Qualifiers (none) + function name + number of arguments.
-                    let hash = calc_script_fn_hash(empty(), &func.name, func.params.len()).unwrap();
-
-                    if lib.contains_key(&hash) {
-                        return Err(PERR::FnDuplicatedDefinition(
-                            func.name.into_owned(),
-                            func.params.len(),
-                        )
-                        .into_err(pos));
-                    }
-
-                    lib.insert(hash, func);
+                    insert_fn_def(lib, func, pos)?;
 
                     Ok(Stmt::Noop(pos))
                 }
@@ -2709,7 +2828,9 @@ fn parse_stmt(
         Token::Import => parse_import(input, state, lib, settings.level_up()),
 
         #[cfg(not(feature = "no_module"))]
-        Token::Export if !settings.is_global => Err(PERR::WrongExport.into_err(settings.pos)),
+        Token::Export if !settings.is_global => {
+            Err(PERR::WrongExport("".to_string()).into_err(settings.pos))
+        }
 
         #[cfg(not(feature = "no_module"))]
         Token::Export => parse_export(input, state, lib, settings.level_up()),
@@ -2779,6 +2900,40 @@ fn parse_try_catch(
     ))
 }
 
+/// Parse the type annotation of a function parameter, e.g. `int`, `()` or `my_module::MyType`.
+#[cfg(not(feature = "no_function"))]
+fn parse_param_type(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    fn_name: &str,
+    param: &str,
+) -> Result<ImmutableString, ParseError> {
+    let mut typ = match input.next().unwrap() {
+        (Token::Identifier(s), _) => s,
+        (Token::LeftParen, _) if match_token(input, Token::RightParen).0 => "()".into(),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(PERR::FnMissingParamType(fn_name.into(), param.into()).into_err(pos))
+        }
+    };
+
+    // Namespace-qualified type name
+    while match_token(input, Token::DoubleColon).0 {
+        match input.next().unwrap() {
+            (Token::Identifier(s), _) => {
+                typ.push_str(Token::DoubleColon.syntax().as_ref());
+                typ.push_str(&s);
+            }
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::FnMissingParamType(fn_name.into(), param.into()).into_err(pos))
+            }
+        }
+    }
+
+    Ok(state.get_interned_string(typ))
+}
+
 /// Parse a function definition.
 #[cfg(not(feature = "no_function"))]
 fn parse_fn(
@@ -2786,7 +2941,7 @@ fn parse_fn(
     state: &mut ParseState,
     lib: &mut FunctionsLib,
     access: FnAccess,
-    mut settings: ParseSettings,
+    settings: ParseSettings,
     comments: Vec<String>,
 ) -> Result<ScriptFnDef, ParseError> {
     #[cfg(not(feature = "unchecked"))]
@@ -2794,19 +2949,47 @@ fn parse_fn(
 
     let (token, pos) = input.next().unwrap();
 
-    let name = token
-        .into_function_name_for_override()
-        .map_err(|t| match t {
-            Token::Reserved(s) => PERR::Reserved(s).into_err(pos),
-            _ => PERR::FnMissingName.into_err(pos),
-        })?;
+    let name = match token {
+        // Custom operators
+        Token::Custom(s)
+            if state
+                .engine
+                .custom_keywords
+                .get(&s)
+                .map(Option::is_some)
+                .unwrap_or(false) =>
+        {
+            s
+        }
+        token => token
+            .into_function_name_for_override()
+            .map_err(|t| match t {
+                Token::Reserved(s) => PERR::Reserved(s).into_err(pos),
+                _ => PERR::FnMissingName.into_err(pos),
+            })?,
+    };
+
+    parse_fn_def(input, state, lib, name, access, settings, comments)
+}
 
+/// Parse the parameters and body of a function definition, after its name.
+#[cfg(not(feature = "no_function"))]
+fn parse_fn_def(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    name: String,
+    access: FnAccess,
+    mut settings: ParseSettings,
+    comments: Vec<String>,
+) -> Result<ScriptFnDef, ParseError> {
     match input.peek().unwrap() {
         (Token::LeftParen, _) => eat_token(input, Token::LeftParen),
         (_, pos) => return Err(PERR::FnMissingParams(name).into_err(*pos)),
     };
 
     let mut params: StaticVec<_> = Default::default();
+    let mut param_types: StaticVec<_> = Default::default();
 
     if !match_token(input, Token::RightParen).0 {
         let sep_err = format!("to separate the parameters of function '{}'", name);
@@ -2820,6 +3003,14 @@ fn parse_fn(
                     }
                     let s = state.get_interned_string(s);
                     state.stack.push((s.clone(), AccessMode::ReadWrite));
+
+                    // Type annotation
+                    param_types.push(if match_token(input, Token::Colon).0 {
+                        Some(parse_param_type(input, state, &name, &s)?)
+                    } else {
+                        None
+                    });
+
                     params.push((s, pos))
                 }
                 (Token::LexError(err), pos) => return Err(err.into_err(pos)),
@@ -2854,6 +3045,10 @@ fn parse_fn(
 
     let params: StaticVec<_> = params.into_iter().map(|(p, _)| p).collect();
 
+    if param_types.iter().all(Option::is_none) {
+        param_types.clear();
+    }
+
     #[cfg(not(feature = "no_closure"))]
     let externals = state
         .externals
@@ -2867,16 +3062,356 @@ fn parse_fn(
         name: name.into(),
         access,
         params,
+        param_types,
+        overloads: Default::default(),
         #[cfg(not(feature = "no_closure"))]
         externals,
         body,
         lib: None,
         #[cfg(not(feature = "no_module"))]
         mods: Default::default(),
+        #[cfg(not(feature = "no_module"))]
+        vars: None,
         comments,
+        #[cfg(not(feature = "no_object"))]
+        class: None,
+        #[cfg(feature = "bytecode")]
+        #[cfg(not(feature = "no_function"))]
+        bytecode: Default::default(),
+        #[cfg(not(feature = "no_module"))]
+        needs_globals: Default::default(),
     })
 }
 
+/// Add a function definition to a functions library.
+///
+/// A function with the same name and number of parameters as an existing one is added as another
+/// overload, unless their parameter types are also the same or their access modes differ.
+#[cfg(not(feature = "no_function"))]
+fn insert_fn_def(
+    lib: &mut FunctionsLib,
+    func: ScriptFnDef,
+    pos: Position,
+) -> Result<(), ParseError> {
+    // Qualifiers (none) + function name + number of arguments.
+    let hash = calc_script_fn_hash(empty(), &func.name, func.params.len()).unwrap();
+
+    match lib.get_mut(&hash) {
+        // Overload with different access mode
+        Some(existing)
+            if existing.access != func.access
+                && existing
+                    .iter_overloads()
+                    .all(|f| !f.has_same_param_types(&func)) =>
+        {
+            return Err(
+                PERR::FnMismatchedAccess(func.name.into_owned(), func.params.len()).into_err(pos),
+            )
+        }
+        // Overload with different parameter types
+        Some(existing)
+            if existing
+                .iter_overloads()
+                .all(|f| !f.has_same_param_types(&func)) =>
+        {
+            existing.overloads.push(func)
+        }
+        Some(_) => {
+            return Err(
+                PERR::FnDuplicatedDefinition(func.name.into_owned(), func.params.len())
+                    .into_err(pos),
+            )
+        }
+        None => {
+            lib.insert(hash, func);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a class definition.
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+fn parse_class(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    settings: ParseSettings,
+) -> Result<Stmt, ParseError> {
+    #[cfg(not(feature = "unchecked"))]
+    settings.ensure_level_within_max_limit(state.max_expr_depth)?;
+
+    // class ...
+    let class_pos = eat_token(input, Token::Class);
+
+    // class name ...
+    let (name, name_pos) = match input.next().unwrap() {
+        (Token::Identifier(s), pos) => (state.get_interned_string(s), pos),
+        (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
+            return Err(PERR::Reserved(s).into_err(pos));
+        }
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => return Err(PERR::ClassMissingName.into_err(pos)),
+    };
+
+    if lib
+        .values()
+        .any(|f| f.class.as_ref().map_or(false, |c| c.name == name))
+    {
+        return Err(PERR::ClassDuplicatedDefinition(name.into_owned()).into_err(name_pos));
+    }
+
+    // class name { ...
+    match input.next().unwrap() {
+        (Token::LeftBrace, _) => (),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(PERR::MissingToken(
+                Token::LeftBrace.into(),
+                format!("to start the body of class '{}'", name),
+            )
+            .into_err(pos))
+        }
+    }
+
+    let mut fields: StaticVec<(ImmutableString, Expr)> = Default::default();
+    let mut methods: FunctionsLib = Default::default();
+    let mut constructors: Vec<(ScriptFnDef, Position)> = Default::default();
+
+    loop {
+        let mut comments: Vec<String> = Default::default();
+
+        // Handle doc-comments of methods
+        while let (Token::Comment(_), _) = input.peek().unwrap() {
+            match input.next().unwrap() {
+                (Token::Comment(comment), pos) => {
+                    comments.push(comment);
+
+                    match input.peek().unwrap() {
+                        (Token::Fn, _) | (Token::Comment(_), _) => (),
+                        _ => return Err(PERR::WrongDocComment.into_err(pos)),
+                    }
+                }
+                t => unreachable!("expecting Token::Comment, but gets {:?}", t),
+            }
+        }
+
+        let mut new_state = ParseState::new(
+            state.engine,
+            #[cfg(not(feature = "unchecked"))]
+            state.max_function_expr_depth,
+            #[cfg(not(feature = "unchecked"))]
+            state.max_function_expr_depth,
+        );
+
+        match input.next().unwrap() {
+            (Token::RightBrace, _) => break,
+
+            // let field = expr;
+            (Token::Let, _) => {
+                let (field, pos) = match input.next().unwrap() {
+                    (Token::Identifier(s), pos) => (state.get_interned_string(s), pos),
+                    (Token::Reserved(s), pos) if is_valid_identifier(s.chars()) => {
+                        return Err(PERR::Reserved(s).into_err(pos));
+                    }
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+                };
+
+                if fields.iter().any(|(f, _)| *f == field) {
+                    return Err(
+                        PERR::ClassDuplicatedField(name.into_owned(), field.into_owned())
+                            .into_err(pos),
+                    );
+                }
+
+                // Default values are evaluated without access to any variables
+                let expr = if match_token(input, Token::Equals).0 {
+                    parse_expr(input, &mut new_state, lib, settings.level_up())?
+                } else {
+                    Expr::Unit(pos)
+                };
+
+                match input.next().unwrap() {
+                    (Token::SemiColon, _) => (),
+                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                    (_, pos) => {
+                        return Err(PERR::MissingToken(
+                            Token::SemiColon.into(),
+                            format!("to terminate the declaration of field '{}'", field),
+                        )
+                        .into_err(pos))
+                    }
+                }
+
+                fields.push((field, expr));
+            }
+
+            // fn new(...) { ... } | fn method(...) { ... }
+            (Token::Fn, pos) => {
+                let settings = ParseSettings {
+                    allow_if_expr: true,
+                    allow_switch_expr: true,
+                    allow_stmt_expr: true,
+                    allow_anonymous_fn: true,
+                    is_global: false,
+                    is_function_scope: true,
+                    is_breakable: false,
+                    level: 0,
+                    pos,
+                };
+
+                if match_token(input, Token::New).0 {
+                    // Constructors are named after the class
+                    let func = parse_fn_def(
+                        input,
+                        &mut new_state,
+                        lib,
+                        name.to_string(),
+                        FnAccess::Public,
+                        settings,
+                        comments,
+                    )?;
+                    constructors.push((func, pos));
+                } else {
+                    let func = parse_fn(
+                        input,
+                        &mut new_state,
+                        lib,
+                        FnAccess::Public,
+                        settings,
+                        comments,
+                    )?;
+                    insert_fn_def(&mut methods, func, pos)?;
+                }
+            }
+
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => {
+                return Err(PERR::MissingToken(
+                    Token::RightBrace.into(),
+                    format!("to end the body of class '{}'", name),
+                )
+                .into_err(pos))
+            }
+        }
+    }
+
+    // Default constructor without parameters
+    if constructors.is_empty() {
+        let func = ScriptFnDef {
+            name: name.clone(),
+            access: FnAccess::Public,
+            params: Default::default(),
+            param_types: Default::default(),
+            overloads: Default::default(),
+            #[cfg(not(feature = "no_closure"))]
+            externals: Default::default(),
+            body: Stmt::Noop(class_pos),
+            lib: None,
+            #[cfg(not(feature = "no_module"))]
+            mods: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            vars: None,
+            comments: Default::default(),
+            class: None,
+            #[cfg(feature = "bytecode")]
+            bytecode: Default::default(),
+            #[cfg(not(feature = "no_module"))]
+            needs_globals: Default::default(),
+        };
+        constructors.push((func, class_pos));
+    }
+
+    let class: Shared<ClassDef> = ClassDef {
+        name,
+        fields,
+        methods,
+    }
+    .into();
+
+    for (mut func, pos) in constructors {
+        func.class = Some(class.clone());
+        func.overloads
+            .iter_mut()
+            .for_each(|f| f.class = Some(class.clone()));
+        insert_fn_def(lib, func, pos)?;
+    }
+
+    Ok(Stmt::Noop(class_pos))
+}
+
+/// Parse a call to the constructor of a class, e.g. `new Point(1, 2)` or `new geo::Point(1, 2)`.
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+fn parse_new(
+    input: &mut TokenStream,
+    state: &mut ParseState,
+    lib: &mut FunctionsLib,
+    mut settings: ParseSettings,
+) -> Result<Expr, ParseError> {
+    #[cfg(not(feature = "unchecked"))]
+    settings.ensure_level_within_max_limit(state.max_expr_depth)?;
+
+    // new ...
+    eat_token(input, Token::New);
+
+    let mut namespace: Option<NamespaceRef> = None;
+
+    // new name ...
+    let mut name = match input.next().unwrap() {
+        (Token::Identifier(s), pos) => Ident {
+            name: state.get_interned_string(s),
+            pos,
+        },
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => return Err(PERR::ClassMissingName.into_err(pos)),
+    };
+
+    // new module::name ...
+    #[cfg(not(feature = "no_module"))]
+    while match_token(input, Token::DoubleColon).0 {
+        let next = match input.next().unwrap() {
+            (Token::Identifier(s), pos) => Ident {
+                name: state.get_interned_string(s),
+                pos,
+            },
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (_, pos) => return Err(PERR::ClassMissingName.into_err(pos)),
+        };
+
+        namespace
+            .get_or_insert_with(Default::default)
+            .push(crate::stdlib::mem::replace(&mut name, next));
+    }
+
+    // new name(...)
+    match input.next().unwrap() {
+        (Token::LeftParen, _) => (),
+        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+        (_, pos) => {
+            return Err(PERR::MissingToken(
+                Token::LeftParen.into(),
+                format!(
+                    "to start arguments list of the constructor of '{}'",
+                    name.name
+                ),
+            )
+            .into_err(pos))
+        }
+    }
+
+    // Once the class name consumed we must enable next variables capturing
+    #[cfg(not(feature = "no_closure"))]
+    {
+        state.allow_capture = true;
+    }
+
+    settings.pos = name.pos;
+    parse_fn_call(input, state, lib, name.name, false, namespace, settings)
+}
+
 /// Creates a curried expression from a list of external variables
 #[cfg(not(feature = "no_function"))]
 #[cfg(not(feature = "no_closure"))]
@@ -3014,13 +3549,24 @@ fn parse_anon_fn(
         name: fn_name.clone(),
         access: FnAccess::Public,
         params,
+        param_types: Default::default(),
+        overloads: Default::default(),
         #[cfg(not(feature = "no_closure"))]
         externals: Default::default(),
         body,
         lib: None,
         #[cfg(not(feature = "no_module"))]
         mods: Default::default(),
+        #[cfg(not(feature = "no_module"))]
+        vars: None,
         comments: Default::default(),
+        #[cfg(not(feature = "no_object"))]
+        class: None,
+        #[cfg(feature = "bytecode")]
+        #[cfg(not(feature = "no_function"))]
+        bytecode: Default::default(),
+        #[cfg(not(feature = "no_module"))]
+        needs_globals: Default::default(),
     };
 
     let expr = Expr::FnPointer(fn_name, settings.pos);
@@ -7,6 +7,9 @@ pub use crate::{
     NativeCallContext, RegisterFn, RegisterResultFn,
 };
 
+#[doc(hidden)]
+pub use crate::build_type::derive as custom_type;
+
 #[cfg(not(features = "no_module"))]
 pub use rhai_codegen::*;
 #[cfg(features = "no_module")]
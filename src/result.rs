@@ -3,8 +3,9 @@
 use crate::stdlib::{
     boxed::Box,
     error::Error,
-    fmt,
+    fmt, format,
     string::{String, ToString},
+    vec::Vec,
 };
 use crate::{Dynamic, ImmutableString, ParseErrorType, Position, INT};
 
@@ -36,11 +37,16 @@ pub enum EvalAltResult {
     /// An error has occurred inside a called function.
     /// Wrapped values are the function name, function source, and the interior error.
     ErrorInFunctionCall(String, String, Box<EvalAltResult>, Position),
-    /// Usage of an unknown [module][crate::Module]. Wrapped value is the [module][crate::Module] name.
-    ErrorModuleNotFound(String, Position),
+    /// Usage of an unknown [module][crate::Module].
+    /// Wrapped values are the [module][crate::Module] name and the paths attempted (if any).
+    ErrorModuleNotFound(String, Vec<String>, Position),
     /// An error has occurred while loading a [module][crate::Module].
     /// Wrapped value are the [module][crate::Module] name and the interior error.
     ErrorInModule(String, Box<EvalAltResult>, Position),
+    /// A [module][crate::Module] imports itself, directly or indirectly.
+    /// Wrapped value is the cycle of [module][crate::Module] paths imported, each with the
+    /// position of its `import` statement, ending with the `import` that closes the cycle.
+    ErrorCircularImport(Vec<(String, Position)>, Position),
     /// Access to `this` that is not bound.
     ErrorUnboundThis(Position),
     /// Data is not of the required type.
@@ -100,6 +106,7 @@ impl EvalAltResult {
             Self::ErrorParsing(p, _) => p.desc(),
             Self::ErrorInFunctionCall(_,_, _, _) => "Error in called function",
             Self::ErrorInModule(_, _, _) => "Error in module",
+            Self::ErrorCircularImport(_, _) => "Circular module import",
             Self::ErrorFunctionNotFound(_, _) => "Function not found",
             Self::ErrorUnboundThis(_) => "'this' is not bound",
             Self::ErrorMismatchDataType(_, _, _) => "Data type is incorrect",
@@ -118,7 +125,7 @@ impl EvalAltResult {
             Self::ErrorStringBounds(_, _, _) => "String index out of bounds",
             Self::ErrorFor(_) => "For loop expects an array, object map, or range",
             Self::ErrorVariableNotFound(_, _) => "Variable not found",
-            Self::ErrorModuleNotFound(_, _) => "Module not found",
+            Self::ErrorModuleNotFound(_, _, _) => "Module not found",
             Self::ErrorDataRace(_, _) => "Data race detected when accessing variable",
             Self::ErrorAssignmentToConstant(_, _) => "Cannot assign to a constant",
             Self::ErrorMismatchOutputType(_, _, _) => "Output type is incorrect",
@@ -170,11 +177,39 @@ impl fmt::Display for EvalAltResult {
             }
             Self::ErrorInModule(s, err, _) => write!(f, "Error in module '{}': {}", s, err)?,
 
+            Self::ErrorCircularImport(cycle, _) => write!(
+                f,
+                "{}: {}",
+                desc,
+                cycle
+                    .iter()
+                    .map(|(path, pos)| if pos.is_none() {
+                        format!("'{}'", path)
+                    } else {
+                        format!("'{}' ({})", path, pos)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            )?,
+
             Self::ErrorFunctionNotFound(s, _)
             | Self::ErrorVariableNotFound(s, _)
             | Self::ErrorDataRace(s, _) => write!(f, "{}: {}", desc, s)?,
 
-            Self::ErrorModuleNotFound(s, _) => write!(f, "{}: '{}'", desc, s)?,
+            Self::ErrorModuleNotFound(s, paths, _) if paths.is_empty() => {
+                write!(f, "{}: '{}'", desc, s)?
+            }
+            Self::ErrorModuleNotFound(s, paths, _) => write!(
+                f,
+                "{}: '{}' (attempted paths: {})",
+                desc,
+                s,
+                paths
+                    .iter()
+                    .map(|p| format!("'{}'", p))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?,
 
             Self::ErrorDotExpr(s, _) if !s.is_empty() => write!(f, "{}", s)?,
 
@@ -285,6 +320,7 @@ impl EvalAltResult {
             Self::ErrorFunctionNotFound(_, _)
             | Self::ErrorInFunctionCall(_, _, _, _)
             | Self::ErrorInModule(_, _, _)
+            | Self::ErrorCircularImport(_, _)
             | Self::ErrorUnboundThis(_)
             | Self::ErrorMismatchDataType(_, _, _)
             | Self::ErrorArrayBounds(_, _, _)
@@ -292,7 +328,7 @@ impl EvalAltResult {
             | Self::ErrorIndexingType(_, _)
             | Self::ErrorFor(_)
             | Self::ErrorVariableNotFound(_, _)
-            | Self::ErrorModuleNotFound(_, _)
+            | Self::ErrorModuleNotFound(_, _, _)
             | Self::ErrorDataRace(_, _)
             | Self::ErrorAssignmentToConstant(_, _)
             | Self::ErrorMismatchOutputType(_, _, _)
@@ -343,6 +379,7 @@ impl EvalAltResult {
             | Self::ErrorFunctionNotFound(_, pos)
             | Self::ErrorInFunctionCall(_, _, _, pos)
             | Self::ErrorInModule(_, _, pos)
+            | Self::ErrorCircularImport(_, pos)
             | Self::ErrorUnboundThis(pos)
             | Self::ErrorMismatchDataType(_, _, pos)
             | Self::ErrorArrayBounds(_, _, pos)
@@ -350,7 +387,7 @@ impl EvalAltResult {
             | Self::ErrorIndexingType(_, pos)
             | Self::ErrorFor(pos)
             | Self::ErrorVariableNotFound(_, pos)
-            | Self::ErrorModuleNotFound(_, pos)
+            | Self::ErrorModuleNotFound(_, _, pos)
             | Self::ErrorDataRace(_, pos)
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, _, pos)
@@ -381,6 +418,7 @@ impl EvalAltResult {
             | Self::ErrorFunctionNotFound(_, pos)
             | Self::ErrorInFunctionCall(_, _, _, pos)
             | Self::ErrorInModule(_, _, pos)
+            | Self::ErrorCircularImport(_, pos)
             | Self::ErrorUnboundThis(pos)
             | Self::ErrorMismatchDataType(_, _, pos)
             | Self::ErrorArrayBounds(_, _, pos)
@@ -388,7 +426,7 @@ impl EvalAltResult {
             | Self::ErrorIndexingType(_, pos)
             | Self::ErrorFor(pos)
             | Self::ErrorVariableNotFound(_, pos)
-            | Self::ErrorModuleNotFound(_, pos)
+            | Self::ErrorModuleNotFound(_, _, pos)
             | Self::ErrorDataRace(_, pos)
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, _, pos)
@@ -171,9 +171,10 @@ impl From<crate::ScriptFnMetadata<'_>> for FnMetadata {
             params: info
                 .params
                 .iter()
-                .map(|s| FnParam {
+                .zip(info.param_types.iter())
+                .map(|(s, t)| FnParam {
                     name: s.to_string(),
-                    typ: Some("Dynamic".to_string()),
+                    typ: Some(t.unwrap_or("Dynamic").to_string()),
                 })
                 .collect(),
             return_type: Some("Dynamic".to_string()),
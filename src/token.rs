@@ -327,6 +327,18 @@ pub enum Token {
     /// Reserved under the `no_module` feature.
     #[cfg(not(feature = "no_module"))]
     As,
+    /// `class`, only when followed by the name of the class.
+    ///
+    /// Not available under the `no_function` and `no_object` features.
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    Class,
+    /// `new`
+    ///
+    /// Reserved under the `no_function` and `no_object` features.
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    New,
     /// A lexer error.
     LexError(LexError),
     /// A comment block.
@@ -436,6 +448,13 @@ impl Token {
                 Export => "export",
                 #[cfg(not(feature = "no_module"))]
                 As => "as",
+
+                #[cfg(not(feature = "no_function"))]
+                #[cfg(not(feature = "no_object"))]
+                Class => "class",
+                #[cfg(not(feature = "no_function"))]
+                #[cfg(not(feature = "no_object"))]
+                New => "new",
                 EOF => "{EOF}",
                 _ => unreachable!("operator should be matched in outer scope"),
             }
@@ -526,16 +545,23 @@ impl Token {
             #[cfg(not(feature = "no_module"))]
             "as" => As,
 
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_object"))]
+            "new" => New,
+
             #[cfg(feature = "no_function")]
             "fn" | "private" => Reserved(syntax.into()),
 
             #[cfg(feature = "no_module")]
             "import" | "export" | "as" => Reserved(syntax.into()),
 
+            #[cfg(any(feature = "no_function", feature = "no_object"))]
+            "new" => Reserved(syntax.into()),
+
             "===" | "!==" | "->" | "<-" | ":=" | "**" | "::<" | "(*" | "*)" | "#" | "public"
-            | "new" | "use" | "module" | "package" | "var" | "static" | "begin" | "end"
-            | "shared" | "with" | "each" | "then" | "goto" | "unless" | "exit" | "match"
-            | "case" | "default" | "void" | "null" | "nil" | "spawn" | "thread" | "go" | "sync"
+            | "use" | "module" | "package" | "var" | "static" | "begin" | "end" | "shared"
+            | "with" | "each" | "then" | "goto" | "unless" | "exit" | "match" | "case"
+            | "default" | "void" | "null" | "nil" | "spawn" | "thread" | "go" | "sync"
             | "async" | "await" | "yield" => Reserved(syntax.into()),
 
             KEYWORD_PRINT | KEYWORD_DEBUG | KEYWORD_TYPE_OF | KEYWORD_EVAL | KEYWORD_FN_PTR
@@ -696,6 +722,10 @@ impl Token {
             #[cfg(not(feature = "no_module"))]
             Import | Export | As => true,
 
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_object"))]
+            Class | New => true,
+
             True | False | Let | Const | If | Else | Do | While | Until | Loop | For | In
             | Continue | Break | Return | Throw | Try | Catch => true,
 
@@ -718,10 +748,25 @@ impl Token {
         match self {
             Self::Reserved(s) if can_override_keyword(&s) => Ok(s),
             Self::Custom(s) | Self::Identifier(s) if is_valid_identifier(s.chars()) => Ok(s),
+            _ if self.can_override_operator() => Ok(self.syntax().into()),
             _ => Err(self),
         }
     }
 
+    /// Can this operator be overloaded by a script-defined function?
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn can_override_operator(&self) -> bool {
+        use Token::*;
+
+        match self {
+            Plus | UnaryPlus | Minus | UnaryMinus | Multiply | Divide | Modulo | PowerOf
+            | LeftShift | RightShift | Ampersand | Pipe | XOr | Bang | EqualsTo | NotEqualsTo
+            | LessThan | LessThanEqualsTo | GreaterThan | GreaterThanEqualsTo => true,
+
+            _ => false,
+        }
+    }
+
     /// Is this token a custom keyword?
     #[inline(always)]
     pub fn is_custom(&self) -> bool {
@@ -1691,12 +1736,50 @@ pub struct TokenIterator<'a, 'e> {
     stream: MultiInputsStream<'a>,
     /// A processor function that maps a token to another.
     map: Option<fn(Token) -> Token>,
+    /// Token looked ahead to find out whether `class` starts a class definition.
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    look_ahead: Option<(Token, Position)>,
 }
 
 impl<'a> Iterator for TokenIterator<'a, '_> {
     type Item = (Token, Position);
 
+    #[cfg(any(feature = "no_function", feature = "no_object"))]
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(r) = self.look_ahead.take() {
+            return Some(r);
+        }
+
+        match self.next_token()? {
+            // `class` is a keyword only when followed by the class name, so it can still be used
+            // as the name of a variable, parameter or property
+            (Token::Identifier(s), pos) if s == "class" => {
+                self.look_ahead = self.next_token();
+
+                match self.look_ahead {
+                    Some((Token::Identifier(_), _)) => Some((Token::Class, pos)),
+                    Some((Token::Reserved(ref name), _)) if is_valid_identifier(name.chars()) => {
+                        Some((Token::Class, pos))
+                    }
+                    _ => Some((Token::Identifier(s), pos)),
+                }
+            }
+            r => Some(r),
+        }
+    }
+}
+
+impl<'a> TokenIterator<'a, '_> {
+    /// Get the next token, not taking `class` definitions into account.
+    fn next_token(&mut self) -> Option<(Token, Position)> {
         let (token, pos) = match get_next_token(&mut self.stream, &mut self.state, &mut self.pos) {
             // {EOF}
             None => return None,
@@ -1820,6 +1903,9 @@ impl Engine {
                 index: 0,
             },
             map,
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_object"))]
+            look_ahead: None,
         }
     }
 }
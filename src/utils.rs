@@ -139,6 +139,33 @@ pub(crate) fn combine_hashes(a: NonZeroU64, b: NonZeroU64) -> NonZeroU64 {
     NonZeroU64::new(a.get() ^ b.get()).unwrap_or_else(|| NonZeroU64::new(42).unwrap())
 }
 
+/// A partially-calculated hash key for a non-qualified native function call, with the function
+/// name already hashed.
+///
+/// Completing it with the parameter types yields the same value as [`calc_native_fn_hash`].
+#[cfg(feature = "bytecode")]
+#[derive(Debug, Clone)]
+pub(crate) struct NativeFnHashPrefix(ahash::AHasher);
+
+#[cfg(feature = "bytecode")]
+impl NativeFnHashPrefix {
+    /// Hash the name of a non-qualified function.
+    pub fn new(fn_name: &str) -> Self {
+        let mut s: ahash::AHasher = Default::default();
+        false.hash(&mut s);
+        fn_name.hash(&mut s);
+        Self(s)
+    }
+    /// Complete the hash key with the parameter types.
+    #[inline]
+    pub fn complete(&self, params: impl Iterator<Item = TypeId>) -> Option<NonZeroU64> {
+        let s = &mut self.0.clone();
+        params.for_each(|t| t.hash(s));
+        // HACK - If it so happens to hash directly to zero (OMG!) then change it to 42...
+        NonZeroU64::new(s.finish()).or_else(|| NonZeroU64::new(42))
+    }
+}
+
 /// _(INTERNALS)_ A type that wraps a [`HashMap`] and implements [`Hash`].
 /// Exported under the `internals` feature only.
 #[derive(Clone, Default)]
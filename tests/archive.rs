@@ -0,0 +1,168 @@
+#![cfg(feature = "archive")]
+#![cfg(not(feature = "no_module"))]
+use rhai::{module_resolvers::ArchiveModuleResolver, Engine, EvalAltResult, INT};
+
+fn make_archive(files: &[(&str, &str)]) -> Vec<u8> {
+    let files: Vec<_> = files
+        .iter()
+        .map(|(path, contents)| (*path, contents.as_bytes()))
+        .collect();
+
+    make_binary_archive(&files)
+}
+
+fn make_binary_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+
+    for (path, contents) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, *contents).unwrap();
+    }
+
+    builder.into_inner().unwrap()
+}
+
+#[test]
+fn test_archive_resolver() -> Result<(), Box<EvalAltResult>> {
+    let archive = make_archive(&[
+        (
+            "manifest.json",
+            r#"{
+                "name": "pkg",
+                "version": "1.2.3",
+                "entry": "main",
+                "modules": {
+                    "main": "src/main.rhai",
+                    "net/http": "src/net/http.rhai",
+                    "net/util": "src/net/util.rhai"
+                }
+            }"#,
+        ),
+        (
+            "src/main.rhai",
+            r#"import "pkg/net/http" as http; export const answer = http::answer;"#,
+        ),
+        (
+            "src/net/http.rhai",
+            r#"import "./util" as util; export const answer = util::base + 1;"#,
+        ),
+        ("src/net/util.rhai", "export const base = 41;"),
+    ]);
+
+    let resolver = ArchiveModuleResolver::from_bytes(&archive)?;
+    assert_eq!(resolver.name(), "pkg");
+    assert_eq!(resolver.version(), "1.2.3");
+    assert_eq!(resolver.entry(), Some("main"));
+    assert!(resolver.contains_path("pkg"));
+    assert!(resolver.contains_path("pkg/net/util"));
+    assert!(!resolver.contains_path("pkg/missing"));
+    assert!(!resolver.contains_path("other/main"));
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(engine.eval::<INT>(r#"import "pkg" as p; p::answer"#)?, 42);
+    assert_eq!(
+        engine.eval::<INT>(r#"import "pkg/net/http" as h; h::answer"#)?,
+        42
+    );
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import "pkg/missing" as m; 0"#).expect_err("should error"),
+        EvalAltResult::ErrorModuleNotFound(p, _, _) if p == "pkg/missing"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_archive_resolver_default_modules() -> Result<(), Box<EvalAltResult>> {
+    let archive = make_archive(&[
+        (
+            "manifest.json",
+            r#"{ "name": "lib", "version": "0.1.0-beta.1+build.5", "description": "a {braced} description" }"#,
+        ),
+        ("math.rhai", "fn double(x) { x * 2 }"),
+        ("README.md", "not a module"),
+    ]);
+
+    let resolver = ArchiveModuleResolver::from_bytes(&archive)?;
+    assert_eq!(resolver.version(), "0.1.0-beta.1+build.5");
+    assert_eq!(resolver.entry(), None);
+    assert!(resolver.contains_path("lib/math"));
+    assert!(!resolver.contains_path("lib/README"));
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>(r#"import "lib/math" as m; m::double(21)"#)?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_archive_resolver_invalid_manifest() {
+    for files in &[
+        &[("math.rhai", "42")][..],
+        &[("manifest.json", r#"{ "version": "0.1.0" }"#)][..],
+        &[("manifest.json", r#"{ "name": "lib", "version": 1 }"#)][..],
+        &[("manifest.json", r#"{ "name": "lib", "version": "1.0" }"#)][..],
+        &[("manifest.json", r#"{ "name": "lib", "version": "1.0.x" }"#)][..],
+        &[("manifest.json", r#"{ "name": "lib", "version": "1.0.0-" }"#)][..],
+        &[("manifest.json", r#"{ "name": "a/b", "version": "1.0.0" }"#)][..],
+        &[("manifest.json", r#"[ "lib", "1.0.0" ]"#)][..],
+        &[(
+            "manifest.json",
+            r#"{ "name": "lib", "version": "0.1.0", "modules": { "a": "a.rhai" } }"#,
+        )][..],
+        &[(
+            "manifest.json",
+            r#"{ "name": "lib", "version": "0.1.0", "entry": "main" }"#,
+        )][..],
+    ] {
+        assert!(matches!(
+            ArchiveModuleResolver::from_bytes(&make_archive(files)).expect_err("should error").as_ref(),
+            EvalAltResult::ErrorSystem(s, _) if s == "Invalid package manifest"
+        ));
+    }
+}
+
+#[test]
+fn test_archive_resolver_binary_files() -> Result<(), Box<EvalAltResult>> {
+    let manifest = br#"{ "name": "lib", "version": "0.1.0" }"#;
+
+    // Files that are not modules need not be UTF-8
+    let archive = make_binary_archive(&[
+        ("manifest.json", &manifest[..]),
+        ("math.rhai", b"export const answer = 42;"),
+        ("logo.png", b"\x89PNG\r\n\x1a\n\xff\xfe"),
+        ("README.txt", b"caf\xe9"),
+    ]);
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(ArchiveModuleResolver::from_bytes(&archive)?);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "lib/math" as m; m::answer"#)?,
+        42
+    );
+
+    // Modules must be UTF-8
+    let archive = make_binary_archive(&[
+        ("manifest.json", &manifest[..]),
+        ("math.rhai", b"export const name = \"caf\xe9\";"),
+    ]);
+
+    assert!(matches!(
+        ArchiveModuleResolver::from_bytes(&archive).expect_err("should error").as_ref(),
+        EvalAltResult::ErrorSystem(s, _) if s.contains("'math.rhai'")
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use rhai::{CacheError, Engine, EvalAltResult, AST, INT};
+
+#[test]
+fn test_ast_cache() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = r#"
+        let x = 40;
+        let total = 0;
+
+        for n in range(1, 4) { total += n; }
+
+        switch total {
+            6 => x += 1,
+            _ => x = 0
+        }
+
+        try { throw x; } catch (err) { return err + 1; }
+    "#;
+
+    let ast = engine.compile(script)?;
+    let bytes = ast.to_bytes().unwrap();
+    let cached = AST::from_bytes(&bytes).unwrap();
+
+    assert_eq!(engine.eval_ast::<INT>(&cached)?, 42);
+    assert_eq!(cached.to_bytes().unwrap(), bytes);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_ast_cache_functions() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let mut ast = engine.compile(
+        r#"
+            /// Doubles a number
+            fn double(x) { x * 2 }
+            private fn helper(x) { x + 1 }
+            fn calc(x) { double(helper(x)) }
+
+            calc(20) + 0
+        "#,
+    )?;
+    ast.set_source("calc");
+
+    let cached = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(cached.source(), Some("calc"));
+    assert_eq!(cached.iter_functions().count(), 3);
+    assert_eq!(engine.eval_ast::<INT>(&cached)?, 42);
+
+    let f = cached
+        .iter_functions()
+        .find(|f| f.name == "double")
+        .unwrap();
+    assert_eq!(f.comments, vec!["/// Doubles a number"]);
+
+    assert_eq!(
+        engine.call_fn::<INT>(&mut Default::default(), &cached, "calc", (1 as INT,))?,
+        4
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_ast_cache_modules() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{module_resolvers::StaticModuleResolver, Module, Scope};
+
+    let mut engine = Engine::new();
+
+    let module_ast = engine.compile("export const ANSWER = 41; fn inc(x) { x + 1 }")?;
+    let module = Module::eval_ast_as_new(Scope::new(), &module_ast, &engine)?;
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("question", module);
+    engine.set_module_resolver(resolver);
+
+    let ast = engine.compile_into_self_contained(
+        &Scope::new(),
+        r#"import "question" as q; q::inc(q::ANSWER)"#,
+    )?;
+
+    let cached = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    // The embedded resolver is restored from the cache
+    let engine = Engine::new();
+    assert_eq!(engine.eval_ast::<INT>(&cached)?, 42);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_closure"))]
+#[test]
+fn test_ast_cache_module_vars() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{module_resolvers::StaticModuleResolver, Module, Scope};
+
+    let mut engine = Engine::new();
+
+    let module_ast = engine.compile(
+        "export const STEP = 1; export let count = 39; fn next() { count += STEP; count }",
+    )?;
+    let module = Module::eval_ast_as_new(Scope::new(), &module_ast, &engine)?;
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("counter", module);
+    engine.set_module_resolver(resolver);
+
+    let ast = engine.compile_into_self_contained(
+        &Scope::new(),
+        r#"import "counter" as c; c::next(); c::next(); c::next()"#,
+    )?;
+
+    let cached = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    // Module state is restored from the cache and shared across calls
+    let engine = Engine::new();
+    assert_eq!(engine.eval_ast::<INT>(&cached)?, 42);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_ast_cache_not_serializable() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{module_resolvers::StaticModuleResolver, Module, Scope};
+
+    let mut engine = Engine::new();
+
+    let mut module = Module::new();
+    module.set_fn_1("inc", |x: INT| Ok(x + 1));
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("native", module);
+    engine.set_module_resolver(resolver);
+
+    let ast =
+        engine.compile_into_self_contained(&Scope::new(), r#"import "native" as n; n::inc(41)"#)?;
+
+    assert!(matches!(
+        ast.to_bytes().expect_err("should error"),
+        CacheError::NotSerializable(s) if s.contains("inc")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_ast_cache_errors() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let bytes = engine.compile("let x = 42; x")?.to_bytes().unwrap();
+
+    assert_eq!(
+        AST::from_bytes(b"not a cache").expect_err("should error"),
+        CacheError::InvalidFormat
+    );
+    assert_eq!(
+        AST::from_bytes(&bytes[..bytes.len() - 1]).expect_err("should error"),
+        CacheError::Corrupted
+    );
+
+    // Byte 7 is the format version, right after the magic bytes
+    let mut wrong_version = bytes.clone();
+    wrong_version[7] += 1;
+    assert!(matches!(
+        AST::from_bytes(&wrong_version).expect_err("should error"),
+        CacheError::IncompatibleVersion(_)
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_ast_cache_overloads() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let ast = engine.compile(
+        r#"
+            fn describe(x: int) { 1 }
+            fn describe(x: string) { 2 }
+            fn describe(x) { 3 }
+
+            describe(0) * 100 + describe("x") * 10 + describe(true)
+        "#,
+    )?;
+
+    let cached = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(cached.iter_functions().count(), 3);
+    assert_eq!(engine.eval_ast::<INT>(&cached)?, 123);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_ast_cache_classes() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let ast = engine.compile(
+        r#"
+            class Point {
+                let x = 40;
+                let y;
+
+                fn new(y) { this.y = y; }
+                fn sum() { this.x + this.y }
+            }
+
+            new Point(2).sum()
+        "#,
+    )?;
+
+    let cached = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(engine.eval_ast::<INT>(&cached)?, 42);
+
+    Ok(())
+}
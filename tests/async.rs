@@ -0,0 +1,204 @@
+#![cfg(feature = "async")]
+use rhai::{
+    Dynamic, Engine, EvalAltResult, RegisterAsyncFn, RegisterAsyncResultFn, RegisterFn, Scope,
+    Shared, INT,
+};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+/// A minimal single-threaded executor.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(result) => return result,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// A future that completes on another thread after a short delay.
+struct Delayed<T> {
+    value: Option<T>,
+    state: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+impl<T> Delayed<T> {
+    fn new(value: T) -> Self {
+        let state: Arc<Mutex<(bool, Option<Waker>)>> = Default::default();
+        let timer = state.clone();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            let mut state = timer.lock().unwrap();
+            state.0 = true;
+            if let Some(waker) = state.1.take() {
+                waker.wake();
+            }
+        });
+
+        Self {
+            value: Some(value),
+            state,
+        }
+    }
+}
+
+impl<T: Unpin> Future for Delayed<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let ready = {
+            let mut state = self.state.lock().unwrap();
+            state.1 = Some(cx.waker().clone());
+            state.0
+        };
+
+        if ready {
+            Poll::Ready(self.value.take().unwrap())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn test_async_fn() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_async_fn("fetch", |x: INT| Delayed::new(x * 2))
+        .register_async_fn("greet", |name: String| async move {
+            format!("hello, {}!", name)
+        })
+        .register_fn("inc", |x: INT| x + 1);
+
+    let engine = Shared::new(engine);
+
+    let ast = engine.compile("let x = fetch(10); inc(fetch(x)) + fetch(1)")?;
+    assert_eq!(block_on(engine.eval_ast_async::<INT>(&ast))?, 43);
+
+    let ast = engine.compile(r#"greet("world")"#)?;
+    assert_eq!(
+        block_on(engine.eval_ast_async::<String>(&ast))?,
+        "hello, world!"
+    );
+
+    let ast = engine.compile("let sum = 0; for x in range(0, 5) { sum += fetch(x); } sum")?;
+    assert_eq!(block_on(engine.eval_ast_async::<INT>(&ast))?, 20);
+
+    Ok(())
+}
+
+#[test]
+fn test_async_fn_str_param() -> Result<(), Box<EvalAltResult>> {
+    async fn count_chars(s: &str) -> INT {
+        Delayed::new(()).await;
+        s.chars().count() as INT
+    }
+
+    let mut engine = Engine::new();
+
+    engine.register_async_fn("count_chars", count_chars);
+
+    let engine = Shared::new(engine);
+
+    let ast = engine.compile(r#"let s = "hello"; count_chars(s + ", world!")"#)?;
+    assert_eq!(block_on(engine.eval_ast_async::<INT>(&ast))?, 13);
+
+    Ok(())
+}
+
+#[test]
+fn test_async_fn_result() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_async_result_fn("div", |x: INT, y: INT| async move {
+        if y == 0 {
+            Err("division by zero!".into())
+        } else {
+            Ok(Dynamic::from(x / y))
+        }
+    });
+
+    let engine = Shared::new(engine);
+
+    let ast = engine.compile("div(84, 2)")?;
+    assert_eq!(block_on(engine.eval_ast_async::<INT>(&ast))?, 42);
+
+    let ast = engine.compile("div(42, 0)")?;
+    assert!(matches!(
+        *block_on(engine.eval_ast_async::<INT>(&ast)).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    let ast = engine.compile("try { div(42, 0); } catch (err) { return err; }")?;
+    assert_eq!(
+        block_on(engine.eval_ast_async::<String>(&ast))?,
+        "division by zero!"
+    );
+
+    let ast = engine.compile("div(84, 2)")?;
+    assert!(matches!(
+        *block_on(engine.eval_ast_async::<bool>(&ast)).expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(_, _, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_async_fn_scope() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_async_fn("fetch", |x: INT| Delayed::new(x * 2));
+
+    let engine = Shared::new(engine);
+
+    let mut scope = Scope::new();
+    scope.push("x", 20 as INT);
+
+    let ast = engine.compile("x = fetch(x); x + 2")?;
+
+    let mut eval = engine.eval_ast_with_scope_async::<INT>(scope, &ast);
+    assert_eq!(block_on(&mut eval)?, 42);
+
+    let scope = eval.into_scope().expect("evaluation should be completed");
+    assert_eq!(
+        scope
+            .get_value::<INT>("x")
+            .expect("variable x should exist"),
+        40
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_async_fn_sync_eval() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_async_fn("fetch", |x: INT| async move { x * 2 });
+
+    assert!(matches!(
+        *engine.eval::<INT>("fetch(21)").expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(_, _, _, _) | EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use rhai::{CustomType, Engine, EvalAltResult, TypeBuilder, INT};
+
+#[test]
+fn test_build_type() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Vec3 {
+        x: INT,
+        y: INT,
+        z: INT,
+    }
+
+    impl Vec3 {
+        fn new(x: INT, y: INT, z: INT) -> Self {
+            Self { x, y, z }
+        }
+        fn get_x(&mut self) -> INT {
+            self.x
+        }
+        fn set_x(&mut self, x: INT) {
+            self.x = x
+        }
+        fn get_y(&mut self) -> INT {
+            self.y
+        }
+        fn get_z(&mut self) -> INT {
+            self.z
+        }
+        fn set_z(&mut self, z: INT) {
+            self.z = z
+        }
+    }
+
+    impl CustomType for Vec3 {
+        fn build(mut builder: TypeBuilder<Self>) {
+            builder
+                .with_name("Vec3")
+                .with_fn("vec3", Self::new)
+                .with_fn("sum", |v: &mut Vec3| v.x + v.y + v.z)
+                .with_result_fn("checked_x", |v: &mut Vec3| {
+                    if v.x >= 0 {
+                        Ok(v.x.into())
+                    } else {
+                        Err("negative x".into())
+                    }
+                })
+                .with_get_set("x", Self::get_x, Self::set_x)
+                .with_get("y", Self::get_y)
+                .with_get_set("z", Self::get_z, Self::set_z);
+        }
+    }
+
+    let mut engine = Engine::new();
+    engine.build_type::<Vec3>();
+
+    assert_eq!(engine.eval::<Vec3>("vec3(1, 2, 3)")?, Vec3::new(1, 2, 3));
+    assert_eq!(engine.eval::<String>("type_of(vec3(1, 2, 3))")?, "Vec3");
+    assert_eq!(engine.eval::<INT>("let v = vec3(1, 2, 3); sum(v)")?, 6);
+    assert_eq!(engine.eval::<INT>("checked_x(vec3(1, 2, 3))")?, 1);
+    assert!(engine.eval::<INT>("checked_x(vec3(-1, 2, 3))").is_err());
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>("let v = vec3(1, 2, 3); v.x = 10; v.z = 20; v.x + v.y + v.z")?,
+            32
+        );
+        assert!(engine
+            .eval::<INT>("let v = vec3(1, 2, 3); v.y = 10; v.y")
+            .is_err());
+    }
+
+    Ok(())
+}
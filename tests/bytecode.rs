@@ -0,0 +1,142 @@
+#![cfg(feature = "bytecode")]
+use rhai::{Engine, EvalAltResult, ExecutionBackend, Position, INT};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Run a script on both backends, returning the results and the number of operations.
+fn run_both(script: &str) -> Vec<(Result<INT, Box<EvalAltResult>>, u64)> {
+    [ExecutionBackend::TreeWalking, ExecutionBackend::Bytecode]
+        .iter()
+        .map(|&backend| {
+            let mut engine = Engine::new();
+            engine.set_execution_backend(backend);
+            assert_eq!(engine.execution_backend(), backend);
+
+            let ops = Arc::new(AtomicU64::new(0));
+            let counter = ops.clone();
+            engine.on_progress(move |count| {
+                counter.store(count, Ordering::Relaxed);
+                None
+            });
+
+            let result = engine.eval::<INT>(script);
+            (result, ops.load(Ordering::Relaxed))
+        })
+        .collect()
+}
+
+#[test]
+fn test_bytecode_default() {
+    assert_eq!(
+        Engine::new().execution_backend(),
+        ExecutionBackend::Bytecode
+    );
+}
+
+#[test]
+fn test_bytecode_parity() -> Result<(), Box<EvalAltResult>> {
+    let script = r#"
+        let total = 0;
+        let i = 0;
+
+        while i < 10 {
+            i += 1;
+            if i % 2 == 0 { continue; }
+            total += i;
+        }
+
+        do { total -= 1; } until total < 20;
+
+        for n in range(0, 100) {
+            switch n {
+                3 => total += 100,
+                7 => break,
+                _ => { let x = n; total += x; }
+            }
+        }
+
+        total + if total > 0 && !(total < 10 || false) { 1 } else { 2 }
+    "#;
+
+    let results = run_both(script);
+
+    assert_eq!(*results[0].0.as_ref().unwrap(), 138);
+    assert_eq!(*results[1].0.as_ref().unwrap(), 138);
+    assert_eq!(results[0].1, results[1].1);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_bytecode_parity_functions() -> Result<(), Box<EvalAltResult>> {
+    let script = r#"
+        fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } }
+
+        fn find(limit) {
+            for x in range(0, limit) {
+                try {
+                    if x == 5 { break; }
+                    if x == 3 { throw x; }
+                } catch (err) {
+                    return err * 100;
+                }
+            }
+            0
+        }
+
+        fib(6) + find(10)
+    "#;
+
+    let results = run_both(script);
+
+    assert_eq!(*results[0].0.as_ref().unwrap(), 308);
+    assert_eq!(*results[1].0.as_ref().unwrap(), 308);
+    assert_eq!(results[0].1, results[1].1);
+
+    Ok(())
+}
+
+#[test]
+fn test_bytecode_parity_errors() -> Result<(), Box<EvalAltResult>> {
+    for script in &[
+        "let x = 1; if x { 0 } else { 1 }",
+        "let x = 40; while x { x += 1; } x",
+        "let x = \"hello\"; x * 2",
+        "let x = 42; throw x + 1;",
+        "for x in 42 { print(x); } 0",
+        "let x = 1; y + x",
+    ] {
+        let results = run_both(script);
+        let tree_walking = results[0].0.as_ref().expect_err("should error");
+        let bytecode = results[1].0.as_ref().expect_err("should error");
+
+        assert_ne!(tree_walking.position(), Position::NONE);
+        assert_eq!(format!("{:?}", tree_walking), format!("{:?}", bytecode));
+        assert_eq!(results[0].1, results[1].1);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bytecode_ast_reuse() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let mut ast = engine.compile("let x = 40; x + 2")?;
+
+    for _ in 0..3 {
+        assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    }
+
+    // Statements added after the first evaluation must be picked up
+    ast.combine(engine.compile("x * 2")?);
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 80);
+
+    ast.clear_statements();
+    assert!(engine.eval_ast::<()>(&ast).is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,274 @@
+#![cfg(not(feature = "no_function"))]
+#![cfg(not(feature = "no_object"))]
+
+use rhai::{ClassInstance, Engine, EvalAltResult, ParseErrorType, INT};
+
+const POINT: &str = r#"
+    class Point {
+        let x = 0;
+        let y = 0;
+        let label;
+
+        fn new(x, y) {
+            this.x = x;
+            this.y = y;
+        }
+        fn new(x, y, label) {
+            this.x = x;
+            this.y = y;
+            this.label = label;
+        }
+
+        fn sum() { this.x + this.y }
+        fn scale(factor) {
+            this.x *= factor;
+            this.y *= factor;
+            this
+        }
+        fn moved(dx, dy) { new Point(this.x + dx, this.y + dy) }
+    }
+"#;
+
+#[test]
+fn test_classes() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(&format!("{} let p = new Point(40, 2); p.sum()", POINT))?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let p = new Point(1, 2); p.x = 20; p.y += 1; p.sum()",
+            POINT
+        ))?,
+        23
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let p = new Point(1, 2); p.scale(10); p.moved(1, 1).scale(2).sum()",
+            POINT
+        ))?,
+        64
+    );
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>(&format!(
+            r#"{} let p = new Point(1, 2, "A"); p.label + p["x"]"#,
+            POINT
+        ))?,
+        "A1"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!("{} type_of(new Point(1, 2))", POINT))?,
+        "Point"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!("{} new Point(1, 2).to_string()", POINT))?,
+        r#"Point #{"x": 1, "y": 2, "label": ()}"#
+    );
+
+    let p = engine.eval::<ClassInstance>(&format!("{} new Point(1, 2)", POINT))?;
+    assert_eq!(p.class_name(), "Point");
+    assert_eq!(
+        p.iter_fields().map(|(name, _)| name).collect::<Vec<_>>(),
+        vec!["x", "y", "label"]
+    );
+
+    // Methods are shared, but fields are not
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let a = new Point(1, 1); let b = a; b.x = 10; a.sum() * 100 + b.sum()",
+            POINT
+        ))?,
+        211
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(&format!("{} let p = new Point(1, 2); p.z", POINT))
+            .expect_err("should error"),
+        EvalAltResult::ErrorDotExpr(msg, _) if msg == "Unknown property 'z' for class 'Point'"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<()>(&format!("{} let p = new Point(1, 2); p.z = 1;", POINT))
+            .expect_err("should error"),
+        EvalAltResult::ErrorDotExpr(_, _)
+    ));
+    // No default constructor when constructors are defined
+    assert!(matches!(
+        *engine
+            .eval::<ClassInstance>(&format!("{} new Point()", POINT))
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f == "Point ()"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(&format!("{} new Point(1, 2).foo()", POINT))
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_classes_defaults() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn initial() { 40 }
+
+                class Counter {
+                    let count = initial();
+                    let items = [];
+
+                    fn inc() { this.count += 1; this.items.push(this.count); }
+                }
+
+                let a = new Counter();
+                let b = new Counter();
+                a.inc();
+                a.inc();
+                b.inc();
+                a.count + a.items.len() * 1000 + b.items.len() * 100
+            "#
+        )?,
+        2142
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_classes_overloads() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                class Cat { fn speak() { "meow" } }
+                class Dog { fn speak() { "woof" } }
+
+                fn greet(pet: Cat) { "cat says " + pet.speak() }
+                fn greet(pet: Dog) { "dog says " + pet.speak() }
+                fn greet(pet) { "???" }
+
+                greet(new Cat()) + ", " + greet(new Dog()) + ", " + greet(42)
+            "#
+        )?,
+        "cat says meow, dog says woof, ???"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_classes_parse_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(
+        *engine
+            .compile("class Point {} class Point {}")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ClassDuplicatedDefinition(c) if c == "Point"
+    ));
+    assert!(matches!(
+        *engine
+            .compile("class Point { let x; let x = 1; }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ClassDuplicatedField(c, f) if c == "Point" && f == "x"
+    ));
+    assert!(matches!(
+        *engine
+            .compile("fn foo() { class Point {} }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ClassWrongDefinition
+    ));
+    assert!(matches!(
+        *engine
+            .compile("let p = new 42;")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::ClassMissingName
+    ));
+    assert!(matches!(
+        *engine
+            .compile("class Point { fn new() {} fn new() {} }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedDefinition(f, 0) if f == "Point"
+    ));
+}
+
+#[test]
+fn test_classes_keyword() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    // `class` is a keyword only when followed by the name of the class
+    assert_eq!(engine.eval::<INT>("let class = 40; class += 2; class")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("fn foo(class) { class * 2 } foo(21)")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("let obj = #{ class: 41 }; obj.class += 1; obj.class")?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>("class Point { let x = 42; } let class = new Point(); class.x")?,
+        42
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_classes_modules() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{module_resolvers::StaticModuleResolver, Module};
+
+    let mut engine = Engine::new();
+
+    let ast = engine.compile(
+        r#"
+            let unit = 10;
+            export unit;
+
+            fn twice(v) { v * 2 }
+
+            class Meter {
+                let value = 0;
+
+                fn new(value) { this.value = value; }
+                fn centimeters() { twice(this.value * unit) * 5 }
+            }
+        "#,
+    )?;
+
+    let module = Module::eval_ast_as_new(Default::default(), &ast, &engine)?;
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("units", module);
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                import "units" as u;
+                let m = new u::Meter(42);
+                m.centimeters() + m.value
+            "#
+        )?,
+        4242
+    );
+
+    Ok(())
+}
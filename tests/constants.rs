@@ -95,3 +95,81 @@ fn test_constant_mut() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_constant_global() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                const X = 40;
+                fn foo() { global::X + 2 }
+                foo()
+            "
+        )?,
+        42
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("fn foo() { global::Y } foo()")
+            .expect_err("expects error"),
+        EvalAltResult::ErrorInFunctionCall(_, _, err, _)
+            if matches!(&*err, EvalAltResult::ErrorVariableNotFound(x, _) if x == "global::Y")
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile(r#"import "hello" as global;"#)
+            .expect_err("expects error")
+            .0,
+        ParseErrorType::Reserved(x) if x == "global"
+    ));
+
+    let mut scope = Scope::new();
+    scope.push_constant("MY_NUMBER", 21 as INT);
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "fn foo() { global::MY_NUMBER * 2 } foo()")?,
+        42
+    );
+
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        engine.set_optimization_level(rhai::OptimizationLevel::Simple);
+
+        let ast = engine.compile_with_scope(&scope, "fn foo() { global::MY_NUMBER * 2 } foo()")?;
+
+        assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+
+        // Constants declared after other code are not inlined into functions
+        let ast = engine.compile("fn foo() { global::X } let r = foo(); const X = 1; r")?;
+
+        assert!(matches!(
+            *engine.eval_ast::<INT>(&ast).expect_err("expects error"),
+            EvalAltResult::ErrorInFunctionCall(_, _, err, _)
+                if matches!(&*err, EvalAltResult::ErrorVariableNotFound(x, _) if x == "global::X")
+        ));
+    }
+
+    let ast = engine.compile("fn foo() { global::MY_NUMBER * 2 }")?;
+
+    let r: INT = engine.call_fn(&mut scope, &ast, "foo", ())?;
+    assert_eq!(r, 42);
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                const X = 40;
+                fn foo() { eval("global::X + 2") }
+                foo()
+            "#
+        )?,
+        42
+    );
+
+    Ok(())
+}
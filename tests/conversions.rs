@@ -0,0 +1,162 @@
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Meters(INT);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Kilometers(INT);
+
+#[test]
+fn test_conversions() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn("double", |m: Meters| Meters(m.0 * 2))
+        .register_fn("add", |m: Meters, n: Meters| Meters(m.0 + n.0))
+        .register_conversion::<INT, Meters>(Meters);
+
+    assert_eq!(engine.eval::<Meters>("double(21)")?, Meters(42));
+    assert_eq!(engine.eval::<Meters>("add(40, 2)")?, Meters(42));
+
+    // Results are the same when the conversions are cached
+    assert_eq!(
+        engine.eval::<Meters>("let x = 0; for i in range(0, 5) { x = double(i) } x")?,
+        Meters(8)
+    );
+
+    // Exact matches are preferred
+    engine.register_fn("double", |x: INT| x * 2);
+    assert_eq!(engine.eval::<INT>("double(21)")?, 42);
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        engine
+            .register_fn("half", |x: rhai::FLOAT| x / 2.0)
+            .register_conversion::<INT, rhai::FLOAT>(|x| x as rhai::FLOAT);
+
+        assert_eq!(engine.eval::<rhai::FLOAT>("half(3)")?, 1.5);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_conversions_builtin() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new_raw();
+
+    engine
+        .register_fn("+", |m: Meters, n: Meters| Meters(m.0 + n.0))
+        .register_conversion::<INT, Meters>(Meters);
+
+    // Built-in operators are preferred
+    assert_eq!(engine.eval::<INT>("1 + 2")?, 3);
+
+    // Also when the failed search for a registered function is cached
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; let i = 0; while i < 5 { x = x + i; i += 1; } x")?,
+        10
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_optimize"))]
+#[test]
+fn test_conversions_optimizer() -> Result<(), Box<EvalAltResult>> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(rhai::OptimizationLevel::Full);
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+
+    engine
+        .register_fn("tick", move |m: Meters| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            m.0
+        })
+        .register_conversion::<INT, Meters>(Meters);
+
+    // Functions reached via conversions are not `const` and must not run at compile time
+    let ast = engine.compile("tick(42)")?;
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_conversions_chain() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn("km", |k: Kilometers| k.0)
+        .register_conversion::<INT, Meters>(Meters)
+        .register_conversion::<Meters, Kilometers>(|m| Kilometers(m.0 / 1000));
+
+    assert_eq!(engine.max_conversion_chain(), 2);
+    assert_eq!(engine.eval::<INT>("km(42000)")?, 42);
+
+    engine.set_max_conversion_chain(1);
+    assert!(matches!(
+        *engine.eval::<INT>("km(42000)").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    engine.set_max_conversion_chain(0);
+    engine.register_fn("m", |m: Meters| m.0);
+    assert!(engine.eval::<INT>("m(42)").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_conversions_precedence() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn("pick", |_: Meters, _: INT| "first")
+        .register_fn("pick", |_: INT, _: Meters| "second")
+        .register_fn("unit", |_: Meters| "meters")
+        .register_fn("unit", |_: Kilometers| "kilometers")
+        .register_conversion::<INT, Meters>(Meters)
+        .register_conversion::<INT, Kilometers>(Kilometers);
+
+    // Later arguments are converted first
+    assert_eq!(engine.eval::<String>("pick(1, 2)")?, "second");
+
+    // Conversions registered earlier are preferred
+    assert_eq!(engine.eval::<String>("unit(1)")?, "meters");
+
+    // Replacing a conversion keeps its precedence
+    engine.register_conversion::<INT, Meters>(|x| Meters(x * 1000));
+    assert_eq!(engine.eval::<String>("unit(1)")?, "meters");
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_conversions_method() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn("stretch", |m: &mut Meters, n: Meters| m.0 += n.0)
+        .register_fn("meters", Meters)
+        .register_conversion::<INT, Meters>(Meters);
+
+    // The first argument is not converted and is modified
+    assert_eq!(
+        engine.eval::<Meters>("let x = meters(40); x.stretch(2); x")?,
+        Meters(42)
+    );
+
+    // A converted first argument is a copy
+    assert_eq!(engine.eval::<INT>("let x = 40; x.stretch(2); x")?, 40);
+
+    Ok(())
+}
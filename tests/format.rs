@@ -0,0 +1,188 @@
+use rhai::{Engine, EvalAltResult};
+
+#[test]
+fn test_format() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"format("{} + {} = {}", 1, 2, 1 + 2)"#)?,
+        "1 + 2 = 3"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{1}-{0}-{1}, {{literal}}", 'a', "b")"#)?,
+        "b-a-b, {literal}"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:>6}|{:<6}|{:^7}]", "ab", true, "mid")"#)?,
+        "[    ab|true  |  mid  ]"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:*^8}|{:-<4}]", "❤❤", 'x')"#)?,
+        "[***❤❤***|x---]"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:5}|{:.3}|{:?}]", 42, "abcdef", "q")"#)?,
+        r#"[   42|abc|"q"]"#
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:+} {:#x} {:#X} {:o} {:#b}", 42, 255, 255, 8, 5)"#)?,
+        "+42 0xff 0xFF 10 0b101"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:08} {:+#06x} {:x}", -42, 255, -255)"#)?,
+        "-0000042 +0x0ff -ff"
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(
+        engine.eval::<String>(
+            r#"format("{:.2} {:+.1} {:08.3} {:e} {}", 3.14159, 2.0, -1.5, 1234.5, 0.5)"#
+        )?,
+        "3.14 +2.0 -001.500 1.2345e3 0.5"
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(
+            engine.eval::<String>(r#"let x = 42; "x = {}, hex = {0:#x}".fmt(x)"#)?,
+            "x = 42, hex = 0x2a"
+        );
+        assert_eq!(
+            engine.eval::<String>(
+                r#"format("{name} is {age:>3} years old", #{ name: "Bob", age: 7 })"#
+            )?,
+            "Bob is   7 years old"
+        );
+        assert_eq!(
+            engine.eval::<String>(r#""{0} says {greeting}".fmt("Bob", #{ greeting: "hi" })"#)?,
+            "Bob says hi"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_format_errors() {
+    let engine = Engine::new();
+
+    for script in &[
+        r#"format("{", 1)"#,
+        r#"format("}", 1)"#,
+        r#"format("{} {}", 1)"#,
+        r#"format("{5}", 1)"#,
+        r#"format("{missing}", 1)"#,
+        r#"format("{:q}", 1)"#,
+        r#"format("{:.2}", 1)"#,
+        r#"format("{:x}", "abc")"#,
+        r#"format("{:+}", "abc")"#,
+        r#"format("{:.}", 1)"#,
+    ] {
+        assert!(
+            matches!(
+                *engine.eval::<String>(script).expect_err("should error"),
+                EvalAltResult::ErrorRuntime(_, _)
+            ),
+            "{}",
+            script
+        );
+    }
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_format_custom_types() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{RegisterFn, INT};
+
+    #[derive(Debug, Clone)]
+    struct Point {
+        x: INT,
+        y: INT,
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Point>("Point")
+        .register_fn("new_point", |x: INT, y: INT| Point { x, y })
+        .register_fn("to_string", |p: &mut Point| format!("({}, {})", p.x, p.y))
+        .register_fn("to_debug", |p: &mut Point| format!("{:?}", p));
+
+    assert_eq!(
+        engine.eval::<String>(r#"format("p = {:>10}!", new_point(1, 2))"#)?,
+        "p =     (1, 2)!"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:?}", new_point(1, 2))"#)?,
+        "Point { x: 1, y: 2 }"
+    );
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        // Script-defined functions override formatting
+        assert_eq!(
+            engine.eval::<String>(r#"fn format(a, b) { "custom" } format("{}", 1)"#)?,
+            "custom"
+        );
+
+        // Errors raised when converting values are not swallowed
+        assert!(matches!(
+            *engine
+                .eval::<String>(
+                    r#"fn to_string(p) { throw "oops"; } format("{}", new_point(1, 2))"#
+                )
+                .expect_err("should error"),
+            EvalAltResult::ErrorInFunctionCall(f, _, err, _)
+                if f == "to_string" && matches!(*err, EvalAltResult::ErrorRuntime(_, _))
+        ));
+
+        #[cfg(not(feature = "unchecked"))]
+        {
+            let mut engine = Engine::new();
+            engine
+                .register_type_with_name::<Point>("Point")
+                .register_fn("new_point", |x: INT, y: INT| Point { x, y })
+                .set_max_operations(100);
+
+            assert!(matches!(
+                *engine
+                    .eval::<String>(r#"fn to_string(p) { loop {} } format("{}", new_point(1, 2))"#)
+                    .expect_err("should error"),
+                EvalAltResult::ErrorTooManyOperations(_)
+            ));
+        }
+    }
+
+    // Values of types without `to_string` are formatted as their type names
+    let mut engine = Engine::new();
+    engine
+        .register_type_with_name::<Point>("Point")
+        .register_fn("new_point", |x: INT, y: INT| Point { x, y });
+
+    assert_eq!(
+        engine.eval::<String>(r#"format("{}", new_point(1, 2))"#)?,
+        "Point"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_format_limits() {
+    let mut engine = Engine::new();
+    engine.set_max_string_size(10);
+
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"format("{:20}", 1)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"format("{}{}{}", "abcd", "efgh", "ijkl")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+}
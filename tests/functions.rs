@@ -240,3 +240,152 @@ fn test_function_is_def() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_functions_overloads() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let script = r#"
+        fn describe(x: int) { "int" }
+        fn describe(x: string) { "string" }
+        fn describe(x) { "other" }
+    "#;
+
+    assert_eq!(
+        engine.eval::<String>(&format!("{} describe(42)", script))?,
+        "int"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!("{} describe(\"hello\")", script))?,
+        "string"
+    );
+    assert_eq!(
+        engine.eval::<String>(&format!("{} describe(true)", script))?,
+        "other"
+    );
+
+    // Overloads with more parameter types are preferred, then those defined first
+    let script = r#"
+        fn f(x: int, y) { 1 }
+        fn f(x, y: int) { 2 }
+        fn f(x: int, y: int) { 3 }
+        fn g(x: int, y) { 1 }
+        fn g(x, y: int) { 2 }
+    "#;
+
+    assert_eq!(engine.eval::<INT>(&format!("{} f(1, 2)", script))?, 3);
+    assert_eq!(engine.eval::<INT>(&format!("{} f(1, ())", script))?, 1);
+    assert_eq!(engine.eval::<INT>(&format!("{} f((), 1)", script))?, 2);
+    assert_eq!(engine.eval::<INT>(&format!("{} g(1, 2)", script))?, 1);
+
+    // No overload matches
+    assert!(matches!(
+        *engine
+            .eval::<INT>("fn h(x: int) { x } h(true)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(sig, _) if sig == "h (bool)"
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("fn h(x: int) { x } fn h(y: int) { y }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnDuplicatedDefinition(f, 1) if f == "h"
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("fn h(x: ) { x }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnMissingParamType(f, p) if f == "h" && p == "x"
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_functions_overloads_types() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone)]
+    struct Point {
+        x: INT,
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Point>("Point")
+        .register_fn("new_point", |x: INT| Point { x })
+        .register_fn("get_x", |p: &mut Point| p.x);
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn size(p: Point) { p.get_x() }
+                fn size(s: string) { s.len() }
+                fn size(u: ()) { 0 }
+                size(new_point(40)) + size("ab") + size(())
+            "#
+        )?,
+        42
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn grow(by: int) { this.x += by; }
+                fn grow(by: string) { this.x += by.len(); }
+                let obj = #{ x: 1 };
+                obj.grow(39);
+                obj.grow("ab");
+                obj.x
+            "#
+        )?,
+        42
+    );
+
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        engine.set_optimization_level(rhai::OptimizationLevel::Full);
+
+        assert_eq!(
+            engine.eval::<INT>("fn k(x: int) { 1 } fn k(x) { 2 } k(true) * 10 + k(0)")?,
+            21
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_functions_overloads_metadata() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let ast = engine.compile(
+        r#"
+            /// Describe an integer
+            fn describe(x: int) { "int" }
+            fn describe(x) { "other" }
+            fn pair(x, y: string) { x + y }
+        "#,
+    )?;
+
+    let mut signatures: Vec<_> = ast.iter_functions().map(|f| f.to_string()).collect();
+    signatures.sort();
+
+    assert_eq!(
+        signatures,
+        vec!["describe(x)", "describe(x: int)", "pair(x, y: string)"]
+    );
+
+    let f = ast
+        .iter_functions()
+        .find(|f| f.param_types == vec![Some("int")])
+        .unwrap();
+    assert_eq!(f.name, "describe");
+    assert_eq!(f.comments, vec!["/// Describe an integer"]);
+
+    Ok(())
+}
@@ -373,18 +373,205 @@ fn test_module_export() -> Result<(), Box<EvalAltResult>> {
 
     assert!(matches!(
         engine.compile(r"let x = 10; { export x; }").expect_err("should error"),
-        ParseError(x, _) if *x == ParseErrorType::WrongExport
+        ParseError(x, _) if *x == ParseErrorType::WrongExport("".to_string())
     ));
 
     #[cfg(not(feature = "no_function"))]
     assert!(matches!(
         engine.compile(r"fn abc(x) { export x; }").expect_err("should error"),
-        ParseError(x, _) if *x == ParseErrorType::WrongExport
+        ParseError(x, _) if *x == ParseErrorType::WrongExport("".to_string())
     ));
 
     Ok(())
 }
 
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_module_import_members() -> Result<(), Box<EvalAltResult>> {
+    use rhai::module_resolvers::SourceModuleResolver;
+
+    let mut resolver = SourceModuleResolver::new();
+    resolver.insert(
+        "util",
+        r#"
+            export const answer = 41;
+            fn inc(x) { x + 1 }
+            fn dec(x) { x - 1 }
+        "#,
+    );
+    resolver.insert(
+        "base",
+        r#"
+            export import "util" as util;
+            export { answer as base, inc } from "util";
+            let hidden = 0;
+        "#,
+    );
+    resolver.insert("nested", r#"export { util } from "base";"#);
+
+    let mut module = Module::new();
+    module.set_fn_1("double", |x: INT| Ok(x * 2));
+    let mut static_resolver = StaticModuleResolver::new();
+    static_resolver.insert("native", module);
+
+    let mut resolvers = rhai::module_resolvers::ModuleResolversCollection::new();
+    resolvers.push(resolver);
+    resolvers.push(static_resolver);
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolvers);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import { answer, inc as plus } from "util"; plus(answer)"#)?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"import { double as twice } from "native"; twice(21)"#)?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"let x = 1; let y = 2; import { answer } from "util"; answer + x - y + 2"#
+        )?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"import "base" as b; b::inc(b::base)"#)?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"import "base" as b; b::util::dec(b::util::answer + 2)"#)?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"import { util } from "nested"; util::inc(util::answer)"#)?,
+        42
+    );
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import "base" as b; b::dec(1)"#).expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f.starts_with("b::dec")
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import { inc } from "util"; answer"#).expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(v, _) if v == "answer"
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import { hidden } from "base"; 0"#).expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(v, _) if v == "hidden"
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"import { answer } from "util"; answer = 0;"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorAssignmentToConstant(v, _) if v == "answer"
+    ));
+
+    assert!(matches!(
+        engine.compile(r#"export import "util";"#).expect_err("should error"),
+        ParseError(x, _) if matches!(*x, ParseErrorType::WrongExport(ref s) if !s.is_empty())
+    ));
+    assert!(matches!(
+        engine.compile(r#"{ export { inc } from "util"; }"#).expect_err("should error"),
+        ParseError(x, _) if *x == ParseErrorType::WrongExport("".to_string())
+    ));
+    assert!(matches!(
+        engine.compile(r#"import { inc } "util";"#).expect_err("should error"),
+        ParseError(x, _) if *x == ParseErrorType::MissingToken("from".to_string(), "after the list of imported members".to_string())
+    ));
+
+    assert_eq!(
+        engine
+            .compile(r#"export import "util";"#)
+            .expect_err("should error")
+            .to_string(),
+        "A re-exported module must have an alias, i.e. 'export import ... as name' (line 1, position 8)"
+    );
+
+    // Imported variables outlive the AST
+    let mut scope = Scope::new();
+    {
+        let ast = engine.compile(r#"import { answer as the_answer } from "util";"#)?;
+        engine.eval_ast_with_scope::<()>(&mut scope, &ast)?;
+    }
+    assert_eq!(scope.get_value::<INT>("the_answer"), Some(41));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_module_vars() -> Result<(), Box<EvalAltResult>> {
+    use rhai::module_resolvers::SourceModuleResolver;
+
+    let mut resolver = SourceModuleResolver::new();
+    resolver.insert(
+        "config",
+        r#"
+            export const step = 2;
+            export let count = 0;
+            let secret = 40;
+
+            fn next() { count += step; count }
+            fn shadow(step) { step }
+            fn get_secret() { secret }
+            fn set_step() { step = 1; }
+        "#,
+    );
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "config" as c; c::shadow(42)"#)?,
+        42
+    );
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import "config" as c; c::get_secret()"#).expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(_, _, err, _)
+            if matches!(*err, EvalAltResult::ErrorVariableNotFound(ref v, _) if v == "secret")
+    ));
+    assert!(matches!(
+        *engine.eval::<()>(r#"import "config" as c; c::set_step()"#).expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(_, _, err, _)
+            if matches!(*err, EvalAltResult::ErrorAssignmentToConstant(ref v, _) if v == "step")
+    ));
+
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                import "config" as c;
+                c::next();
+                c::next();
+                let x = c::count;
+                x += 100;
+                c::next() + c::count
+            "#
+        )?,
+        12
+    );
+
+    #[cfg(feature = "no_closure")]
+    assert_eq!(
+        engine.eval::<INT>(r#"import "config" as c; c::next(); c::next() + c::count"#)?,
+        2
+    );
+
+    let mut resolver = SourceModuleResolver::new();
+    resolver.insert("private", "let secret = 42; fn get_secret() { secret }");
+
+    engine.set_module_resolver(resolver);
+    engine.enable_private_module_vars(true);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "private" as p; p::get_secret()"#)?,
+        42
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_module_str() -> Result<(), Box<EvalAltResult>> {
     fn test_fn(_input: ImmutableString) -> Result<INT, Box<EvalAltResult>> {
@@ -461,6 +648,67 @@ fn test_module_ast_namespace() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_module_ast_overloads() -> Result<(), Box<EvalAltResult>> {
+    let script = r#"
+        fn foo(x) { 1 }
+        fn bar(x) { 10 }
+        fn bar(x: string) { foo(x) * 100 }
+    "#;
+
+    let mut engine = Engine::new();
+
+    let ast = engine.compile(script)?;
+
+    let module = Module::eval_ast_as_new(Default::default(), &ast, &engine)?;
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("testing", module);
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "testing" as t; t::bar(0) + t::bar("x")"#)?,
+        110
+    );
+
+    // A private overload after a public one is not exported
+    assert!(matches!(
+        *engine
+            .compile("fn f(x) { 1 } private fn f(x: int) { 2 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnMismatchedAccess(f, 1) if f == "f"
+    ));
+
+    // A public overload after a private one is not hidden
+    assert!(matches!(
+        *engine
+            .compile("private fn f(x: int) { 2 } fn f(x) { 1 }")
+            .expect_err("should error")
+            .0,
+        ParseErrorType::FnMismatchedAccess(f, 1) if f == "f"
+    ));
+
+    // Private overloads are all hidden
+    let ast = engine.compile("private fn f(x) { 1 } private fn f(x: int) { 2 } fn g() { 0 }")?;
+    let module = Module::eval_ast_as_new(Default::default(), &ast, &engine)?;
+    assert_eq!(module.iter_script_fn_info().count(), 1);
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("testing", module);
+    engine.set_module_resolver(resolver);
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"import "testing" as t; t::f(1)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f.starts_with("t::f")
+    ));
+
+    Ok(())
+}
+
 #[cfg(not(feature = "no_function"))]
 #[test]
 fn test_module_ast_namespace2() -> Result<(), Box<EvalAltResult>> {
@@ -506,3 +754,301 @@ fn test_module_file() -> Result<(), Box<EvalAltResult>> {
     Module::eval_ast_as_new(Default::default(), &ast, &engine)?;
     Ok(())
 }
+
+#[test]
+#[cfg(not(feature = "no_std"))]
+#[cfg(not(target_arch = "wasm32"))]
+fn test_module_file_relative() -> Result<(), Box<EvalAltResult>> {
+    use rhai::module_resolvers::FileModuleResolver;
+    use std::fs;
+
+    let root = std::env::temp_dir().join(format!("rhai-test-modules-{}", std::process::id()));
+    let lib = root.join("lib");
+    let vendor = root.join("vendor");
+
+    fs::create_dir_all(lib.join("net")).unwrap();
+    fs::create_dir_all(&vendor).unwrap();
+    fs::write(
+        lib.join("net").join("http.rhai"),
+        r#"import "./util" as util; import "../common" as common; export const X = util::X + common::X;"#,
+    )
+    .unwrap();
+    fs::write(lib.join("net").join("util.rhai"), "export const X = 40;").unwrap();
+    fs::write(lib.join("common.rhai"), "export const X = 1;").unwrap();
+    fs::write(vendor.join("extra.rhai"), "export const X = 1;").unwrap();
+
+    let mut resolver = FileModuleResolver::new_with_path(&lib);
+    resolver.add_search_path(&vendor);
+    assert_eq!(resolver.search_paths(), &[vendor.clone()]);
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    let result = engine
+        .eval::<INT>(r#"import "net/http" as http; import "extra" as extra; http::X + extra::X"#);
+
+    let err = *engine
+        .eval::<INT>(r#"import "missing" as m; 0"#)
+        .expect_err("should error");
+
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(result?, 42);
+
+    match err {
+        EvalAltResult::ErrorModuleNotFound(path, attempted, _) => {
+            assert_eq!(path, "missing");
+            assert_eq!(
+                attempted,
+                vec![
+                    lib.join("missing.rhai").to_string_lossy().to_string(),
+                    vendor.join("missing.rhai").to_string_lossy().to_string(),
+                ]
+            );
+        }
+        err => panic!("wrong error: {}", err),
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_std"))]
+#[cfg(not(target_arch = "wasm32"))]
+fn test_module_file_reload() -> Result<(), Box<EvalAltResult>> {
+    use rhai::module_resolvers::{FileModuleResolver, ReloadMode};
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+
+    let root = std::env::temp_dir().join(format!("rhai-test-reload-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    fs::write(
+        root.join("a.rhai"),
+        r#"import "b" as b; export const X = b::X + 1;"#,
+    )
+    .unwrap();
+    fs::write(root.join("b.rhai"), "export const X = 1;").unwrap();
+
+    let reloaded = Arc::new(Mutex::new(Vec::new()));
+    let log = reloaded.clone();
+
+    let mut resolver = FileModuleResolver::new_with_path(&root);
+    resolver
+        .set_reload_mode(ReloadMode::ContentHash)
+        .on_reload(move |path| log.lock().unwrap().push(path.to_path_buf()));
+    assert_eq!(resolver.reload_mode(), ReloadMode::ContentHash);
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    let script = r#"import "a" as a; a::X"#;
+
+    let first = engine.eval::<INT>(script);
+    let unchanged = engine.eval::<INT>(script);
+
+    // Changing an imported module reloads all modules importing it
+    fs::write(root.join("b.rhai"), "export const X = 41;").unwrap();
+    let changed = engine.eval::<INT>(script);
+
+    let mut reloaded = reloaded.lock().unwrap().clone();
+    reloaded.sort();
+
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(first?, 2);
+    assert_eq!(unchanged?, 2);
+    assert_eq!(changed?, 42);
+    assert_eq!(reloaded, vec![root.join("a.rhai"), root.join("b.rhai")]);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_std"))]
+#[cfg(not(target_arch = "wasm32"))]
+fn test_module_file_clear_dependants() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{module_resolvers::FileModuleResolver, ModuleResolver, Position};
+    use std::fs;
+
+    let root = std::env::temp_dir().join(format!("rhai-test-dependants-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("a.rhai"), "export const X = 1;").unwrap();
+    fs::write(root.join("b.rhai"), "export const X = 1;").unwrap();
+    fs::write(root.join("c.rhai"), "export const X = 1;").unwrap();
+
+    let mut resolver = FileModuleResolver::new_with_path(&root);
+    let engine = Engine::new();
+    let a_path = root.join("a.rhai").to_string_lossy().to_string();
+
+    let result = (|| {
+        resolver.resolve(&engine, "a", Position::NONE)?;
+        // Import "b" from within "a"
+        resolver.resolve_from_source(&engine, Some(&a_path), "b", Position::NONE)?;
+        resolver.resolve(&engine, "c", Position::NONE)?;
+        Ok::<_, Box<EvalAltResult>>(())
+    })();
+
+    fs::remove_dir_all(&root).unwrap();
+    result?;
+
+    let mut removed = resolver.clear_cache_for_path_and_dependants("b");
+    removed.sort();
+
+    assert_eq!(removed, vec![root.join("a.rhai"), root.join("b.rhai")]);
+    assert!(!resolver.is_cached("a"));
+    assert!(!resolver.is_cached("b"));
+    assert!(resolver.is_cached("c"));
+
+    Ok(())
+}
+
+#[test]
+fn test_module_source_resolver() -> Result<(), Box<EvalAltResult>> {
+    use rhai::module_resolvers::SourceModuleResolver;
+    use std::collections::HashMap;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mut sources = HashMap::new();
+    sources.insert(
+        "hello",
+        r#"import "world" as w; export const x = w::x + 1;"#,
+    );
+
+    let loads = Arc::new(AtomicUsize::new(0));
+    let counter = loads.clone();
+
+    let mut engine = Engine::new();
+
+    let mut resolver = SourceModuleResolver::from(sources);
+    assert!(resolver.contains_path("hello"));
+    assert!(!resolver.is_cached("hello"));
+    engine.set_module_resolver(resolver);
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import "hello" as h; h::x"#).expect_err("should error"),
+        EvalAltResult::ErrorInModule(p, err, _) if p == "hello"
+            && matches!(*err, EvalAltResult::ErrorModuleNotFound(ref p, _, _) if p == "world")
+    ));
+
+    resolver = SourceModuleResolver::new_with_loader(move |path| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        match path {
+            "world" => Some("export const x = 41;".into()),
+            _ => None,
+        }
+    });
+    resolver.insert(
+        "hello",
+        r#"import "world" as w; export const x = w::x + 1;"#,
+    );
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(engine.eval::<INT>(r#"import "hello" as h; h::x"#)?, 42);
+    assert_eq!(engine.eval::<INT>(r#"import "world" as w; w::x"#)?, 41);
+    assert_eq!(loads.load(Ordering::Relaxed), 1);
+
+    // Modules are embedded into self-contained ASTs
+    let ast = engine.compile_into_self_contained(&Scope::new(), r#"import "hello" as h; h::x"#)?;
+    engine.set_module_resolver(DummyModuleResolver::new());
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_module_circular_import() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{module_resolvers::SourceModuleResolver, Position};
+
+    let mut resolver = SourceModuleResolver::new();
+    resolver.insert("a", r#"import "b" as b; export const x = 1;"#);
+    resolver.insert("b", "\n\nimport \"c\" as c;");
+    resolver.insert("c", r#"import "a" as a;"#);
+    resolver.insert("self", r#"import "self" as me;"#);
+    resolver.insert(
+        "top",
+        r#"import "left" as l; import "right" as r; export const x = l::x + r::x;"#,
+    );
+    resolver.insert("left", r#"import "shared" as s; export const x = s::x;"#);
+    resolver.insert("right", r#"import "shared" as s; export const x = s::x;"#);
+    resolver.insert("shared", "export const x = 21;");
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    let err = *engine
+        .eval::<INT>(r#"import "a" as a; a::x"#)
+        .expect_err("should error");
+
+    assert_eq!(
+        err.to_string(),
+        "Circular module import: 'a' (line 1, position 8) -> 'b' (line 1, position 8) -> 'c' (line 3, position 8) -> 'a' (line 1, position 8) (line 1, position 8)"
+    );
+
+    match err {
+        EvalAltResult::ErrorCircularImport(cycle, _) => assert_eq!(
+            cycle,
+            vec![
+                ("a".to_string(), Position::new(1, 8)),
+                ("b".to_string(), Position::new(1, 8)),
+                ("c".to_string(), Position::new(3, 8)),
+                ("a".to_string(), Position::new(1, 8)),
+            ]
+        ),
+        err => panic!("wrong error: {}", err),
+    }
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import "self" as me; 0"#).expect_err("should error"),
+        EvalAltResult::ErrorCircularImport(cycle, _) if cycle.len() == 2
+    ));
+
+    // Importing the same module along different branches is not circular
+    assert_eq!(engine.eval::<INT>(r#"import "top" as t; t::x"#)?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_module_circular_import_after_panic() -> Result<(), Box<EvalAltResult>> {
+    use rhai::{module_resolvers::SourceModuleResolver, Position, Shared};
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    /// Panics the first time module `boom` is resolved.
+    struct PanickingResolver(SourceModuleResolver, AtomicBool);
+
+    impl rhai::ModuleResolver for PanickingResolver {
+        fn resolve(
+            &self,
+            engine: &Engine,
+            path: &str,
+            pos: Position,
+        ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+            if path == "boom" && !self.1.swap(true, Ordering::Relaxed) {
+                panic!("resolver failure");
+            }
+            self.0.resolve(engine, path, pos)
+        }
+    }
+
+    let mut resolver = SourceModuleResolver::new();
+    resolver.insert("outer", r#"import "boom" as b; export const x = b::x;"#);
+    resolver.insert("boom", "export const x = 42;");
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(PanickingResolver(resolver, AtomicBool::new(false)));
+
+    let script = r#"import "outer" as o; o::x"#;
+
+    assert!(catch_unwind(AssertUnwindSafe(|| engine.eval::<INT>(script))).is_err());
+
+    // No stale import is left behind to cause a false circular import
+    assert_eq!(engine.eval::<INT>(script)?, 42);
+
+    Ok(())
+}
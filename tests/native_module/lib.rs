@@ -0,0 +1,22 @@
+//! Native module library loaded by `tests/native_modules.rs`.
+
+use rhai::{Module, INT};
+
+fn create_module() -> Module {
+    let mut module = Module::new();
+    module.set_fn_1("double", |x: INT| Ok(x * 2));
+    module.set_var("answer", 42 as INT);
+    module
+}
+
+#[cfg(not(feature = "incompatible"))]
+rhai::export_native_module!(create_module);
+
+#[cfg(feature = "incompatible")]
+#[no_mangle]
+pub static RHAI_NATIVE_MODULE: rhai::module_resolvers::NativeModuleDeclaration =
+    rhai::module_resolvers::NativeModuleDeclaration {
+        rhai_version: "0.0.0",
+        build_flags: rhai::module_resolvers::NATIVE_MODULE_BUILD_FLAGS,
+        create: create_module,
+    };
@@ -0,0 +1,187 @@
+#![cfg(feature = "dylib")]
+#![cfg(not(feature = "no_module"))]
+use rhai::module_resolvers::{NativeModuleDeclaration, NativeModuleResolver};
+use rhai::{Engine, EvalAltResult, Module, INT};
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn create_module() -> Module {
+    let mut module = Module::new();
+    module.set_fn_1("double", |x: INT| Ok(x * 2));
+    module
+}
+
+rhai::export_native_module!(create_module);
+
+#[test]
+fn test_native_module_declaration() -> Result<(), Box<EvalAltResult>> {
+    assert!(RHAI_NATIVE_MODULE.is_compatible());
+
+    let decl = NativeModuleDeclaration {
+        rhai_version: "0.0.0",
+        ..RHAI_NATIVE_MODULE
+    };
+    assert!(!decl.is_compatible());
+
+    let decl = NativeModuleDeclaration {
+        build_flags: !RHAI_NATIVE_MODULE.build_flags,
+        ..RHAI_NATIVE_MODULE
+    };
+    assert!(!decl.is_compatible());
+
+    let mut engine = Engine::new();
+    engine.register_static_module("foo", (RHAI_NATIVE_MODULE.create)().into());
+
+    assert_eq!(engine.eval::<INT>("foo::double(21)")?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_native_module_resolver() -> Result<(), Box<EvalAltResult>> {
+    let dir = std::env::temp_dir().join("rhai_test_native_module_resolver");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let file_name = format!(
+        "{}garbage{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    std::fs::write(dir.join(&file_name), "not a library").unwrap();
+
+    let mut resolver = NativeModuleResolver::new_with_path(&dir);
+    resolver.add_search_path(dir.join("more"));
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"import "foo" as foo; 42"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorModuleNotFound(p, paths, _) if p == "foo" && paths.is_empty()
+    ));
+
+    match *engine
+        .eval::<INT>(r#"import "native:foo" as foo; 42"#)
+        .expect_err("should error")
+    {
+        EvalAltResult::ErrorModuleNotFound(p, paths, _) => {
+            assert_eq!(p, "native:foo");
+            assert_eq!(paths.len(), 2);
+            assert!(paths[1].contains("more"));
+        }
+        err => panic!("wrong error: {}", err),
+    }
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"import "native:garbage" as foo; 42"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorInModule(p, err, _)
+            if p == "native:garbage" && matches!(*err, EvalAltResult::ErrorSystem(_, _))
+    ));
+
+    Ok(())
+}
+
+/// Build the native module library in `tests/native_module` with the same Rhai features as this
+/// test, and copy it into `dir` as the library `name`.
+fn build_native_module(dir: &Path, name: &str, incompatible: bool) -> PathBuf {
+    let mut features: Vec<_> = [
+        (cfg!(feature = "sync"), "sync"),
+        (cfg!(feature = "only_i32"), "only_i32"),
+        (cfg!(feature = "only_i64"), "only_i64"),
+        (cfg!(feature = "no_float"), "no_float"),
+        (cfg!(feature = "f32_float"), "f32_float"),
+        (cfg!(feature = "no_index"), "no_index"),
+        (cfg!(feature = "no_object"), "no_object"),
+        (cfg!(feature = "no_function"), "no_function"),
+        (cfg!(feature = "no_closure"), "no_closure"),
+        (cfg!(feature = "no_optimize"), "no_optimize"),
+        (cfg!(feature = "unchecked"), "unchecked"),
+        (cfg!(feature = "internals"), "internals"),
+        (cfg!(feature = "metadata"), "metadata"),
+        (cfg!(feature = "bytecode"), "bytecode"),
+    ]
+    .iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, feature)| format!("rhai/{}", feature))
+    .collect();
+
+    if incompatible {
+        features.push("incompatible".into());
+    }
+
+    let root_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let manifest_dir = root_dir.join("tests/native_module");
+    let target_dir = dir.join("target");
+
+    // The library must use the same versions of all dependencies as the host
+    if root_dir.join("Cargo.lock").exists() {
+        std::fs::copy(root_dir.join("Cargo.lock"), manifest_dir.join("Cargo.lock")).unwrap();
+    }
+
+    let status = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .arg("--features")
+        .arg(features.join(","))
+        .status()
+        .expect("cannot run cargo");
+
+    assert!(status.success(), "cannot build native module library");
+
+    let library = |name: &str| format!("{}{}{}", DLL_PREFIX, name, DLL_SUFFIX);
+    let file_path = dir.join(library(name));
+
+    std::fs::copy(
+        target_dir
+            .join("debug")
+            .join(library("rhai_test_native_module")),
+        &file_path,
+    )
+    .unwrap();
+
+    file_path
+}
+
+#[test]
+fn test_native_module_library() -> Result<(), Box<EvalAltResult>> {
+    let dir = std::env::temp_dir().join("rhai_test_native_module_library");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let file_path = build_native_module(&dir, "double", false);
+    build_native_module(&dir, "incompatible", true);
+
+    let module = NativeModuleResolver::load_module(&file_path)?;
+    assert!(module.contains_var("answer"));
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(NativeModuleResolver::new_with_path(&dir));
+
+    assert_eq!(
+        engine.eval::<INT>(r#"import "native:double" as m; m::double(m::answer / 2)"#)?,
+        42
+    );
+
+    match *engine
+        .eval::<INT>(r#"import "native:incompatible" as m; 42"#)
+        .expect_err("should error")
+    {
+        EvalAltResult::ErrorInModule(p, err, _) if p == "native:incompatible" => match *err {
+            EvalAltResult::ErrorSystem(msg, err) => {
+                assert!(msg.starts_with("Incompatible native module library"));
+                assert!(err.to_string().contains("built against Rhai 0.0.0"));
+            }
+            err => panic!("wrong error: {}", err),
+        },
+        err => panic!("wrong error: {}", err),
+    }
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, Module, RegisterFn, INT};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
 #[test]
 fn test_ops() -> Result<(), Box<EvalAltResult>> {
@@ -21,3 +23,260 @@ fn test_op_precedence() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash)]
+struct Vec2(INT, INT);
+
+impl fmt::Display for Vec2 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}, {}>", self.0, self.1)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+    fn add(self, y: Self) -> Self {
+        Vec2(self.0 + y.0, self.1 + y.1)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+    fn sub(self, y: Self) -> Self {
+        Vec2(self.0 - y.0, self.1 - y.1)
+    }
+}
+
+impl Mul for Vec2 {
+    type Output = Self;
+    fn mul(self, y: Self) -> Self {
+        Vec2(self.0 * y.0, self.1 * y.1)
+    }
+}
+
+impl Div for Vec2 {
+    type Output = Self;
+    fn div(self, y: Self) -> Self {
+        Vec2(self.0 / y.0, self.1 / y.1)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Vec2(-self.0, -self.1)
+    }
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_ops_from_traits() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Vec2>("Vec2")
+        .register_fn("vec2", Vec2)
+        .register_display::<Vec2>()
+        .register_debug::<Vec2>()
+        .register_comparison::<Vec2>()
+        .register_arithmetic::<Vec2>()
+        .register_hash::<Vec2>();
+
+    assert_eq!(engine.eval::<Vec2>("vec2(1, 2) + vec2(3, 4)")?, Vec2(4, 6));
+    assert_eq!(
+        engine.eval::<Vec2>("vec2(1, 2) - vec2(3, 4)")?,
+        Vec2(-2, -2)
+    );
+    assert_eq!(engine.eval::<Vec2>("vec2(1, 2) * vec2(3, 4)")?, Vec2(3, 8));
+    assert_eq!(engine.eval::<Vec2>("vec2(9, 8) / vec2(3, 4)")?, Vec2(3, 2));
+    assert_eq!(engine.eval::<Vec2>("-vec2(1, 2)")?, Vec2(-1, -2));
+    assert_eq!(
+        engine.eval::<Vec2>("let x = vec2(1, 2); x *= vec2(2, 2); x")?,
+        Vec2(2, 4)
+    );
+
+    assert!(engine.eval::<bool>("vec2(1, 2) == vec2(1, 2)")?);
+    assert!(engine.eval::<bool>("vec2(1, 2) != vec2(2, 1)")?);
+    assert!(engine.eval::<bool>("vec2(1, 2) < vec2(1, 3)")?);
+    assert!(engine.eval::<bool>("vec2(1, 2) <= vec2(1, 2)")?);
+    assert!(engine.eval::<bool>("vec2(2, 0) > vec2(1, 9)")?);
+    assert!(!engine.eval::<bool>("vec2(1, 2) >= vec2(1, 3)")?);
+
+    assert_eq!(engine.eval::<String>("vec2(1, 2).to_string()")?, "<1, 2>");
+    assert_eq!(engine.eval::<String>("to_debug(vec2(1, 2))")?, "Vec2(1, 2)");
+    assert!(engine.eval::<bool>("hash(vec2(1, 2)) == hash(vec2(1, 2))")?);
+    assert!(engine.eval::<bool>("hash(vec2(1, 2)) != hash(vec2(2, 1))")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_ops_from_traits_module() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let mut module = Module::new();
+    module
+        .set_display_fns::<Vec2>()
+        .set_comparison_fns::<Vec2>()
+        .set_arithmetic_fns::<Vec2>();
+
+    engine
+        .register_fn("vec2", Vec2)
+        .register_global_module(module.into());
+
+    assert_eq!(engine.eval::<Vec2>("vec2(1, 2) + vec2(3, 4)")?, Vec2(4, 6));
+    assert!(engine.eval::<bool>("vec2(1, 2) < vec2(1, 3)")?);
+    assert_eq!(engine.eval::<String>("to_string(vec2(1, 2))")?, "<1, 2>");
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Meters(INT);
+
+impl Add for Meters {
+    type Output = Self;
+    fn add(self, y: Self) -> Self {
+        Meters(self.0 + y.0)
+    }
+}
+
+#[test]
+fn test_ops_from_single_trait() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let mut module = Module::new();
+    module.set_add_fn::<Meters>();
+
+    engine
+        .register_fn("meters", Meters)
+        .register_global_module(module.into());
+
+    assert_eq!(
+        engine.eval::<Meters>("let x = meters(40); x += meters(2); x")?,
+        Meters(42)
+    );
+    assert!(matches!(
+        *engine
+            .eval::<Meters>("meters(40) - meters(2)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    // Equality does not need an ordering
+    engine.register_equality::<Meters>();
+
+    assert!(engine.eval::<bool>("meters(42) == meters(40) + meters(2)")?);
+    assert!(engine.eval::<bool>("meters(42) != meters(40)")?);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+fn test_ops_script_overload() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let script = r#"
+        fn vec(x, y) { #{ x: x, y: y } }
+        fn +(a, b) { vec(a.x + b.x, a.y + b.y) }
+        fn -(a, b) { vec(a.x - b.x, a.y - b.y) }
+        fn -(a) { vec(-a.x, -a.y) }
+        fn *(a, k) { vec(a.x * k, a.y * k) }
+        fn ==(a, b) { a.x == b.x && a.y == b.y }
+        fn !=(a, b) { !(a == b) }
+    "#;
+
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let v = vec(1, 2) + vec(3, 4) * 2; v.x * 100 + v.y",
+            script
+        ))?,
+        710
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let v = -vec(1, 2) - vec(1, 1); v.x + v.y",
+            script
+        ))?,
+        -5
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!(
+            "{} let v = vec(1, 2); v += vec(3, 4); v.y",
+            script
+        ))?,
+        6
+    );
+    assert!(engine.eval::<bool>(&format!("{} vec(1, 2) + vec(1, 1) == vec(2, 3)", script))?);
+    assert!(engine.eval::<bool>(&format!("{} vec(1, 2) != vec(2, 1)", script))?);
+
+    // Built-in operators are not overridden
+    assert_eq!(engine.eval::<INT>(&format!("{} 40 + 2", script))?, 42);
+    assert_eq!(
+        engine.eval::<INT>(&format!("{} let x = 40; x -= 2; let y = -x; y", script))?,
+        -38
+    );
+
+    // Script-defined operators are not inlined
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        engine.set_optimization_level(rhai::OptimizationLevel::Full);
+        assert_eq!(
+            engine.eval::<INT>("fn -(a) { a } let x = 40; let y = -x; y")?,
+            -40
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+fn test_ops_script_overload_native() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine
+        .register_type::<Vec2>()
+        .register_fn("vec2", |x: INT, y: INT| Vec2(x, y))
+        .register_fn("+", |a: Vec2, b: Vec2| a + b)
+        .register_fn("sum", |a: &mut Vec2| a.0 + a.1);
+
+    // Script-defined operators override registered functions, like other script functions
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                fn +(a, b) { vec2(a.sum() * b.sum(), 0) }
+                let v = vec2(1, 2) + vec2(3, 4);
+                v.sum() + 1 + 2
+            "
+        )?,
+        24
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_ops_script_custom_operator() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_custom_operator("#", 160).unwrap();
+    engine.register_custom_operator("max", 160).unwrap();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                fn #(x, y) { (x * y) - (x + y) }
+                fn max(x, y) { if x > y { x } else { y } }
+                1 + 2 * 3 # 4 - 5 max 6
+            "
+        )?,
+        9
+    );
+
+    Ok(())
+}
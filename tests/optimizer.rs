@@ -1,6 +1,10 @@
 #![cfg(not(feature = "no_optimize"))]
 
 use rhai::{Engine, EvalAltResult, OptimizationLevel, RegisterFn, INT};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 #[test]
 fn test_optimizer_run() -> Result<(), Box<EvalAltResult>> {
@@ -72,3 +76,109 @@ fn test_optimizer_parse() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+/// Is the [`AST`] a single integer constant statement?
+#[cfg(feature = "internals")]
+#[allow(deprecated)]
+fn is_int_constant(ast: &rhai::AST, value: INT) -> bool {
+    use rhai::{Expr, Stmt};
+
+    matches!(ast.statements(), [Stmt::Expr(Expr::IntegerConstant(x, _))] if *x == value)
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_optimizer_inline() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    // Inlined calls still work after the function definitions are removed
+    let mut ast = engine.compile("fn sq(x) { x * x } sq(3)")?;
+    #[cfg(feature = "internals")]
+    assert!(is_int_constant(&ast, 9));
+    ast.clear_functions();
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 9);
+
+    let mut ast = engine.compile("fn sq(x) { x * x } fn quad(x) { sq(x) * sq(x) } quad(2)")?;
+    #[cfg(feature = "internals")]
+    assert!(is_int_constant(&ast, 16));
+    ast.clear_functions();
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 16);
+
+    assert_eq!(
+        engine.eval::<INT>("fn sq(x) { x * x } let x = 5; sq(x) + sq(x + 1)")?,
+        61
+    );
+    assert_eq!(
+        engine.eval::<INT>("fn add(x, y) { x + y * 10 } let a = 1; add(a, 2) + add(a + 1, 3)")?,
+        53
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "fn sum(x, y) { x + y } let a = 1; let b = 2; sum(a + b, a * b) + sum(a, b)"
+        )?,
+        8
+    );
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<INT>("fn first(a) { a[0] } let arr = [4, 5]; first(arr) + first([1])")?,
+        5
+    );
+
+    // Recursive functions are not inlined
+    let mut ast = engine.compile("fn f(x) { if x > 0 { f(x - 1) } else { 0 } } f(3)")?;
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 0);
+    ast.clear_functions();
+    assert!(engine.eval_ast::<INT>(&ast).is_err());
+
+    // Functions using `this` are not inlined
+    let mut ast = engine.compile("fn f() { this } f()")?;
+    ast.clear_functions();
+    assert!(engine.eval_ast::<()>(&ast).is_err());
+
+    // Inlining can be disabled
+    engine.set_max_inline_fn_size(0);
+    assert_eq!(engine.max_inline_fn_size(), 0);
+
+    let mut ast = engine.compile("fn sq(x) { x * x } sq(3)")?;
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 9);
+    ast.clear_functions();
+    assert!(engine.eval_ast::<INT>(&ast).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_optimizer_const_fn() -> Result<(), Box<EvalAltResult>> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    let counter = calls.clone();
+    engine.register_fn("host_value", move |x: INT| {
+        x + counter.fetch_add(1, Ordering::Relaxed) as INT + 1
+    });
+    engine.register_const_fn("double", |x: INT| x * 2);
+
+    // An engine without any functions can only run calls evaluated at compile time
+    let raw_engine = Engine::new_raw();
+
+    // Functions not marked `const` are never called at compile time
+    let ast = engine.compile("host_value(40)")?;
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    assert!(raw_engine.eval_ast::<INT>(&ast).is_err());
+    assert_eq!(engine.eval_ast::<INT>(&ast)?, 41);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    // `const` functions are evaluated at compile time
+    let ast = engine.compile("double(21)")?;
+    assert_eq!(raw_engine.eval_ast::<INT>(&ast)?, 42);
+
+    #[cfg(not(feature = "no_std"))]
+    assert!(raw_engine
+        .eval_ast::<std::time::Instant>(&engine.compile("timestamp()")?)
+        .is_err());
+
+    Ok(())
+}
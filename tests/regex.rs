@@ -0,0 +1,195 @@
+#![cfg(feature = "regex")]
+#![cfg(not(feature = "no_object"))]
+
+use rhai::{Engine, EvalAltResult};
+
+#[test]
+fn test_regex() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>(
+        r#"let re = regex("^[a-z]+@[a-z]+\\.com$"); re.is_match("joe@example.com")"#
+    )?);
+    assert!(!engine.eval::<bool>(r#"regex("^\\d+$").is_match("12a")"#)?);
+    assert_eq!(engine.eval::<String>(r#"type_of(regex("a+"))"#)?, "Regex");
+    assert_eq!(
+        engine.eval::<String>(r#"let re = regex("a+b"); re.pattern + "|" + re.to_string()"#)?,
+        "a+b|a+b"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"regex("\\d+").find("abc 123 def 45")"#)?,
+        "123"
+    );
+    assert!(engine.eval::<()>(r#"regex("\\d+").find("abc")"#).is_ok());
+    assert_eq!(
+        engine.eval::<String>(r#"regex("(\\w+)@(\\w+)").replace("a@b c@d", "$2 at $1")"#)?,
+        "b at a c@d"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"regex("(?P<user>\\w+)@(?P<host>\\w+)").replace_all("a@b c@d", "${host}/${user}")"#
+        )?,
+        "b/a d/c"
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"regex("(unclosed")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_regex_arrays() -> Result<(), Box<EvalAltResult>> {
+    use rhai::INT;
+
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(r#"let r = regex("\\d+").find_all("1 22 333 x"); r.len()"#)?,
+        3
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let r = regex("\\d+").find_all("1 22 333 x"); r[2]"#)?,
+        "333"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"let c = regex("(\\d+)-(\\d+)(x)?").captures("from 10-20 on"); c[0] + "|" + c[1] + "|" + c[2] + "|" + type_of(c[3])"#
+        )?,
+        "10-20|10|20|()"
+    );
+    assert!(engine
+        .eval::<()>(r#"regex("(\\d+)").captures("none")"#)
+        .is_ok());
+    assert_eq!(
+        engine.eval::<String>(
+            r#"let all = regex("(\\w)=(\\d)").captures_all("a=1, b=2, c=3"); all[1][1] + all.len()"#
+        )?,
+        "b3"
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"regex("\\s*,\\s*").split("a , b,c ,  d").len()"#)?,
+        4
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = regex(",").split("a,b,c", 2); s[1]"#)?,
+        "b,c"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_regex_fn_ptr() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                fn double(c) { parse_int(c[0]) * 2 }
+
+                let re = regex("\\d+");
+                re.replace_all("1 plus 20 is 21", Fn("double")) + " / " +
+                re.replace("1 plus 20", |c| "<" + c[0] + ">")
+            "#
+        )?,
+        "2 plus 40 is 42 / <1> plus 20"
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"regex("\\d").replace_all("1", |c| throw "oops")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorInFunctionCall(f, _, _, _) if f == "replace_all"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_regex_maps() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let m = regex("(?P<key>\\w+)=(?P<value>\\w+)?").named_captures("name=");
+                m.key + "|" + type_of(m.value)
+            "#
+        )?,
+        "name|()"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_regex_limits() {
+    let mut engine = Engine::new();
+
+    engine.set_max_regex_size(10_000);
+
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"regex("\\w{10000}")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+    assert!(engine
+        .eval::<bool>(r#"regex("[a-z]{3}").is_match("abc")"#)
+        .unwrap());
+
+    engine.set_max_string_size(10);
+
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"regex("a").replace_all("aaaaa", "xyz")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        engine.set_max_array_size(3);
+
+        assert!(matches!(
+            *engine
+                .eval::<rhai::Array>(r#"regex(",").split("a,b,c,d")"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorDataTooLarge(_, _)
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<rhai::Array>(r#"regex(",").split("a,b,c,d", 10)"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorDataTooLarge(_, _)
+        ));
+        assert_eq!(
+            engine
+                .eval::<rhai::Array>(r#"regex(",").split("a,b,c,d", 3)"#)
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(matches!(
+            *engine
+                .eval::<rhai::Array>(r#"regex("\\d").find_all("123456789")"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorDataTooLarge(_, _)
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<rhai::Array>(r#"regex("(\\d)").captures_all("123456789")"#)
+                .expect_err("should error"),
+            EvalAltResult::ErrorDataTooLarge(_, _)
+        ));
+    }
+}
@@ -223,3 +223,113 @@ fn test_string_split() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_unicode() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#""Grüße, Ὀδυσσεύς!".to_upper()"#)?,
+        "GRÜSSE, ὈΔΥΣΣΕΎΣ!"
+    );
+    assert_eq!(engine.eval::<String>(r#""HeLLo".to_lower()"#)?, "hello");
+    assert!(engine.eval::<bool>(r#""hello".starts_with("he") && "hello".ends_with('o')"#)?);
+    assert!(engine.eval::<bool>(r#""ÄPFEL".eq_ignore_case("äpfel")"#)?);
+    assert_eq!(
+        engine.eval::<INT>(r#""apple".compare_ignore_case("BANANA")"#)?,
+        -1
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "  hello  "; x.trim_start(); x + "|""#)?,
+        "hello  |"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "  hello  "; x.trim_end(); "|" + x"#)?,
+        "|  hello"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "a❤b"; x.reverse(); x"#)?,
+        "b❤a"
+    );
+    assert_eq!(engine.eval::<String>(r#""❤-".repeat(3)"#)?, "❤-❤-❤-");
+    assert_eq!(engine.eval::<String>(r#""abc".repeat(0)"#)?, "");
+    assert_eq!(
+        engine.eval::<String>(
+            r#"let r = ""; for ch in "❤hello".chars(1, 3) { r += ch.to_upper(); } r"#
+        )?,
+        "HEL"
+    );
+
+    assert!(engine.eval::<bool>("'x'.is_alphabetic() && '7'.is_digit()")?);
+    assert!(engine.eval::<bool>("'f'.is_digit(16) && !'g'.is_digit(16)")?);
+    assert!(engine.eval::<bool>("' '.is_whitespace() && 'A'.is_uppercase()")?);
+    assert_eq!(engine.eval::<INT>("'f'.to_int(16)")?, 15);
+    assert_eq!(engine.eval::<INT>("'A'.to_int()")?, 65);
+    assert_eq!(engine.eval::<char>("to_char(0x2764)")?, '❤');
+    assert!(engine.eval::<char>("to_char(0xd800)").is_err());
+    assert!(engine.eval::<INT>("'z'.to_int(10)").is_err());
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_string_unicode_arrays() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "a,b,c,d"; let s = x.split(",", 2); s[1]"#)?,
+        "b,c,d"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "a,b,c,d"; let s = x.split_rev(',', 2); s[1]"#)?,
+        "a,b,c"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "a,b,c"; let s = x.split_rev(","); s[0] + s[2]"#)?,
+        "ca"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "❤❤hello".split(2); s[0] + "|" + s[1]"#)?,
+        "❤❤|hello"
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#""line 1\nline 2\r\nline 3".lines().len"#)?,
+        3
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let c = "h❤i".to_chars(); c.reverse(); from_chars(c)"#)?,
+        "i❤h"
+    );
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"from_chars(['a', 42])"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_unicode_limits() {
+    let mut engine = Engine::new();
+    engine.set_max_string_size(10);
+
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#""hello".repeat(3)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#""ŉŉŉŉŉ".to_upper()"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorDataTooLarge(_, _)
+    ));
+}
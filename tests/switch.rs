@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, Scope, INT};
+use rhai::{Engine, EvalAltResult, RegisterFn, Scope, INT};
 
 #[test]
 fn test_switch() -> Result<(), Box<EvalAltResult>> {
@@ -63,6 +63,53 @@ fn test_switch() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_switch_enum_type() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone)]
+    enum Color {
+        Red,
+        Blue,
+    }
+
+    let mut engine = Engine::new();
+
+    engine.register_fn("enum_type", |c: &mut Color| match c {
+        Color::Red => "Red",
+        Color::Blue => "Blue",
+    });
+
+    let mut scope = Scope::new();
+    scope.push("a", Color::Red);
+    scope.push("b", Color::Blue);
+
+    let script = r#"switch x { "Red" => 1, "Blue" => 2, _ => 9 }"#;
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, &script.replace('x', "a"))?,
+        1
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, &script.replace('x', "b"))?,
+        2
+    );
+
+    #[cfg(feature = "bytecode")]
+    {
+        engine.set_execution_backend(rhai::ExecutionBackend::Bytecode);
+
+        assert_eq!(
+            engine.eval_with_scope::<INT>(&mut scope, &script.replace('x', "a"))?,
+            1
+        );
+        assert_eq!(
+            engine.eval_with_scope::<INT>(&mut scope, &script.replace('x', "b"))?,
+            2
+        );
+    }
+
+    Ok(())
+}
+
 #[cfg(not(feature = "no_index"))]
 #[cfg(not(feature = "no_object"))]
 mod test_switch_enum {